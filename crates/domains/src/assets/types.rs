@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use fuel_core_types::fuel_crypto::Hasher;
+use fuel_data_parser::DataEncoder;
+use fuel_streams_types::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    receipts::Receipt,
+    transactions::Transaction,
+};
+
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    derive_more::Display,
+    derive_more::IsVariant,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetEventType {
+    #[default]
+    #[display("mint")]
+    Mint,
+    #[display("burn")]
+    Burn,
+}
+
+impl TryFrom<&str> for AssetEventType {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.to_lowercase().as_str() {
+            "mint" => Ok(AssetEventType::Mint),
+            "burn" => Ok(AssetEventType::Burn),
+            _ => Err(format!("Invalid asset event type: {}", s)),
+        }
+    }
+}
+
+impl_enum_string_serialization!(AssetEventType, "asset_event_type");
+
+/// A native asset minted or burned by a contract, from a `Mint` or `Burn`
+/// receipt
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AssetEvent {
+    pub r#type: AssetEventType,
+    pub asset_id: AssetId,
+    /// Contract minting or burning the asset
+    pub contract_id: ContractId,
+    pub sub_id: Bytes32,
+    pub amount: Word,
+    pub tx_id: TxId,
+    pub tx_index: i32,
+    /// Position of the receipt in its transaction
+    pub receipt_index: i32,
+}
+
+impl DataEncoder for AssetEvent {}
+
+impl AssetEvent {
+    /// Mints and burns of `tx`, the `tx_index`-th transaction of its block.
+    /// A failed transaction reverts them, so it has none.
+    pub fn from_transaction(tx_index: i32, tx: &Transaction) -> Vec<Self> {
+        if tx.status == TransactionStatus::Failed {
+            return vec![];
+        }
+
+        tx.receipts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, receipt)| {
+                let (r#type, contract_id, sub_id, amount) = match receipt {
+                    Receipt::Mint(mint) => (
+                        AssetEventType::Mint,
+                        &mint.contract_id,
+                        &mint.sub_id,
+                        mint.val,
+                    ),
+                    Receipt::Burn(burn) => (
+                        AssetEventType::Burn,
+                        &burn.contract_id,
+                        &burn.sub_id,
+                        burn.val,
+                    ),
+                    _ => return None,
+                };
+                Some(Self {
+                    r#type,
+                    asset_id: asset_id(contract_id, sub_id),
+                    contract_id: contract_id.clone(),
+                    sub_id: sub_id.clone(),
+                    amount,
+                    tx_id: tx.id.clone(),
+                    tx_index,
+                    receipt_index: index as i32,
+                })
+            })
+            .collect()
+    }
+
+    /// Change of the asset supply, negative for burns
+    pub fn signed_amount(&self) -> i128 {
+        match self.r#type {
+            AssetEventType::Mint => self.amount.0 as i128,
+            AssetEventType::Burn => -(self.amount.0 as i128),
+        }
+    }
+}
+
+/// Id of the asset minted by `contract_id` under `sub_id`: the SHA-256 of the
+/// contract id followed by the sub id.
+pub fn asset_id(contract_id: &ContractId, sub_id: &Bytes32) -> AssetId {
+    let hash = Hasher::default()
+        .chain(contract_id.0)
+        .chain(sub_id.0)
+        .finalize();
+    AssetId::from(*hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_core_types::fuel_tx::{
+        ContractIdExt,
+        SubAssetId,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        receipts::MockReceipt,
+        transactions::MockTransaction,
+    };
+
+    #[test]
+    fn test_asset_id() {
+        let contract_id = ContractId::random();
+        let sub_id = Bytes32::random();
+        let expected = contract_id.0.asset_id(&SubAssetId::from(*sub_id.0));
+        assert_eq!(asset_id(&contract_id, &sub_id), AssetId::from(expected));
+    }
+
+    #[test]
+    fn test_asset_events_from_transaction() {
+        let receipts = vec![
+            MockReceipt::call(),
+            MockReceipt::mint(),
+            MockReceipt::burn(),
+        ];
+        let mut tx = MockTransaction::script(vec![], vec![], receipts.clone());
+        let events = AssetEvent::from_transaction(2, &tx);
+
+        assert_eq!(events.len(), 2);
+        let Receipt::Mint(mint) = &receipts[1] else {
+            unreachable!()
+        };
+        assert_eq!(events[0].r#type, AssetEventType::Mint);
+        assert_eq!(
+            events[0].asset_id,
+            asset_id(&mint.contract_id, &mint.sub_id)
+        );
+        assert_eq!(events[0].contract_id, mint.contract_id);
+        assert_eq!(events[0].receipt_index, 1);
+        assert_eq!(events[0].tx_index, 2);
+        assert_eq!(events[0].signed_amount(), 100);
+        assert_eq!(events[1].r#type, AssetEventType::Burn);
+        assert_eq!(events[1].receipt_index, 2);
+        assert_eq!(events[1].signed_amount(), -100);
+
+        tx.status = TransactionStatus::Failed;
+        assert!(AssetEvent::from_transaction(2, &tx).is_empty());
+    }
+}
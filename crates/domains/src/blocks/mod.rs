@@ -1,2 +1,5 @@
+pub mod stats;
 pub mod types;
+
+pub use stats::*;
 pub use types::*;
@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use fuel_streams_types::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    inputs::input_owners,
+    transactions::Transaction,
+};
+
+/// Number of transactions of each type
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema,
+)]
+pub struct TransactionTypeCounts {
+    pub create: u64,
+    pub mint: u64,
+    pub script: u64,
+    pub upgrade: u64,
+    pub upload: u64,
+    pub blob: u64,
+}
+
+impl TransactionTypeCounts {
+    fn add(&mut self, tx_type: TransactionType) {
+        let count = match tx_type {
+            TransactionType::Create => &mut self.create,
+            TransactionType::Mint => &mut self.mint,
+            TransactionType::Script => &mut self.script,
+            TransactionType::Upgrade => &mut self.upgrade,
+            TransactionType::Upload => &mut self.upload,
+            TransactionType::Blob => &mut self.blob,
+        };
+        *count += 1;
+    }
+}
+
+/// Number of receipts of each type
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema,
+)]
+pub struct ReceiptTypeCounts {
+    pub call: u64,
+    pub r#return: u64,
+    pub return_data: u64,
+    pub panic: u64,
+    pub revert: u64,
+    pub log: u64,
+    pub log_data: u64,
+    pub transfer: u64,
+    pub transfer_out: u64,
+    pub script_result: u64,
+    pub message_out: u64,
+    pub mint: u64,
+    pub burn: u64,
+}
+
+impl ReceiptTypeCounts {
+    fn add(&mut self, receipt_type: ReceiptType) {
+        let count = match receipt_type {
+            ReceiptType::Call => &mut self.call,
+            ReceiptType::Return => &mut self.r#return,
+            ReceiptType::ReturnData => &mut self.return_data,
+            ReceiptType::Panic => &mut self.panic,
+            ReceiptType::Revert => &mut self.revert,
+            ReceiptType::Log => &mut self.log,
+            ReceiptType::LogData => &mut self.log_data,
+            ReceiptType::Transfer => &mut self.transfer,
+            ReceiptType::TransferOut => &mut self.transfer_out,
+            ReceiptType::ScriptResult => &mut self.script_result,
+            ReceiptType::MessageOut => &mut self.message_out,
+            ReceiptType::Mint => &mut self.mint,
+            ReceiptType::Burn => &mut self.burn,
+        };
+        *count += 1;
+    }
+}
+
+/// Aggregates of the transactions and receipts of one block
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema,
+)]
+pub struct BlockStats {
+    pub transactions: TransactionTypeCounts,
+    pub successful_transactions: u64,
+    pub failed_transactions: u64,
+    /// Sum of the gas consumed by the transactions, predicates and intrinsic
+    /// costs included
+    pub total_gas: u64,
+    /// Sum of the gas used by the scripts, from their `ScriptResult` receipts
+    pub gas_used: u64,
+    pub total_fee: u64,
+    pub total_tip: u64,
+    /// Distinct owners of the coin and message inputs
+    pub unique_senders: u64,
+    pub receipts: ReceiptTypeCounts,
+}
+
+impl BlockStats {
+    /// Aggregates the transactions of a block. Sums saturate at `u64::MAX`.
+    pub fn from_transactions(transactions: &[Transaction]) -> Self {
+        let mut stats = Self::default();
+        let mut senders = HashSet::new();
+        for tx in transactions {
+            stats.transactions.add(tx.r#type);
+            match tx.status {
+                TransactionStatus::Success
+                | TransactionStatus::PreConfirmationSuccess => {
+                    stats.successful_transactions += 1
+                }
+                TransactionStatus::Failed | TransactionStatus::PreConfirmationFailed => {
+                    stats.failed_transactions += 1
+                }
+                TransactionStatus::Submitted | TransactionStatus::SqueezedOut => {}
+            }
+            let gas = tx.total_gas.as_ref().map(|gas| gas.0).unwrap_or(0);
+            stats.total_gas = stats.total_gas.saturating_add(gas);
+            let gas_used = tx.gas_used.as_ref().map(|gas| gas.0).unwrap_or(0);
+            stats.gas_used = stats.gas_used.saturating_add(gas_used);
+            let fee = tx.total_fee.as_ref().map(|fee| fee.0).unwrap_or(0);
+            stats.total_fee = stats.total_fee.saturating_add(fee);
+            let tip = tx.tip.as_ref().map(|tip| tip.0).unwrap_or(0);
+            stats.total_tip = stats.total_tip.saturating_add(tip);
+            senders.extend(input_owners(&tx.inputs));
+            for receipt in &tx.receipts {
+                stats.receipts.add(receipt.receipt_type());
+            }
+        }
+        stats.unique_senders = senders.len() as u64;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        inputs::MockInput,
+        receipts::MockReceipt,
+        transactions::MockTransaction,
+    };
+
+    #[test]
+    fn test_block_stats_from_transactions() {
+        let coin = MockInput::coin_signed(None);
+        let script = MockTransaction::script(
+            vec![coin.clone(), coin.clone(), MockInput::contract()],
+            vec![],
+            vec![MockReceipt::call(), MockReceipt::log(), MockReceipt::call()],
+        );
+        let mut failed = MockTransaction::script(
+            vec![coin, MockInput::message_coin_signed()],
+            vec![],
+            vec![MockReceipt::revert(), MockReceipt::script_result()],
+        );
+        failed.status = TransactionStatus::Failed;
+        failed.tip = None;
+        let mint = MockTransaction::mint(vec![], vec![], vec![]);
+        let stats = BlockStats::from_transactions(&[script, failed, mint]);
+
+        assert_eq!(
+            stats.transactions,
+            TransactionTypeCounts {
+                script: 2,
+                mint: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(stats.successful_transactions, 2);
+        assert_eq!(stats.failed_transactions, 1);
+        assert_eq!(stats.total_gas, 3000);
+        assert_eq!(stats.total_fee, 600);
+        assert_eq!(stats.total_tip, 100);
+        assert_eq!(stats.unique_senders, 2);
+        assert_eq!(
+            stats.receipts,
+            ReceiptTypeCounts {
+                call: 2,
+                log: 1,
+                revert: 1,
+                script_result: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_block_stats_gas_used_differs_from_total_gas() {
+        let mut script = MockTransaction::script(vec![], vec![], vec![]);
+        script.total_gas = Some(1500.into());
+        script.gas_used = Some(400.into());
+        let mint = MockTransaction::mint(vec![], vec![], vec![]);
+        let stats = BlockStats::from_transactions(&[script.clone(), script, mint]);
+        assert_eq!(stats.total_gas, 3000);
+        assert_eq!(stats.gas_used, 800);
+    }
+
+    #[test]
+    fn test_block_stats_saturate() {
+        let mut tx = MockTransaction::script(vec![], vec![], vec![]);
+        tx.total_fee = Some(u64::MAX.into());
+        let stats = BlockStats::from_transactions(&[tx.clone(), tx]);
+        assert_eq!(stats.total_fee, u64::MAX);
+    }
+}
@@ -0,0 +1,129 @@
+use fuel_core_types::fuel_crypto::Hasher;
+use fuel_data_parser::DataEncoder;
+use fuel_streams_types::primitives::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    inputs::sole_owner,
+    outputs::Output,
+    transactions::Transaction,
+};
+
+/// A contract deployed by a `Create` transaction
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema,
+)]
+pub struct Contract {
+    pub contract_id: ContractId,
+    pub tx_id: TxId,
+    pub tx_index: i32,
+    pub salt: Salt,
+    /// SHA-256 of the bytecode witness
+    pub bytecode_hash: Bytes32,
+    pub bytecode_length: u32,
+    pub state_root: Bytes32,
+    pub storage_slots_count: u32,
+    /// Owner of the coins and messages paying for the deployment, when all
+    /// of them share one
+    pub deployer: Option<Address>,
+}
+
+impl DataEncoder for Contract {}
+
+impl Contract {
+    /// Contracts deployed by `tx`, the `tx_index`-th transaction of its
+    /// block. Only `Create` transactions deploy any.
+    pub fn from_transaction(tx_index: i32, tx: &Transaction) -> Vec<Self> {
+        if !tx.is_create {
+            return vec![];
+        }
+
+        let bytecode = tx
+            .bytecode_witness_index
+            .and_then(|index| tx.witnesses.as_ref()?.get(index as usize))
+            .map(|witness| witness.0.0.as_slice())
+            .unwrap_or_default();
+        let bytecode_hash = Bytes32::from(*Hasher::hash(bytecode));
+        let deployer = sole_owner(&tx.inputs);
+
+        tx.outputs
+            .iter()
+            .filter_map(|output| match output {
+                Output::ContractCreated(created) => Some(Self {
+                    contract_id: created.contract_id.clone(),
+                    tx_id: tx.id.clone(),
+                    tx_index,
+                    salt: tx.salt.clone().unwrap_or_default(),
+                    bytecode_hash: bytecode_hash.clone(),
+                    bytecode_length: bytecode.len() as u32,
+                    state_root: created.state_root.clone(),
+                    storage_slots_count: tx.storage_slots_count,
+                    deployer: deployer.clone(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        inputs::{
+            Input,
+            MockInput,
+        },
+        outputs::MockOutput,
+        transactions::MockTransaction,
+    };
+
+    #[test]
+    fn test_contracts_from_transaction() {
+        let bytecode = vec![0x12u8; 64];
+        let coin = MockInput::coin_signed(None);
+        let owner = match &coin {
+            Input::Coin(coin) => coin.owner.clone(),
+            _ => unreachable!(),
+        };
+        let outputs = vec![MockOutput::change(10), MockOutput::contract_created()];
+        let mut tx = MockTransaction::create(
+            vec![coin.clone(), coin, MockInput::contract()],
+            outputs.clone(),
+            vec![],
+        );
+        tx.witnesses = Some(vec![HexData::random(), HexData(bytecode.clone().into())]);
+        tx.bytecode_witness_index = Some(1);
+
+        let contracts = Contract::from_transaction(3, &tx);
+        assert_eq!(contracts.len(), 1);
+        let Output::ContractCreated(created) = &outputs[1] else {
+            unreachable!()
+        };
+        let contract = &contracts[0];
+        assert_eq!(contract.contract_id, created.contract_id);
+        assert_eq!(contract.state_root, created.state_root);
+        assert_eq!(contract.tx_id, tx.id);
+        assert_eq!(contract.tx_index, 3);
+        assert_eq!(Some(&contract.salt), tx.salt.as_ref());
+        assert_eq!(
+            contract.bytecode_hash,
+            Bytes32::from(*Hasher::hash(&bytecode))
+        );
+        assert_eq!(contract.bytecode_length, 64);
+        assert_eq!(contract.storage_slots_count, 1);
+        assert_eq!(contract.deployer, Some(owner));
+
+        // Inputs of several owners leave the deployer unknown
+        tx.inputs.push(MockInput::coin_predicate());
+        assert_eq!(Contract::from_transaction(3, &tx)[0].deployer, None);
+
+        let script = MockTransaction::script(vec![], outputs, vec![]);
+        assert!(Contract::from_transaction(0, &script).is_empty());
+    }
+}
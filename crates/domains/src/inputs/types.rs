@@ -120,6 +120,23 @@ impl Default for Input {
     }
 }
 
+/// Owners of the coin and message inputs, in input order and with repeats
+pub fn input_owners(inputs: &[Input]) -> impl Iterator<Item = &Address> {
+    inputs.iter().filter_map(|input| match input {
+        Input::Coin(coin) => Some(&coin.owner),
+        Input::Message(message) => Some(&message.recipient),
+        Input::Contract(_) => None,
+    })
+}
+
+/// The single owner of the coin and message inputs, `None` when there are
+/// none or they belong to several owners
+pub fn sole_owner(inputs: &[Input]) -> Option<Address> {
+    let mut owners = input_owners(inputs);
+    let owner = owners.next()?;
+    owners.all(|other| other == owner).then(|| owner.clone())
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct InputCoin {
     pub amount: Amount,
@@ -1,4 +1,6 @@
+pub mod assets;
 pub mod blocks;
+pub mod contracts;
 pub mod inputs;
 pub mod messages;
 mod msg_payload;
@@ -6,6 +8,7 @@ pub mod outputs;
 pub mod predicates;
 pub mod receipts;
 pub mod transactions;
+pub mod transfers;
 pub mod utxos;
 
 pub use msg_payload::*;
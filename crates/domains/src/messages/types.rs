@@ -96,6 +96,28 @@ impl Message {
             _ => None,
         }
     }
+
+    /// Messages imported and consumed by the block `block_height`, numbered
+    /// in the order the executor emitted them
+    pub fn from_events(
+        block_height: BlockHeight,
+        events: &[FuelCoreExecutorEvent],
+    ) -> Vec<Self> {
+        events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    FuelCoreExecutorEvent::MessageImported(_)
+                        | FuelCoreExecutorEvent::MessageConsumed(_)
+                )
+            })
+            .enumerate()
+            .filter_map(|(index, event)| {
+                Self::new(block_height, index as u32, event.clone())
+            })
+            .collect()
+    }
 }
 
 impl DataEncoder for Message {}
@@ -138,3 +160,47 @@ impl MockMessage {
         vec![Self::imported(), Self::consumed()]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fuel_core_types::entities::relayer::message::MessageV1;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn relayed(amount: u64) -> fuel_core_types::entities::relayer::message::Message {
+        MessageV1 {
+            amount,
+            da_height: 7u64.into(),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_messages_from_events() {
+        let events = vec![
+            FuelCoreExecutorEvent::MessageImported(relayed(10)),
+            FuelCoreExecutorEvent::MessageConsumed(relayed(20)),
+            FuelCoreExecutorEvent::MessageImported(relayed(30)),
+        ];
+        let messages = Message::from_events(BlockHeight::from(5), &events);
+
+        let summary: Vec<_> = messages
+            .iter()
+            .map(|message| (message.r#type, message.amount.0, message.message_index))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (MessageType::Imported, 10, 0),
+                (MessageType::Consumed, 20, 1),
+                (MessageType::Imported, 30, 2),
+            ]
+        );
+        for message in &messages {
+            assert_eq!(message.block_height, BlockHeight::from(5));
+            assert_eq!(message.da_height.0, 7);
+        }
+    }
+}
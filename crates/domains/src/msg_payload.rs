@@ -60,6 +60,29 @@ pub struct MsgPayload {
 impl DataEncoder for MsgPayload {}
 
 impl MsgPayload {
+    /// Builds the payload of a block ingested without executor events
+    pub fn new(
+        block: Block,
+        transactions: Vec<Transaction>,
+        chain_id: FuelCoreChainId,
+        base_asset_id: FuelCoreAssetId,
+    ) -> Self {
+        let metadata = Metadata {
+            chain_id: Arc::new(chain_id),
+            base_asset_id: Arc::new(base_asset_id),
+            block_producer: Arc::new(block.producer.clone()),
+            block_height: Arc::new(block.height),
+            consensus: Arc::new(block.consensus.clone()),
+        };
+        Self {
+            block,
+            transactions,
+            metadata,
+            namespace: None,
+            events: vec![],
+        }
+    }
+
     pub fn with_namespace(mut self, namespace: &str) -> Self {
         self.namespace = Some(namespace.to_string());
         self
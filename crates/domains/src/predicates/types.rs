@@ -1,3 +1,11 @@
+use fuel_core_types::{
+    fuel_asm::{
+        Instruction,
+        RegId,
+    },
+    fuel_crypto::Hasher,
+    fuel_tx,
+};
 use fuel_data_parser::DataEncoder;
 use fuel_streams_types::primitives::*;
 use serde::{
@@ -5,6 +13,11 @@ use serde::{
     Serialize,
 };
 
+use crate::{
+    inputs::Input,
+    transactions::Transaction,
+};
+
 #[derive(
     Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema,
 )]
@@ -15,12 +28,16 @@ pub struct Predicate {
     pub tx_id: TxId,
     pub tx_index: i32,
     pub input_index: i32,
-    pub asset_id: AssetId,
+    /// Asset of the spent coin. Message inputs always carry the base asset
+    /// and have none.
+    pub asset_id: Option<AssetId>,
+    pub predicate_gas_used: GasAmount,
 }
 
 impl DataEncoder for Predicate {}
 
 impl Predicate {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         tx_id: &TxId,
         tx_index: i32,
@@ -28,7 +45,8 @@ impl Predicate {
         blob_id: Option<HexData>,
         predicate_address: &Address,
         predicate_bytecode: &HexData,
-        asset_id: &AssetId,
+        asset_id: Option<&AssetId>,
+        predicate_gas_used: GasAmount,
     ) -> Self {
         Self {
             blob_id,
@@ -37,7 +55,168 @@ impl Predicate {
             input_index,
             predicate_address: predicate_address.clone(),
             predicate_bytecode: predicate_bytecode.clone(),
-            asset_id: asset_id.to_owned(),
+            asset_id: asset_id.cloned(),
+            predicate_gas_used,
+        }
+    }
+
+    /// Predicates of the coin and message inputs of `tx`, the `tx_index`-th
+    /// transaction of its block. Inputs signed by a witness have none.
+    pub fn from_transaction(tx_index: i32, tx: &Transaction) -> Vec<Self> {
+        tx.inputs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, input)| {
+                let (bytecode, asset_id, gas_used) = match input {
+                    Input::Coin(coin) => (
+                        &coin.predicate,
+                        Some(&coin.asset_id),
+                        coin.predicate_gas_used,
+                    ),
+                    Input::Message(message) => {
+                        (&message.predicate, None, message.predicate_gas_used)
+                    }
+                    Input::Contract(_) => return None,
+                };
+                if bytecode.0.0.is_empty() {
+                    return None;
+                }
+                Some(Self::new(
+                    &tx.id,
+                    tx_index,
+                    index as i32,
+                    loader_blob_id(&bytecode.0.0)
+                        .map(|id| HexData(id.into_inner().to_vec().into())),
+                    &predicate_address(&bytecode.0.0),
+                    bytecode,
+                    asset_id,
+                    gas_used,
+                ))
+            })
+            .collect()
+    }
+
+    /// SHA-256 of the predicate bytecode, the key of the bytecode dimension
+    pub fn bytecode_hash(&self) -> Bytes32 {
+        Bytes32::from(*Hasher::hash(&self.predicate_bytecode.0.0))
+    }
+}
+
+/// Address owning the coins and messages spendable by `bytecode`: the root of
+/// the predicate bytecode.
+pub fn predicate_address(bytecode: &[u8]) -> Address {
+    fuel_tx::Input::predicate_owner(bytecode).into()
+}
+
+/// Blob loaded by a loader predicate. The loader moves `$pc` into a register
+/// with its first instruction, offsets that register past its own
+/// instructions to the blob id appended after them and loads the blob with
+/// `LDC` in blob mode. Returns `None` for any other bytecode.
+pub fn loader_blob_id(bytecode: &[u8]) -> Option<BlobId> {
+    let mut instructions = bytecode
+        .chunks_exact(Instruction::SIZE)
+        .map_while(|word| Instruction::try_from(<[u8; 4]>::try_from(word).ok()?).ok());
+
+    let Some(Instruction::MOVE(first)) = instructions.next() else {
+        return None;
+    };
+    let (register, source) = first.unpack();
+    if source != RegId::PC {
+        return None;
+    }
+
+    let mut blob_id_offset = None;
+    for instruction in instructions {
+        match instruction {
+            Instruction::ADDI(addi) if blob_id_offset.is_none() => {
+                let (dst, src, offset) = addi.unpack();
+                if dst == register && src == register {
+                    blob_id_offset = Some(offset.to_u16() as usize);
+                }
+            }
+            Instruction::LDC(ldc) => {
+                let (_, _, _, mode) = ldc.unpack();
+                if mode.to_u8() != 1 {
+                    return None;
+                }
+                let offset = blob_id_offset?;
+                let blob_id: [u8; 32] =
+                    bytecode.get(offset..offset + 32)?.try_into().ok()?;
+                return Some(BlobId::from(blob_id));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_core_types::fuel_asm::op;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        inputs::MockInput,
+        transactions::MockTransaction,
+    };
+
+    #[test]
+    fn test_predicates_from_transaction() {
+        let inputs = vec![
+            MockInput::contract(),
+            MockInput::coin_predicate(),
+            MockInput::message_coin_predicate(),
+        ];
+        let tx = MockTransaction::script(inputs.clone(), vec![], vec![]);
+        let predicates = Predicate::from_transaction(4, &tx);
+
+        assert_eq!(predicates.len(), 2);
+        for (predicate, input) in predicates.iter().zip(&inputs[1..]) {
+            let bytecode = match input {
+                Input::Coin(coin) => &coin.predicate,
+                Input::Message(message) => &message.predicate,
+                Input::Contract(_) => unreachable!(),
+            };
+            assert_eq!(&predicate.predicate_bytecode, bytecode);
+            assert_eq!(
+                predicate.predicate_address,
+                predicate_address(&bytecode.0.0)
+            );
+            assert_eq!(predicate.tx_id, tx.id);
+            assert_eq!(predicate.tx_index, 4);
         }
+        assert_eq!(predicates[0].input_index, 1);
+        assert!(predicates[0].asset_id.is_some());
+        assert_eq!(predicates[1].input_index, 2);
+        assert_eq!(predicates[1].asset_id, None);
+    }
+
+    #[test]
+    fn test_loader_blob_id() {
+        let blob_id = [7u8; 32];
+        let loader = [
+            op::move_(0x10, RegId::PC),
+            op::addi(0x10, 0x10, 5 * Instruction::SIZE as u16),
+            op::bsiz(0x11, 0x10),
+            op::ldc(0x10, 0, 0x11, 1),
+            op::jmp(0x10),
+        ];
+        let mut bytecode: Vec<u8> = loader
+            .iter()
+            .flat_map(|instruction| instruction.to_bytes())
+            .collect();
+        bytecode.extend(blob_id);
+        bytecode.extend([0u8; 8]);
+
+        assert_eq!(loader_blob_id(&bytecode), Some(BlobId::from(blob_id)));
+
+        // Loading a contract instead of a blob
+        let mut contract_loader = bytecode.clone();
+        contract_loader[12..16].copy_from_slice(&op::ldc(0x10, 0, 0x11, 0).to_bytes());
+        assert_eq!(loader_blob_id(&contract_loader), None);
+
+        assert_eq!(loader_blob_id(&bytecode[4..]), None);
+        assert_eq!(loader_blob_id(&[]), None);
     }
 }
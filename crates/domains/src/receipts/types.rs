@@ -30,6 +30,24 @@ pub enum Receipt {
 impl DataEncoder for Receipt {}
 
 impl Receipt {
+    pub fn receipt_type(&self) -> ReceiptType {
+        match self {
+            Receipt::Call(_) => ReceiptType::Call,
+            Receipt::Return(_) => ReceiptType::Return,
+            Receipt::ReturnData(_) => ReceiptType::ReturnData,
+            Receipt::Panic(_) => ReceiptType::Panic,
+            Receipt::Revert(_) => ReceiptType::Revert,
+            Receipt::Log(_) => ReceiptType::Log,
+            Receipt::LogData(_) => ReceiptType::LogData,
+            Receipt::Transfer(_) => ReceiptType::Transfer,
+            Receipt::TransferOut(_) => ReceiptType::TransferOut,
+            Receipt::ScriptResult(_) => ReceiptType::ScriptResult,
+            Receipt::MessageOut(_) => ReceiptType::MessageOut,
+            Receipt::Mint(_) => ReceiptType::Mint,
+            Receipt::Burn(_) => ReceiptType::Burn,
+        }
+    }
+
     #[cfg(any(test, feature = "test-helpers"))]
     pub fn as_call(&self) -> CallReceipt {
         match self {
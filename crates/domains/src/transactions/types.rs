@@ -1,4 +1,7 @@
-use fuel_core_types::fuel_tx;
+use fuel_core_types::{
+    fuel_tx,
+    services::executor::TransactionExecutionResult,
+};
 use fuel_data_parser::DataEncoder;
 pub use fuel_streams_types::{
     TransactionStatus,
@@ -66,6 +69,15 @@ pub struct Transaction {
     pub witnesses_count: u32,
     pub inputs_count: u32,
     pub outputs_count: u32,
+    /// Gas consumed by the transaction, predicates and intrinsic costs
+    /// included
+    pub total_gas: Option<GasAmount>,
+    /// Fee paid by the transaction, tip included
+    pub total_fee: Option<Amount>,
+    /// Gas used by the script, from its `ScriptResult` receipt
+    pub gas_used: Option<GasAmount>,
+    /// Tip paid to the block producer, `None` for mints
+    pub tip: Option<Amount>,
 }
 
 impl DataEncoder for Transaction {}
@@ -74,10 +86,28 @@ impl Transaction {
     pub fn new(
         id: &Bytes32,
         transaction: &FuelCoreTypesTransaction,
-        status: &TransactionStatus,
+        status: &FuelCoreExecutorStatus,
         base_asset_id: &FuelCoreAssetId,
-        receipts: &[FuelCoreReceipt],
     ) -> Self {
+        let (total_gas, total_fee) = match &status.result {
+            TransactionExecutionResult::Success {
+                total_gas,
+                total_fee,
+                ..
+            }
+            | TransactionExecutionResult::Failed {
+                total_gas,
+                total_fee,
+                ..
+            } => (*total_gas, *total_fee),
+        };
+        let receipts: Vec<Receipt> = status
+            .result
+            .receipts()
+            .iter()
+            .map(|r| r.to_owned().into())
+            .collect();
+
         Self {
             id: id.to_owned().into(),
             r#type: transaction.into(),
@@ -106,14 +136,15 @@ impl Transaction {
             script: Self::get_script(transaction),
             script_data: Self::get_script_data(transaction),
             script_gas_limit: Self::get_script_gas_limit(transaction),
-            status: status.to_owned(),
+            status: status.into(),
             storage_slots: Self::get_storage_slots(transaction),
             subsection_index: Self::get_subsection_index(transaction),
             subsections_number: Self::get_subsections_number(transaction),
             tx_pointer: Self::get_tx_pointer(transaction),
             upgrade_purpose: Self::get_upgrade_purpose(transaction),
             witnesses: Self::get_witnesses(transaction),
-            receipts: receipts.iter().map(|r| r.to_owned().into()).collect(),
+            gas_used: Self::get_gas_used(&receipts),
+            receipts,
 
             maturity: Self::get_maturity(transaction),
             policies: Self::get_policies(transaction),
@@ -124,6 +155,9 @@ impl Transaction {
             witnesses_count: Self::get_witnesses_count(transaction),
             inputs_count: Self::get_inputs_count(transaction),
             outputs_count: Self::get_outputs_count(transaction),
+            total_gas: Some(total_gas.into()),
+            total_fee: Some(total_fee.into()),
+            tip: Self::get_tip(transaction),
         }
     }
 
@@ -275,6 +309,19 @@ impl Transaction {
         }
     }
 
+    /// The tip policy is paid in full, and a transaction without one pays none
+    fn get_tip(transaction: &FuelCoreTypesTransaction) -> Option<Amount> {
+        Self::get_policies(transaction)
+            .map(|policies| policies.tip.map(|tip| tip.0).unwrap_or_default().into())
+    }
+
+    fn get_gas_used(receipts: &[Receipt]) -> Option<GasAmount> {
+        receipts.iter().find_map(|receipt| match receipt {
+            Receipt::ScriptResult(result) => Some(result.gas_used.0.into()),
+            _ => None,
+        })
+    }
+
     fn get_proof_set(transaction: &FuelCoreTypesTransaction) -> Option<Vec<Bytes32>> {
         use fuel_core_types::fuel_tx::field::ProofSet;
         match transaction {
@@ -569,6 +616,10 @@ impl MockTransaction {
             witnesses_count: 1, // One witness by default
             inputs_count: 1,    // One input by default
             outputs_count: 1,   // One output by default
+            total_gas: Some(1500.into()),
+            total_fee: Some(300.into()),
+            gas_used: None,
+            tip: Some(100.into()),
         }
     }
 
@@ -613,6 +664,9 @@ impl MockTransaction {
         tx.mint_gas_price = Some(100.into());
         tx.tx_pointer = Some(TxPointer::random());
         tx.witnesses_count = 0; // Mint transactions typically have no witnesses
+        tx.total_gas = Some(0.into());
+        tx.total_fee = Some(0.into());
+        tx.tip = None;
         tx
     }
 
@@ -630,6 +684,7 @@ impl MockTransaction {
         );
         tx.inputs = inputs.clone();
         tx.outputs = outputs.clone();
+        tx.gas_used = Transaction::get_gas_used(&receipts);
         tx.receipts = receipts;
         tx.inputs_count = inputs.len() as u32;
         tx.outputs_count = outputs.len() as u32;
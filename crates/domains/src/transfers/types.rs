@@ -0,0 +1,246 @@
+use std::str::FromStr;
+
+use fuel_data_parser::DataEncoder;
+use fuel_streams_types::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    inputs::sole_owner,
+    outputs::Output,
+    receipts::Receipt,
+    transactions::Transaction,
+};
+
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    derive_more::Display,
+    derive_more::IsVariant,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferKind {
+    /// A `Transfer` receipt, between two contracts
+    #[default]
+    #[display("contract_to_contract")]
+    ContractToContract,
+    /// A `TransferOut` receipt, from a contract to a variable output
+    #[display("contract_to_address")]
+    ContractToAddress,
+    /// A coin output, or a change output paid to another address than the
+    /// sender
+    #[display("address_to_address")]
+    AddressToAddress,
+    /// A `MessageOut` receipt carrying base asset to the L1
+    #[display("withdrawal")]
+    Withdrawal,
+}
+
+impl TryFrom<&str> for TransferKind {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.to_lowercase().as_str() {
+            "contract_to_contract" => Ok(TransferKind::ContractToContract),
+            "contract_to_address" => Ok(TransferKind::ContractToAddress),
+            "address_to_address" => Ok(TransferKind::AddressToAddress),
+            "withdrawal" => Ok(TransferKind::Withdrawal),
+            _ => Err(format!("Invalid transfer kind: {}", s)),
+        }
+    }
+}
+
+impl_enum_string_serialization!(TransferKind, "transfer_kind");
+
+/// Value moved by a transaction, from a receipt or an output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Transfer {
+    pub kind: TransferKind,
+    /// Sending contract or address, `None` for an address transfer whose
+    /// inputs belong to several owners
+    pub from: Option<Bytes32>,
+    /// Receiving contract or address
+    pub to: Bytes32,
+    /// `None` for withdrawals, which carry the base asset
+    pub asset_id: Option<AssetId>,
+    pub amount: Amount,
+    pub tx_id: TxId,
+    pub tx_index: i32,
+    /// Position of the receipt, or of the output for address transfers, in
+    /// its transaction
+    pub index: i32,
+}
+
+impl DataEncoder for Transfer {}
+
+impl Transfer {
+    /// Transfers of `tx`, the `tx_index`-th transaction of its block, with
+    /// the receipt ones first.
+    ///
+    /// A failed transaction reverts its receipts but still creates its coin
+    /// outputs. Variable outputs are left out as they repeat the `TransferOut`
+    /// receipts, and so are change outputs returned to the sender and zero
+    /// amounts.
+    pub fn from_transaction(tx_index: i32, tx: &Transaction) -> Vec<Self> {
+        if tx.is_mint {
+            return vec![];
+        }
+
+        let receipts = match tx.status {
+            TransactionStatus::Failed => &[][..],
+            _ => &tx.receipts[..],
+        };
+        let from_receipts = receipts.iter().enumerate().filter_map(|(index, receipt)| {
+            let transfer: (_, Option<Bytes32>, Bytes32, _, _) = match receipt {
+                Receipt::Transfer(transfer) => (
+                    TransferKind::ContractToContract,
+                    Some(transfer.id.0.into()),
+                    transfer.to.0.into(),
+                    Some(transfer.asset_id.clone()),
+                    transfer.amount.0,
+                ),
+                Receipt::TransferOut(transfer) => (
+                    TransferKind::ContractToAddress,
+                    Some(transfer.id.0.into()),
+                    transfer.to.0.into(),
+                    Some(transfer.asset_id.clone()),
+                    transfer.amount.0,
+                ),
+                Receipt::MessageOut(message) => (
+                    TransferKind::Withdrawal,
+                    Some(message.sender.0.into()),
+                    message.recipient.0.into(),
+                    None,
+                    message.amount.0,
+                ),
+                _ => return None,
+            };
+            Some((index, transfer))
+        });
+
+        let sender: Option<Bytes32> = sole_owner(&tx.inputs).map(|owner| owner.0.into());
+        let from_outputs = tx.outputs.iter().enumerate().filter_map(|(index, output)| {
+            let (to, asset_id, amount) = match output {
+                Output::Coin(coin) => (&coin.to, &coin.asset_id, coin.amount),
+                Output::Change(change) => (&change.to, &change.asset_id, change.amount),
+                _ => return None,
+            };
+            let to: Bytes32 = to.0.into();
+            if matches!(output, Output::Change(_)) && sender.as_ref() == Some(&to) {
+                return None;
+            }
+            let transfer = (
+                TransferKind::AddressToAddress,
+                sender.clone(),
+                to,
+                Some(asset_id.clone()),
+                amount.0,
+            );
+            Some((index, transfer))
+        });
+
+        from_receipts
+            .chain(from_outputs)
+            .filter(|(_, (.., amount))| *amount > 0)
+            .map(|(index, (kind, from, to, asset_id, amount))| Self {
+                kind,
+                from,
+                to,
+                asset_id,
+                amount: amount.into(),
+                tx_id: tx.id.clone(),
+                tx_index,
+                index: index as i32,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        inputs::{
+            Input,
+            MockInput,
+        },
+        outputs::MockOutput,
+        receipts::MockReceipt,
+        transactions::MockTransaction,
+    };
+
+    #[test]
+    fn test_transfers_from_transaction() {
+        let coin = MockInput::coin_signed(None);
+        let Input::Coin(input) = &coin else {
+            unreachable!()
+        };
+        let owner = input.owner.clone();
+        let mut change = MockOutput::change(10);
+        if let Output::Change(change) = &mut change {
+            change.to = owner.clone();
+        }
+        let outputs = vec![
+            MockOutput::coin(50),
+            change,
+            MockOutput::change(20),
+            MockOutput::variable(100),
+            MockOutput::coin(0),
+        ];
+        let receipts = vec![
+            MockReceipt::call(),
+            MockReceipt::transfer(),
+            MockReceipt::transfer_out(),
+            MockReceipt::message_out(),
+        ];
+        let mut tx = MockTransaction::script(vec![coin], outputs, receipts.clone());
+        let transfers = Transfer::from_transaction(4, &tx);
+
+        let kinds: Vec<_> = transfers.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TransferKind::ContractToContract,
+                TransferKind::ContractToAddress,
+                TransferKind::Withdrawal,
+                TransferKind::AddressToAddress,
+                TransferKind::AddressToAddress,
+            ]
+        );
+        let indexes: Vec<_> = transfers.iter().map(|t| t.index).collect();
+        assert_eq!(indexes, vec![1, 2, 3, 0, 2]);
+
+        let Receipt::Transfer(transfer) = &receipts[1] else {
+            unreachable!()
+        };
+        assert_eq!(transfers[0].from, Some(transfer.id.0.into()));
+        assert_eq!(transfers[0].to, transfer.to.0.into());
+        assert_eq!(transfers[0].asset_id, Some(transfer.asset_id.clone()));
+        assert_eq!(transfers[0].amount.0, 100);
+        assert_eq!(transfers[0].tx_id, tx.id);
+        assert_eq!(transfers[0].tx_index, 4);
+        assert_eq!(transfers[2].asset_id, None);
+        assert_eq!(transfers[3].from, Some(owner.0.into()));
+        assert_eq!(transfers[3].amount.0, 50);
+
+        // A failed transaction keeps only its coin outputs
+        tx.status = TransactionStatus::Failed;
+        let transfers = Transfer::from_transaction(4, &tx);
+        assert_eq!(transfers.len(), 2);
+        assert!(transfers.iter().all(|t| t.kind.is_address_to_address()));
+
+        // Inputs of several owners leave the sender unknown
+        tx.inputs.push(MockInput::coin_predicate());
+        assert_eq!(Transfer::from_transaction(4, &tx)[0].from, None);
+    }
+}
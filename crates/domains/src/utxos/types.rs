@@ -5,10 +5,10 @@ use serde::{
     Serialize,
 };
 
-#[cfg(any(test, feature = "test-helpers"))]
 use crate::{
     inputs::Input,
     outputs::Output,
+    transactions::Transaction,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
@@ -27,6 +27,94 @@ pub struct Utxo {
 
 impl DataEncoder for Utxo {}
 
+impl Utxo {
+    /// UTXOs spent and created by `tx`: one spent record per coin and
+    /// contract input and one unspent record per coin, change and variable
+    /// output, in input then output order. Every record points to `tx`.
+    pub fn from_transaction(tx: &Transaction) -> Vec<Self> {
+        let spent = tx
+            .inputs
+            .iter()
+            .filter_map(|input| Self::spent(&tx.id, input));
+        let created =
+            tx.outputs.iter().enumerate().filter_map(|(index, output)| {
+                Self::created(&tx.id, index as u16, output)
+            });
+        spent.chain(created).collect()
+    }
+
+    /// The UTXO consumed by `input` in the transaction `tx_id`. Message
+    /// inputs don't spend UTXOs.
+    pub fn spent(tx_id: &TxId, input: &Input) -> Option<Self> {
+        match input {
+            Input::Contract(contract) => Some(Utxo {
+                status: UtxoStatus::Spent,
+                r#type: UtxoType::InputContract,
+                tx_id: tx_id.clone(),
+                utxo_id: contract.utxo_id.clone(),
+                from: None,
+                to: None,
+                amount: None,
+                asset_id: None,
+                contract_id: Some(ContractId::from(&contract.contract_id)),
+                nonce: None,
+            }),
+            Input::Coin(coin) => Some(Utxo {
+                status: UtxoStatus::Spent,
+                r#type: UtxoType::InputCoin,
+                tx_id: tx_id.clone(),
+                utxo_id: coin.utxo_id.clone(),
+                from: None,
+                to: Some(coin.owner.clone()),
+                amount: Some(coin.amount),
+                asset_id: Some(coin.asset_id.clone()),
+                contract_id: None,
+                nonce: None,
+            }),
+            Input::Message(_) => None,
+        }
+    }
+
+    /// The UTXO created by the `output_index`-th output of the transaction
+    /// `tx_id`, identified the way later inputs reference it.
+    pub fn created(tx_id: &TxId, output_index: u16, output: &Output) -> Option<Self> {
+        let (r#type, to, amount, asset_id) = match output {
+            Output::Coin(coin) => {
+                (UtxoType::OutputCoin, &coin.to, coin.amount, &coin.asset_id)
+            }
+            Output::Change(change) => (
+                UtxoType::OutputChange,
+                &change.to,
+                change.amount,
+                &change.asset_id,
+            ),
+            Output::Variable(variable) => (
+                UtxoType::OutputVariable,
+                &variable.to,
+                variable.amount,
+                &variable.asset_id,
+            ),
+            // Contract and ContractCreated outputs don't create UTXOs
+            Output::Contract(_) | Output::ContractCreated(_) => return None,
+        };
+        Some(Utxo {
+            status: UtxoStatus::Unspent,
+            r#type,
+            tx_id: tx_id.clone(),
+            utxo_id: UtxoId {
+                tx_id: Bytes32::from(tx_id),
+                output_index,
+            },
+            from: None,
+            to: Some(to.clone()),
+            amount: Some(amount),
+            asset_id: Some(asset_id.clone()),
+            contract_id: None,
+            nonce: None,
+        })
+    }
+}
+
 #[cfg(any(test, feature = "test-helpers"))]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MockUtxo;
@@ -109,3 +197,68 @@ impl MockUtxo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        inputs::MockInput,
+        outputs::MockOutput,
+        transactions::MockTransaction,
+    };
+
+    #[test]
+    fn test_utxos_from_transaction() {
+        let inputs = MockInput::all();
+        let outputs = MockOutput::all();
+        let tx = MockTransaction::script(inputs.clone(), outputs.clone(), vec![]);
+        let utxos = Utxo::from_transaction(&tx);
+
+        let spent: Vec<_> = utxos
+            .iter()
+            .filter(|utxo| utxo.status == UtxoStatus::Spent)
+            .collect();
+        let expected_spent: Vec<_> = inputs
+            .iter()
+            .filter_map(|input| match input {
+                Input::Coin(coin) => Some(&coin.utxo_id),
+                Input::Contract(contract) => Some(&contract.utxo_id),
+                Input::Message(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            spent.iter().map(|utxo| &utxo.utxo_id).collect::<Vec<_>>(),
+            expected_spent
+        );
+
+        let created: Vec<_> = utxos
+            .iter()
+            .filter(|utxo| utxo.status == UtxoStatus::Unspent)
+            .collect();
+        let expected_created: Vec<_> = outputs
+            .iter()
+            .enumerate()
+            .filter(|(_, output)| {
+                matches!(
+                    output,
+                    Output::Coin(_) | Output::Change(_) | Output::Variable(_)
+                )
+            })
+            .map(|(index, _)| UtxoId {
+                tx_id: Bytes32::from(&tx.id),
+                output_index: index as u16,
+            })
+            .collect();
+        assert_eq!(
+            created
+                .iter()
+                .map(|utxo| utxo.utxo_id.clone())
+                .collect::<Vec<_>>(),
+            expected_created
+        );
+
+        assert!(utxos.iter().all(|utxo| utxo.tx_id == tx.id));
+    }
+}
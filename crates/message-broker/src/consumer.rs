@@ -0,0 +1,231 @@
+use async_nats::jetstream::{
+    self,
+    consumer::{
+        DeliverPolicy,
+        pull,
+    },
+    stream::{
+        LastRawMessageErrorKind,
+        Stream,
+    },
+};
+use fuel_data_parser::DataEncoder;
+use fuel_streams_domains::{
+    MsgPayload,
+    blocks::Block,
+    receipts::Receipt,
+    transactions::Transaction,
+};
+use futures::{
+    StreamExt,
+    stream::BoxStream,
+};
+
+use crate::{
+    BrokerError,
+    Entity,
+    Subjects,
+};
+
+/// Stream of decoded messages.
+pub type TypedStream<T> = BoxStream<'static, Result<T, BrokerError>>;
+
+/// Where a subscription starts in the stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StartFrom {
+    /// Only messages published after subscribing
+    #[default]
+    New,
+    /// Every message still retained by the stream
+    All,
+    /// The message with this stream sequence and the ones after it
+    Sequence(u64),
+    /// The messages of the block at this height and the ones after it, or
+    /// every retained message when the block is no longer in the stream
+    Height(u32),
+}
+
+/// Subscribes to the payloads published by
+/// [`JetStreamPublisher`](crate::JetStreamPublisher) as typed streams.
+///
+/// Each subscription is an ordered consumer: messages arrive in publish order
+/// and are not acknowledged, so dropping the stream is all it takes to stop.
+pub struct JetStreamConsumer {
+    jetstream: jetstream::Context,
+    subjects: Subjects,
+}
+
+impl JetStreamConsumer {
+    pub async fn connect(
+        url: &str,
+        network: &str,
+        namespace: Option<&str>,
+    ) -> Result<Self, BrokerError> {
+        let client = async_nats::connect(url).await?;
+        Ok(Self::new(
+            client,
+            Subjects::new(network).with_namespace(namespace),
+        ))
+    }
+
+    pub fn new(client: async_nats::Client, subjects: Subjects) -> Self {
+        Self {
+            jetstream: jetstream::new(client),
+            subjects,
+        }
+    }
+
+    pub fn subjects(&self) -> &Subjects {
+        &self.subjects
+    }
+
+    pub async fn payloads(
+        &self,
+        start: StartFrom,
+    ) -> Result<TypedStream<MsgPayload>, BrokerError> {
+        self.subscribe(Entity::Payloads, start).await
+    }
+
+    pub async fn blocks(
+        &self,
+        start: StartFrom,
+    ) -> Result<TypedStream<Block>, BrokerError> {
+        self.subscribe(Entity::Blocks, start).await
+    }
+
+    pub async fn transactions(
+        &self,
+        start: StartFrom,
+    ) -> Result<TypedStream<Transaction>, BrokerError> {
+        self.subscribe(Entity::Transactions, start).await
+    }
+
+    pub async fn receipts(
+        &self,
+        start: StartFrom,
+    ) -> Result<TypedStream<Receipt>, BrokerError> {
+        self.subscribe(Entity::Receipts, start).await
+    }
+
+    /// Decodes every message of `entity` as `T`
+    pub async fn subscribe<T: DataEncoder + 'static>(
+        &self,
+        entity: Entity,
+        start: StartFrom,
+    ) -> Result<TypedStream<T>, BrokerError> {
+        let stream = self
+            .jetstream
+            .get_stream(self.subjects.stream_name())
+            .await?;
+        let deliver_policy = self.deliver_policy(&stream, start).await?;
+        let consumer = stream
+            .create_consumer(pull::OrderedConfig {
+                filter_subject: self.subjects.filter(entity),
+                deliver_policy,
+                ..Default::default()
+            })
+            .await?;
+        let messages = consumer.messages().await?;
+
+        Ok(messages
+            .map(|message| {
+                let message = message?;
+                Ok(T::decode_json(&message.payload)?)
+            })
+            .boxed())
+    }
+
+    async fn deliver_policy(
+        &self,
+        stream: &Stream,
+        start: StartFrom,
+    ) -> Result<DeliverPolicy, BrokerError> {
+        Ok(match start {
+            StartFrom::New => DeliverPolicy::New,
+            StartFrom::All => DeliverPolicy::All,
+            StartFrom::Sequence(start_sequence) => {
+                DeliverPolicy::ByStartSequence { start_sequence }
+            }
+            // The payload is published first, so every message of the block
+            // has a later sequence
+            StartFrom::Height(height) => match stream
+                .get_last_raw_message_by_subject(&self.subjects.payload(height))
+                .await
+            {
+                Ok(message) => DeliverPolicy::ByStartSequence {
+                    start_sequence: message.sequence,
+                },
+                Err(e) if e.kind() == LastRawMessageErrorKind::NoMessageFound => {
+                    DeliverPolicy::All
+                }
+                Err(e) => return Err(e.into()),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use fuel_streams_domains::MockMsgPayload;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::JetStreamPublisher;
+
+    fn nats_url() -> String {
+        std::env::var("NATS_URL").unwrap_or("nats://localhost:4222".to_string())
+    }
+
+    async fn next<T>(stream: &mut TypedStream<T>) -> T {
+        tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Timed out waiting for a message")
+            .expect("Stream ended")
+            .expect("Message decodes")
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a NATS server at NATS_URL"]
+    async fn test_publish_and_consume() -> Result<(), BrokerError> {
+        // A random namespace keeps concurrent runs on separate streams
+        let namespace = format!("test{}", rand::random::<u32>());
+        let payload = MockMsgPayload::build(7u32.into(), &namespace);
+        let publisher = JetStreamPublisher::connect(&nats_url(), "local").await?;
+
+        let summary = publisher.publish(&payload).await?;
+        let receipts: usize = payload
+            .transactions
+            .iter()
+            .map(|tx| tx.receipts.len())
+            .sum();
+        assert_eq!(summary.published, 2 + payload.transactions.len() + receipts);
+        assert_eq!(summary.duplicates, 0);
+
+        // Publishing the same block again is deduplicated by message ID
+        let summary = publisher.publish(&payload).await?;
+        assert_eq!(summary.published, 0);
+
+        let consumer =
+            JetStreamConsumer::connect(&nats_url(), "local", Some(&namespace)).await?;
+        let mut blocks = consumer.blocks(StartFrom::All).await?;
+        assert_eq!(next(&mut blocks).await, payload.block);
+
+        let mut transactions = consumer.transactions(StartFrom::All).await?;
+        for tx in &payload.transactions {
+            assert_eq!(next(&mut transactions).await, *tx);
+        }
+
+        let mut payloads = consumer.payloads(StartFrom::All).await?;
+        assert_eq!(
+            next(&mut payloads).await.block_height(),
+            payload.block_height()
+        );
+
+        let mut blocks = consumer.blocks(StartFrom::Height(7)).await?;
+        assert_eq!(next(&mut blocks).await, payload.block);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use async_nats::jetstream::{
+    consumer::{
+        StreamError,
+        pull::OrderedError,
+    },
+    context::{
+        CreateStreamError,
+        GetStreamError,
+        PublishError,
+    },
+    stream::{
+        ConsumerError,
+        LastRawMessageError,
+    },
+};
+use fuel_data_parser::DataParserError;
+
+/// Message broker error types.
+#[derive(Debug, thiserror::Error)]
+pub enum BrokerError {
+    #[error("Failed to connect to NATS: {0}")]
+    Connect(#[from] async_nats::ConnectError),
+    #[error("Failed to create stream: {0}")]
+    CreateStream(#[from] CreateStreamError),
+    #[error("Failed to get stream: {0}")]
+    GetStream(#[from] GetStreamError),
+    #[error("Failed to publish: {0}")]
+    Publish(#[from] PublishError),
+    #[error("Failed to look up message: {0}")]
+    LastMessage(#[from] LastRawMessageError),
+    #[error("Failed to create consumer: {0}")]
+    Consumer(#[from] ConsumerError),
+    #[error("Failed to read messages: {0}")]
+    Messages(#[from] StreamError),
+    #[error("Failed to receive message: {0}")]
+    Receive(#[from] OrderedError),
+    #[error(transparent)]
+    Encoding(#[from] DataParserError),
+}
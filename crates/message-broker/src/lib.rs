@@ -0,0 +1,18 @@
+//! Publishes Fuel block payloads to NATS JetStream and reads them back as
+//! typed streams.
+//!
+//! Every block is published under
+//! `{namespace}.{network}.{entity}.{height}[.{tx_index}[.{receipt_index}]]`,
+//! where the entity is one of `payloads`, `blocks`, `transactions` or
+//! `receipts`. The subject doubles as the message ID, so JetStream drops a
+//! block published twice within the stream's duplicate window.
+
+mod consumer;
+mod error;
+mod publisher;
+mod subjects;
+
+pub use consumer::*;
+pub use error::*;
+pub use publisher::*;
+pub use subjects::*;
@@ -0,0 +1,217 @@
+use std::{
+    collections::{
+        HashSet,
+        VecDeque,
+    },
+    future::IntoFuture,
+    sync::Mutex,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use async_nats::jetstream::{
+    self,
+    context::Publish,
+    stream,
+};
+use fuel_data_parser::DataEncoder;
+use fuel_streams_domains::MsgPayload;
+
+use crate::{
+    BrokerError,
+    Subjects,
+};
+
+/// Default window in which JetStream drops messages with a known ID
+pub const DEFAULT_DUPLICATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+/// Default retention of the published messages
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// Default number of payloads kept while JetStream is unavailable
+pub const DEFAULT_MAX_UNPUBLISHED: usize = 1_000;
+/// Default time before publishing again after a failure
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Acknowledged messages of a published payload.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PublishSummary {
+    pub published: usize,
+    /// Messages JetStream dropped as already stored
+    pub duplicates: usize,
+}
+
+/// Publishes block payloads to JetStream.
+pub struct JetStreamPublisher {
+    jetstream: jetstream::Context,
+    network: String,
+    duplicate_window: Duration,
+    max_age: Duration,
+    /// Streams already created or checked by this publisher
+    streams: Mutex<HashSet<String>>,
+}
+
+impl JetStreamPublisher {
+    pub async fn connect(url: &str, network: &str) -> Result<Self, BrokerError> {
+        let client = async_nats::connect(url).await?;
+        Ok(Self::new(client, network))
+    }
+
+    pub fn new(client: async_nats::Client, network: &str) -> Self {
+        Self {
+            jetstream: jetstream::new(client),
+            network: network.to_string(),
+            duplicate_window: DEFAULT_DUPLICATE_WINDOW,
+            max_age: DEFAULT_MAX_AGE,
+            streams: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn with_duplicate_window(mut self, window: Duration) -> Self {
+        self.duplicate_window = window;
+        self
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Subjects of a payload, in the payload's namespace
+    pub fn subjects(&self, payload: &MsgPayload) -> Subjects {
+        Subjects::new(&self.network).with_namespace(payload.namespace.as_deref())
+    }
+
+    /// Creates the stream of `subjects` unless it already exists
+    pub async fn ensure_stream(&self, subjects: &Subjects) -> Result<(), BrokerError> {
+        let name = subjects.stream_name();
+        if self.streams.lock().expect("Lock poisoned").contains(&name) {
+            return Ok(());
+        }
+
+        self.jetstream
+            .get_or_create_stream(stream::Config {
+                name: name.clone(),
+                subjects: vec![subjects.stream_subjects()],
+                duplicate_window: self.duplicate_window,
+                max_age: self.max_age,
+                ..Default::default()
+            })
+            .await?;
+        tracing::info!("Publishing to JetStream stream {name}");
+        self.streams.lock().expect("Lock poisoned").insert(name);
+        Ok(())
+    }
+
+    /// Publishes the payload, its block, and each of its transactions and
+    /// receipts. Each message uses its subject as the message ID.
+    pub async fn publish(
+        &self,
+        payload: &MsgPayload,
+    ) -> Result<PublishSummary, BrokerError> {
+        let subjects = self.subjects(payload);
+        self.ensure_stream(&subjects).await?;
+
+        let height = payload.block_height().0;
+        let mut messages = vec![
+            (subjects.payload(height), payload.encode_json()?),
+            (subjects.block(height), payload.block.encode_json()?),
+        ];
+        for (tx_index, tx) in payload.transactions.iter().enumerate() {
+            messages.push((subjects.transaction(height, tx_index), tx.encode_json()?));
+            for (receipt_index, receipt) in tx.receipts.iter().enumerate() {
+                messages.push((
+                    subjects.receipt(height, tx_index, receipt_index),
+                    receipt.encode_json()?,
+                ));
+            }
+        }
+
+        // Send everything first, then wait for the acknowledgements together
+        let mut acks = Vec::with_capacity(messages.len());
+        for (subject, data) in messages {
+            let publish = Publish::build().payload(data.into()).message_id(&subject);
+            acks.push(self.jetstream.send_publish(subject, publish).await?);
+        }
+        let acks =
+            futures::future::try_join_all(acks.into_iter().map(IntoFuture::into_future))
+                .await?;
+
+        let duplicates = acks.iter().filter(|ack| ack.duplicate).count();
+        Ok(PublishSummary {
+            published: acks.len() - duplicates,
+            duplicates,
+        })
+    }
+}
+
+/// Publishes payloads as a side output that never fails its caller for long.
+///
+/// Payloads that fail to publish are kept, in order, and published again by a
+/// later [`publish`](Self::publish) once the retry delay has passed. Beyond
+/// `max_unpublished` waiting payloads the oldest are dropped.
+pub struct BufferedPublisher {
+    publisher: JetStreamPublisher,
+    unpublished: VecDeque<MsgPayload>,
+    max_unpublished: usize,
+    retry_delay: Duration,
+    retry_at: Option<Instant>,
+}
+
+impl BufferedPublisher {
+    pub fn new(publisher: JetStreamPublisher) -> Self {
+        Self {
+            publisher,
+            unpublished: VecDeque::new(),
+            max_unpublished: DEFAULT_MAX_UNPUBLISHED,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            retry_at: None,
+        }
+    }
+
+    pub fn with_max_unpublished(mut self, max_unpublished: usize) -> Self {
+        self.max_unpublished = max_unpublished.max(1);
+        self
+    }
+
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Payloads waiting to be published again
+    pub fn unpublished(&self) -> usize {
+        self.unpublished.len()
+    }
+
+    /// Queues `payload` and publishes the waiting payloads in order, unless
+    /// the last failure is still being waited out. On error the payloads that
+    /// were not published stay queued.
+    pub async fn publish(&mut self, payload: MsgPayload) -> Result<(), BrokerError> {
+        self.unpublished.push_back(payload);
+        if self.unpublished.len() > self.max_unpublished
+            && let Some(dropped) = self.unpublished.pop_front()
+        {
+            tracing::error!(
+                "Dropping block {} that JetStream has been unavailable for",
+                dropped.block_height()
+            );
+        }
+        if self
+            .retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+        {
+            return Ok(());
+        }
+
+        while let Some(payload) = self.unpublished.front() {
+            if let Err(e) = self.publisher.publish(payload).await {
+                self.retry_at = Some(Instant::now() + self.retry_delay);
+                return Err(e);
+            }
+            self.unpublished.pop_front();
+        }
+        self.retry_at = None;
+        Ok(())
+    }
+}
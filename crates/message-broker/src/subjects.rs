@@ -0,0 +1,131 @@
+use std::fmt::Display;
+
+/// Namespace of the subjects when the payload sets none
+pub const DEFAULT_NAMESPACE: &str = "fuel";
+
+/// Kind of message published for a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Entity {
+    /// The whole `MsgPayload`
+    Payloads,
+    Blocks,
+    Transactions,
+    Receipts,
+}
+
+impl Display for Entity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Entity::Payloads => write!(f, "payloads"),
+            Entity::Blocks => write!(f, "blocks"),
+            Entity::Transactions => write!(f, "transactions"),
+            Entity::Receipts => write!(f, "receipts"),
+        }
+    }
+}
+
+/// Builds the subjects and stream name of a namespace and network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subjects {
+    namespace: String,
+    network: String,
+}
+
+/// Subject tokens cannot contain separators or wildcards
+fn token(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '.' | '*' | '>' | ' ' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+impl Subjects {
+    pub fn new(network: &str) -> Self {
+        Self {
+            namespace: DEFAULT_NAMESPACE.to_string(),
+            network: token(network),
+        }
+    }
+
+    pub fn with_namespace(mut self, namespace: Option<&str>) -> Self {
+        self.namespace = token(namespace.unwrap_or(DEFAULT_NAMESPACE));
+        self
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn network(&self) -> &str {
+        &self.network
+    }
+
+    /// Name of the stream holding every subject of the namespace and network
+    pub fn stream_name(&self) -> String {
+        format!("{}_{}", self.namespace, self.network)
+            .replace('-', "_")
+            .to_uppercase()
+    }
+
+    /// Wildcard captured by the stream
+    pub fn stream_subjects(&self) -> String {
+        format!("{}.{}.>", self.namespace, self.network)
+    }
+
+    /// Wildcard matching every message of an entity
+    pub fn filter(&self, entity: Entity) -> String {
+        format!("{}.{}.{entity}.>", self.namespace, self.network)
+    }
+
+    fn entity(&self, entity: Entity, height: u32) -> String {
+        format!("{}.{}.{entity}.{height}", self.namespace, self.network)
+    }
+
+    pub fn payload(&self, height: u32) -> String {
+        self.entity(Entity::Payloads, height)
+    }
+
+    pub fn block(&self, height: u32) -> String {
+        self.entity(Entity::Blocks, height)
+    }
+
+    pub fn transaction(&self, height: u32, tx_index: usize) -> String {
+        format!("{}.{tx_index}", self.entity(Entity::Transactions, height))
+    }
+
+    pub fn receipt(&self, height: u32, tx_index: usize, receipt_index: usize) -> String {
+        format!(
+            "{}.{tx_index}.{receipt_index}",
+            self.entity(Entity::Receipts, height)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_subjects() {
+        let subjects = Subjects::new("mainnet");
+        assert_eq!(subjects.stream_name(), "FUEL_MAINNET");
+        assert_eq!(subjects.stream_subjects(), "fuel.mainnet.>");
+        assert_eq!(subjects.payload(42), "fuel.mainnet.payloads.42");
+        assert_eq!(subjects.block(42), "fuel.mainnet.blocks.42");
+        assert_eq!(
+            subjects.transaction(42, 1),
+            "fuel.mainnet.transactions.42.1"
+        );
+        assert_eq!(subjects.receipt(42, 1, 3), "fuel.mainnet.receipts.42.1.3");
+        assert_eq!(subjects.filter(Entity::Receipts), "fuel.mainnet.receipts.>");
+
+        let subjects = Subjects::new("local").with_namespace(Some("test-1.a"));
+        assert_eq!(subjects.stream_name(), "TEST_1_A_LOCAL");
+        assert_eq!(subjects.block(1), "test-1_a.local.blocks.1");
+    }
+}
@@ -1,2 +1,3 @@
+pub mod server;
 pub mod shutdown;
 pub mod tracing;
@@ -0,0 +1,157 @@
+//! Axum server shared by the HTTP services.
+//!
+//! [`Server`] wraps a service router with the routes every service exposes
+//! (`/health` and, when an OpenAPI document is set, `/openapi.json`), request
+//! logging under the `fuel_web_utils::server` target and permissive CORS, and
+//! runs it until the [`ShutdownController`] fires.
+
+mod error;
+mod pagination;
+mod ws;
+
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    time::Instant,
+};
+
+use axum::{
+    Json,
+    Router,
+    extract::Request,
+    middleware::{
+        self,
+        Next,
+    },
+    response::Response,
+    routing::get,
+};
+pub use error::*;
+pub use pagination::*;
+use serde_json::json;
+use tower_http::cors::CorsLayer;
+use utoipa::openapi::OpenApi;
+pub use ws::*;
+
+use crate::shutdown::ShutdownController;
+
+pub const DEFAULT_PORT: u16 = 9003;
+
+pub struct Server {
+    port: u16,
+    router: Router,
+    openapi: Option<OpenApi>,
+}
+
+impl Server {
+    pub fn new(port: u16, router: Router) -> Self {
+        Self {
+            port,
+            router,
+            openapi: None,
+        }
+    }
+
+    /// Serves `openapi` at `/openapi.json`
+    pub fn with_openapi(mut self, openapi: OpenApi) -> Self {
+        self.openapi = Some(openapi);
+        self
+    }
+
+    /// The service router with the shared routes and layers
+    pub fn router(self) -> Router {
+        let mut router = self.router.route("/health", get(health));
+        if let Some(openapi) = self.openapi {
+            router =
+                router.route("/openapi.json", get(move || async move { Json(openapi) }));
+        }
+
+        router
+            .layer(middleware::from_fn(log_request))
+            .layer(CorsLayer::permissive())
+    }
+
+    pub async fn run(self, shutdown: Arc<ShutdownController>) -> anyhow::Result<()> {
+        let addr = SocketAddr::from(([0, 0, 0, 0], self.port));
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!("Listening on {addr}");
+
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(async move { shutdown.wait_for_shutdown().await })
+            .await?;
+        tracing::info!("Server stopped");
+        Ok(())
+    }
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn log_request(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+    tracing::debug!(
+        "{method} {path} {} in {:?}",
+        response.status(),
+        start.elapsed()
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{
+            Body,
+            to_bytes,
+        },
+        http::StatusCode,
+    };
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use utoipa::openapi::{
+        InfoBuilder,
+        OpenApiBuilder,
+    };
+
+    use super::*;
+
+    async fn get_json(router: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = router
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_shared_routes() {
+        let openapi = OpenApiBuilder::new()
+            .info(InfoBuilder::new().title("test").version("1").build())
+            .build();
+        let router = Server::new(DEFAULT_PORT, Router::new())
+            .with_openapi(openapi)
+            .router();
+
+        let (status, body) = get_json(router.clone(), "/health").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "status": "ok" }));
+
+        let (status, body) = get_json(router, "/openapi.json").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["info"]["title"], "test");
+    }
+
+    #[tokio::test]
+    async fn test_openapi_is_optional() {
+        let router = Server::new(DEFAULT_PORT, Router::new()).router();
+        let (status, _) = get_json(router, "/openapi.json").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+pub const DEFAULT_PAGE_LIMIT: u32 = 20;
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Cursor pagination query parameters.
+///
+/// The cursor is the position of the last item of the previous page, as
+/// returned in [`Page::next_cursor`]. Each endpoint documents its ordering.
+#[derive(Debug, Clone, Copy, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Pagination {
+    /// Position of the last item of the previous page
+    pub cursor: Option<u64>,
+    /// Items per page, at most 100 (default 20)
+    pub limit: Option<u32>,
+}
+
+impl Pagination {
+    pub fn limit(&self) -> u32 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+}
+
+/// A page of items and the cursor of the next one.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    /// Absent on the last page
+    pub next_cursor: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page fetched with `pagination`. A full page may be followed by
+    /// more items, so its last position becomes the next cursor.
+    pub fn new(
+        data: Vec<T>,
+        pagination: &Pagination,
+        position: impl Fn(&T) -> u64,
+    ) -> Self {
+        let next_cursor = if data.len() == pagination.limit() as usize {
+            data.last().map(position)
+        } else {
+            None
+        };
+        Self { data, next_cursor }
+    }
+
+    /// Converts the items, keeping the cursor
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            data: self.data.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_limit_is_clamped() {
+        assert_eq!(Pagination::default().limit(), DEFAULT_PAGE_LIMIT);
+        let pagination = Pagination {
+            cursor: None,
+            limit: Some(0),
+        };
+        assert_eq!(pagination.limit(), 1);
+        let pagination = Pagination {
+            cursor: None,
+            limit: Some(1000),
+        };
+        assert_eq!(pagination.limit(), MAX_PAGE_LIMIT);
+    }
+
+    #[test]
+    fn test_next_cursor() {
+        let pagination = Pagination {
+            cursor: None,
+            limit: Some(3),
+        };
+
+        let page = Page::new(vec![9u64, 8, 7], &pagination, |h| *h);
+        assert_eq!(page.next_cursor, Some(7));
+
+        let page = Page::new(vec![2u64, 1], &pagination, |h| *h);
+        assert_eq!(page.next_cursor, None);
+    }
+}
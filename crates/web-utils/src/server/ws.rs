@@ -0,0 +1,57 @@
+use axum::extract::ws::{
+    CloseFrame,
+    Message,
+    WebSocket,
+    close_code,
+};
+use serde::Serialize;
+use tokio::sync::broadcast::{
+    self,
+    error::RecvError,
+};
+
+/// Sends every item of `receiver` to the socket as a JSON text message until
+/// either side closes.
+///
+/// A client too slow to keep up with the channel is disconnected rather than
+/// silently skipping items, so it knows to catch up through the REST API.
+pub async fn forward_broadcast<T>(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<T>,
+) where
+    T: Clone + Serialize,
+{
+    loop {
+        tokio::select! {
+            item = receiver.recv() => match item {
+                Ok(item) => {
+                    let text = match serde_json::to_string(&item) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            tracing::error!("Failed to encode message: {e}");
+                            continue;
+                        }
+                    };
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Closing lagging socket, {skipped} messages behind");
+                    let frame = CloseFrame {
+                        code: close_code::AGAIN,
+                        reason: "lagged behind".into(),
+                    };
+                    let _ = socket.send(Message::Close(Some(frame))).await;
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum and nothing else is expected
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
@@ -0,0 +1,75 @@
+#!/bin/bash
+
+# Exit immediately if a command exits with a non-zero status
+set -e
+
+# Defaults
+MODE=${MODE:-dev}
+PORT=${PORT:-9004}
+EXTRA_ARGS=""
+
+# ------------------------------
+# Function to Display Usage
+# ------------------------------
+usage() {
+    echo "Usage: $0 --mode <dev|profiling> [--port <port>] [--extra-args <args>]"
+    echo "Options:"
+    echo "  --mode       : Specify the run mode (dev|profiling)"
+    echo "                 Default: dev"
+    echo "  --port       : Port the API listens on"
+    echo "                 Default: 9004"
+    echo "  --extra-args : Additional arguments passed to sv-api (see sv-api --help for details)"
+    echo ""
+    echo "Examples:"
+    echo "  $0 --mode dev                            # Run in dev mode with defaults"
+    echo "  $0 --mode dev --port 8080                # Run in dev mode on port 8080"
+    exit 1
+}
+
+while [[ "$#" -gt 0 ]]; do
+    case $1 in
+        --mode)
+            MODE="$2"
+            shift 2
+            ;;
+        --port)
+            PORT="$2"
+            shift 2
+            ;;
+        --extra-args)
+            EXTRA_ARGS="$2"
+            shift 2
+            ;;
+        --help)
+            usage
+            ;;
+        *)
+            echo "Error: Unknown parameter passed: $1" >&2
+            usage
+            ;;
+    esac
+done
+
+# ------------------------------
+# Load Environment
+# ------------------------------
+source ./scripts/set_env.sh
+
+# Print the configuration being used
+echo -e "\n=========================================="
+echo "⚙️ Configuration"
+echo -e "=========================================="
+echo "Runtime Settings:"
+echo "→ Mode: ${MODE}"
+echo "→ Network: ${NETWORK}"
+echo "→ Port: ${PORT}"
+echo "→ Additional Arguments: ${EXTRA_ARGS:-None}"
+echo -e "==========================================\n"
+
+# Execute based on mode
+if [ "${MODE}" == "dev" ]; then
+    cargo run -p sv-api -- --port "${PORT}" --network "${NETWORK}" ${EXTRA_ARGS}
+else
+    cargo build --profile profiling --package sv-api
+    samply record ./target/profiling/sv-api --port "${PORT}" --network "${NETWORK}" ${EXTRA_ARGS}
+fi
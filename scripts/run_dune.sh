@@ -14,7 +14,7 @@ usage() {
     echo "Options:"
     echo "  --mode : Specify the run mode (dev|profiling)"
     echo "           Default: dev"
-    echo "  Additional arguments are passed directly to sv-dune (see sv-dune --help for details)."
+    echo "  Additional arguments are passed directly to sv-dune run (see sv-dune run --help for details)."
     echo ""
     echo "Examples:"
     echo "  $0 --mode dev                            # Run in dev mode with defaults"
@@ -58,8 +58,8 @@ echo -e "==========================================\n"
 
 # Execute based on mode
 if [ "${MODE}" == "dev" ]; then
-    cargo run -p sv-dune -- "${ARGS[@]}"
+    cargo run -p sv-dune -- run "${ARGS[@]}"
 else
     cargo build --profile profiling --package sv-dune
-    samply record ./target/profiling/sv-dune "${ARGS[@]}"
+    samply record ./target/profiling/sv-dune run "${ARGS[@]}"
 fi
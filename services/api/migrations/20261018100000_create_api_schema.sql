@@ -0,0 +1,21 @@
+CREATE SCHEMA IF NOT EXISTS api;
+
+CREATE TABLE IF NOT EXISTS api.blocks (
+    "block_height" BIGINT PRIMARY KEY,
+    "id" TEXT NOT NULL,
+    -- JSON encoding of the domain block
+    "value" BYTEA NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_blocks_id ON api.blocks ("id");
+
+CREATE TABLE IF NOT EXISTS api.transactions (
+    "block_height" BIGINT NOT NULL,
+    "tx_index" INTEGER NOT NULL,
+    "id" TEXT NOT NULL,
+    -- JSON encoding of the domain transaction, receipts included
+    "value" BYTEA NOT NULL,
+    PRIMARY KEY ("block_height", "tx_index")
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_transactions_id ON api.transactions ("id");
@@ -0,0 +1,31 @@
+use clap::Parser;
+use fuel_web_utils::server::DEFAULT_PORT;
+use url::Url;
+
+#[derive(Debug, Clone, Parser)]
+pub struct Cli {
+    #[arg(long, env = "API_PORT", default_value_t = DEFAULT_PORT)]
+    pub port: u16,
+
+    /// Database the indexed blocks and transactions are stored in.
+    #[arg(long, env)]
+    pub database_url: Url,
+
+    /// NATS server the exporter publishes blocks to.
+    #[arg(long, env)]
+    pub nats_url: Url,
+
+    /// Network of the published subjects: `mainnet`, `testnet`, `devnet` or
+    /// `local`.
+    #[arg(long, env, default_value = "local")]
+    pub network: String,
+
+    /// Namespace of the published subjects, `fuel` when unset.
+    #[arg(long, env)]
+    pub namespace: Option<String>,
+
+    /// The number of live blocks buffered for each WebSocket subscriber
+    /// before a slow client is disconnected.
+    #[arg(long, env, default_value = "256")]
+    pub live_buffer_size: usize,
+}
@@ -0,0 +1,84 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use fuel_message_broker::{
+    JetStreamConsumer,
+    StartFrom,
+};
+use fuel_streams_domains::blocks::Block;
+use fuel_web_utils::shutdown::ShutdownController;
+use futures::StreamExt;
+use tokio::sync::broadcast;
+
+use crate::store::Store;
+
+/// Delay before subscribing again after the indexer fails
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Stores the payloads published to JetStream and forwards new blocks to the
+/// live subscribers.
+pub struct Indexer {
+    consumer: JetStreamConsumer,
+    store: Store,
+    live: broadcast::Sender<Block>,
+}
+
+impl Indexer {
+    pub fn new(
+        consumer: JetStreamConsumer,
+        store: Store,
+        live: broadcast::Sender<Block>,
+    ) -> Self {
+        Self {
+            consumer,
+            store,
+            live,
+        }
+    }
+
+    /// Indexes until shutdown, resubscribing after any failure
+    pub async fn run(self, shutdown: Arc<ShutdownController>) {
+        loop {
+            tokio::select! {
+                result = self.index() => {
+                    if let Err(e) = result {
+                        tracing::error!("Indexer failed, retrying in {RETRY_DELAY:?}: {e:?}");
+                    }
+                }
+                _ = shutdown.wait_for_shutdown() => break,
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(RETRY_DELAY) => {}
+                _ = shutdown.wait_for_shutdown() => break,
+            }
+        }
+    }
+
+    async fn index(&self) -> anyhow::Result<()> {
+        // Resuming from the last stored block replays it, which is harmless
+        // as inserts replace existing rows
+        let last_height = self.store.last_height().await?;
+        let start = last_height
+            .map(|height| StartFrom::Height(height.0))
+            .unwrap_or(StartFrom::All);
+        tracing::info!("Indexing payloads from {start:?}");
+
+        let mut payloads = self.consumer.payloads(start).await?;
+        while let Some(payload) = payloads.next().await {
+            let payload = payload?;
+            self.store
+                .insert(&payload.block, &payload.transactions)
+                .await?;
+
+            // Only blocks never indexed before are live, and having no
+            // subscribers is not an error
+            if last_height.is_none_or(|last| payload.block.height > last) {
+                let _ = self.live.send(payload.block);
+            }
+        }
+
+        anyhow::bail!("Payload stream ended")
+    }
+}
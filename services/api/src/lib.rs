@@ -0,0 +1,9 @@
+#![deny(unused_crate_dependencies)]
+#![deny(warnings)]
+
+mod cli;
+pub mod indexer;
+pub mod routes;
+pub mod store;
+
+pub use cli::*;
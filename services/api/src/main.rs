@@ -0,0 +1,55 @@
+#![deny(warnings)]
+
+use anyhow::Result;
+use clap::Parser;
+use fuel_message_broker::JetStreamConsumer;
+use fuel_web_utils::{
+    server::Server,
+    shutdown::ShutdownController,
+    tracing::init_tracing,
+};
+use std::sync::Arc;
+use sv_api::{
+    Cli,
+    indexer::Indexer,
+    routes::{
+        ApiDoc,
+        AppState,
+        router,
+    },
+    store::Store,
+};
+use tokio::sync::broadcast;
+use utoipa::OpenApi;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    init_tracing()?;
+    let args = Cli::parse();
+
+    let shutdown = Arc::new(ShutdownController::new());
+    shutdown.clone().spawn_signal_handler();
+
+    let store = Store::connect(args.database_url.as_str()).await?;
+    store.migrate().await?;
+
+    let consumer = JetStreamConsumer::connect(
+        args.nats_url.as_str(),
+        &args.network,
+        args.namespace.as_deref(),
+    )
+    .await?;
+    let (live, _) = broadcast::channel(args.live_buffer_size.max(1));
+    let indexer = Indexer::new(consumer, store.clone(), live.clone());
+    let indexer = tokio::spawn(indexer.run(shutdown.clone()));
+
+    let router = router(AppState { store, live });
+    Server::new(args.port, router)
+        .with_openapi(ApiDoc::openapi())
+        .run(shutdown.clone())
+        .await?;
+
+    // The server only stops on shutdown, which also stops the indexer
+    indexer.await?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use axum::{
+    Json,
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+};
+use fuel_streams_domains::{
+    blocks::Block,
+    transactions::Transaction,
+};
+use fuel_streams_types::primitives::BlockId;
+use fuel_web_utils::server::{
+    ApiError,
+    ErrorResponse,
+    Page,
+    Pagination,
+};
+
+use super::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/blocks",
+    tag = "blocks",
+    params(Pagination),
+    responses(
+        (status = 200, description = "Newest blocks first, the cursor being a block height", body = Page<Block>),
+    )
+)]
+pub async fn list_blocks(
+    State(state): State<AppState>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<Block>>, ApiError> {
+    let blocks = state.store.blocks(&pagination).await?;
+    Ok(Json(Page::new(blocks, &pagination, |block| {
+        block.height.0 as u64
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/blocks/{height}",
+    tag = "blocks",
+    params(("height" = u32, Path, description = "Block height")),
+    responses(
+        (status = 200, body = Block),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+pub async fn get_block(
+    State(state): State<AppState>,
+    Path(height): Path<u32>,
+) -> Result<Json<Block>, ApiError> {
+    state
+        .store
+        .block(height)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Block {height} not found")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/blocks/id/{id}",
+    tag = "blocks",
+    params(("id" = String, Path, description = "Hex encoded block ID")),
+    responses(
+        (status = 200, body = Block),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+pub async fn get_block_by_id(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Block>, ApiError> {
+    let id: BlockId = id.parse().map_err(ApiError::BadRequest)?;
+    state
+        .store
+        .block_by_id(&id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Block {id} not found")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/blocks/{height}/transactions",
+    tag = "blocks",
+    params(
+        ("height" = u32, Path, description = "Block height"),
+        Pagination,
+    ),
+    responses(
+        (status = 200, description = "Transactions in block order, the cursor being a transaction index", body = Page<Transaction>),
+    )
+)]
+pub async fn list_block_transactions(
+    State(state): State<AppState>,
+    Path(height): Path<u32>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<Transaction>>, ApiError> {
+    let rows = state.store.block_transactions(height, &pagination).await?;
+    let page = Page::new(rows, &pagination, |(tx_index, _)| *tx_index as u64);
+    Ok(Json(page.map(|(_, transaction)| transaction)))
+}
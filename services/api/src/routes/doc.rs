@@ -0,0 +1,24 @@
+use fuel_web_utils::server::ErrorResponse;
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Fuel API",
+        description = "Blocks and transactions indexed from the exporter's JetStream payloads"
+    ),
+    paths(
+        super::blocks::list_blocks,
+        super::blocks::get_block,
+        super::blocks::get_block_by_id,
+        super::blocks::list_block_transactions,
+        super::transactions::get_transaction,
+        super::ws::subscribe_blocks,
+    ),
+    components(schemas(ErrorResponse)),
+    tags(
+        (name = "blocks", description = "Blocks by height or ID, and live blocks"),
+        (name = "transactions", description = "Transactions by ID"),
+    )
+)]
+pub struct ApiDoc;
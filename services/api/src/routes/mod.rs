@@ -0,0 +1,134 @@
+mod blocks;
+// The code derived for `ApiDoc` calls `Iterator::for_each`
+#[allow(clippy::disallowed_methods)]
+mod doc;
+mod transactions;
+mod ws;
+
+use axum::{
+    Router,
+    routing::get,
+};
+use fuel_streams_domains::blocks::Block;
+use tokio::sync::broadcast;
+
+use crate::store::Store;
+pub use doc::ApiDoc;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub store: Store,
+    /// Blocks sent to the WebSocket subscribers as they are indexed
+    pub live: broadcast::Sender<Block>,
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/api/v1/blocks", get(blocks::list_blocks))
+        .route("/api/v1/blocks/{height}", get(blocks::get_block))
+        .route("/api/v1/blocks/id/{id}", get(blocks::get_block_by_id))
+        .route(
+            "/api/v1/blocks/{height}/transactions",
+            get(blocks::list_block_transactions),
+        )
+        .route(
+            "/api/v1/transactions/{id}",
+            get(transactions::get_transaction),
+        )
+        .route("/api/v1/ws/blocks", get(ws::subscribe_blocks))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{
+            Body,
+            to_bytes,
+        },
+        http::{
+            Request,
+            StatusCode,
+        },
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::{
+        Value,
+        json,
+    };
+    use tower::ServiceExt;
+    use utoipa::OpenApi;
+
+    use super::*;
+    use crate::store::{
+        StoreError,
+        tests::{
+            insert_block,
+            test_store,
+        },
+    };
+
+    async fn get_json(router: &Router, uri: &str) -> (StatusCode, Value) {
+        let response = router
+            .clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[test]
+    fn test_openapi_documents_routes() {
+        let openapi = ApiDoc::openapi();
+        for path in [
+            "/api/v1/blocks",
+            "/api/v1/blocks/{height}",
+            "/api/v1/blocks/id/{id}",
+            "/api/v1/blocks/{height}/transactions",
+            "/api/v1/transactions/{id}",
+            "/api/v1/ws/blocks",
+        ] {
+            assert!(openapi.paths.paths.contains_key(path), "{path} is missing");
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn test_routes() -> Result<(), StoreError> {
+        let store = test_store().await?;
+        let (block, transactions) = insert_block(&store).await?;
+        let height = block.height.0;
+        let (live, _) = broadcast::channel(1);
+        let router = router(AppState { store, live });
+
+        let (status, body) = get_json(&router, &format!("/api/v1/blocks/{height}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::to_value(&block).unwrap());
+
+        let (status, body) =
+            get_json(&router, &format!("/api/v1/blocks/id/{}", block.id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], json!(block.id));
+
+        let uri = format!("/api/v1/blocks/{height}/transactions?limit=1");
+        let (status, body) = get_json(&router, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"][0]["id"], json!(transactions[0].id));
+        assert_eq!(body["next_cursor"], json!(0));
+
+        let uri = format!("/api/v1/transactions/{}", transactions[1].id);
+        let (status, body) = get_json(&router, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::to_value(&transactions[1]).unwrap());
+
+        let (status, _) =
+            get_json(&router, &format!("/api/v1/blocks/{}", u32::MAX)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = get_json(&router, "/api/v1/transactions/0x12").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use axum::{
+    Json,
+    extract::{
+        Path,
+        State,
+    },
+};
+use fuel_streams_domains::transactions::Transaction;
+use fuel_streams_types::primitives::TxId;
+use fuel_web_utils::server::{
+    ApiError,
+    ErrorResponse,
+};
+
+use super::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/transactions/{id}",
+    tag = "transactions",
+    params(("id" = String, Path, description = "Hex encoded transaction ID")),
+    responses(
+        (status = 200, body = Transaction),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+pub async fn get_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Transaction>, ApiError> {
+    let id: TxId = id.parse().map_err(ApiError::BadRequest)?;
+    state
+        .store
+        .transaction(&id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Transaction {id} not found")))
+}
@@ -0,0 +1,27 @@
+use axum::{
+    extract::{
+        State,
+        WebSocketUpgrade,
+    },
+    response::Response,
+};
+use fuel_web_utils::server::forward_broadcast;
+
+use super::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/ws/blocks",
+    tag = "blocks",
+    responses(
+        (status = 101, description = "Switches to a WebSocket sending every new block as a JSON text message"),
+    )
+)]
+pub async fn subscribe_blocks(
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    // Subscribe before the upgrade, so no block is missed in between
+    let receiver = state.live.subscribe();
+    ws.on_upgrade(move |socket| forward_broadcast(socket, receiver))
+}
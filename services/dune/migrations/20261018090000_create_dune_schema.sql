@@ -0,0 +1,8 @@
+CREATE SCHEMA IF NOT EXISTS dune;
+
+-- Last block height written by each exporter
+CREATE TABLE IF NOT EXISTS dune.checkpoints (
+    "name" TEXT PRIMARY KEY,
+    "block_height" BIGINT NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS dune.blocks (
+    "block_height" BIGINT PRIMARY KEY,
+    "block_time" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "id" BYTEA NOT NULL,
+    "version" TEXT NOT NULL,
+    "producer" BYTEA NOT NULL,
+    "transactions_count" INTEGER NOT NULL,
+    "transactions_root" BYTEA NOT NULL,
+    "application_hash" BYTEA NOT NULL,
+    "consensus_parameters_version" BIGINT NOT NULL,
+    "da_height" NUMERIC(20, 0) NOT NULL,
+    "event_inbox_root" BYTEA NOT NULL,
+    "message_outbox_root" BYTEA NOT NULL,
+    "message_receipt_count" BIGINT NOT NULL,
+    "prev_root" BYTEA NOT NULL,
+    "state_transition_bytecode_version" BIGINT NOT NULL,
+    "consensus_type" TEXT NOT NULL,
+    "poa_signature" BYTEA
+);
+
+CREATE INDEX IF NOT EXISTS idx_dune_blocks_block_time ON dune.blocks ("block_time");
+CREATE INDEX IF NOT EXISTS idx_dune_blocks_id ON dune.blocks ("id");
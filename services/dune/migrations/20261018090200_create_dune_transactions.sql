@@ -0,0 +1,35 @@
+CREATE TABLE IF NOT EXISTS dune.transactions (
+    "block_height" BIGINT NOT NULL,
+    "tx_index" INTEGER NOT NULL,
+    "block_time" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "id" BYTEA NOT NULL,
+    "type" TEXT NOT NULL,
+    "status" TEXT NOT NULL,
+    "script_gas_limit" NUMERIC(20, 0),
+    "maturity" BIGINT,
+    "max_fee" NUMERIC(20, 0),
+    "tip" NUMERIC(20, 0),
+    "witness_limit" NUMERIC(20, 0),
+    "mint_amount" NUMERIC(20, 0),
+    "mint_asset_id" BYTEA,
+    "mint_gas_price" NUMERIC(20, 0),
+    "bytecode_root" BYTEA,
+    "bytecode_witness_index" INTEGER,
+    "blob_id" BYTEA,
+    "salt" BYTEA,
+    "receipts_root" BYTEA,
+    "subsection_index" INTEGER,
+    "subsections_number" INTEGER,
+    "script" BYTEA,
+    "script_data" BYTEA,
+    "inputs_count" INTEGER NOT NULL,
+    "outputs_count" INTEGER NOT NULL,
+    "witnesses_count" INTEGER NOT NULL,
+    "storage_slots_count" INTEGER NOT NULL,
+    -- Canonical encoding of the transaction, including the fields above
+    "raw_payload" BYTEA NOT NULL,
+    PRIMARY KEY ("block_height", "tx_index")
+);
+
+CREATE INDEX IF NOT EXISTS idx_dune_transactions_id ON dune.transactions ("id");
+CREATE INDEX IF NOT EXISTS idx_dune_transactions_block_time ON dune.transactions ("block_time");
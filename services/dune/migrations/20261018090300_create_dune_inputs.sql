@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS dune.inputs (
+    "block_height" BIGINT NOT NULL,
+    "tx_index" INTEGER NOT NULL,
+    "input_index" INTEGER NOT NULL,
+    "tx_id" BYTEA NOT NULL,
+    "input_type" TEXT NOT NULL,
+    "utxo_id" TEXT,
+    "owner" BYTEA,
+    "asset_id" BYTEA,
+    "amount" NUMERIC(20, 0),
+    "contract_id" BYTEA,
+    "balance_root" BYTEA,
+    "state_root" BYTEA,
+    "sender" BYTEA,
+    "recipient" BYTEA,
+    "nonce" BYTEA,
+    "data" BYTEA,
+    "predicate" BYTEA,
+    "predicate_data" BYTEA,
+    "predicate_gas_used" NUMERIC(20, 0),
+    "witness_index" INTEGER,
+    PRIMARY KEY ("block_height", "tx_index", "input_index")
+);
+
+CREATE INDEX IF NOT EXISTS idx_dune_inputs_owner ON dune.inputs ("owner");
+CREATE INDEX IF NOT EXISTS idx_dune_inputs_contract_id ON dune.inputs ("contract_id");
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS dune.outputs (
+    "block_height" BIGINT NOT NULL,
+    "tx_index" INTEGER NOT NULL,
+    "output_index" INTEGER NOT NULL,
+    "tx_id" BYTEA NOT NULL,
+    "output_type" TEXT NOT NULL,
+    "recipient" BYTEA,
+    "asset_id" BYTEA,
+    "amount" NUMERIC(20, 0),
+    "contract_id" BYTEA,
+    "input_index" INTEGER,
+    "balance_root" BYTEA,
+    "state_root" BYTEA,
+    PRIMARY KEY ("block_height", "tx_index", "output_index")
+);
+
+CREATE INDEX IF NOT EXISTS idx_dune_outputs_recipient ON dune.outputs ("recipient");
@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS dune.receipts (
+    "block_height" BIGINT NOT NULL,
+    "tx_index" INTEGER NOT NULL,
+    "receipt_index" INTEGER NOT NULL,
+    "tx_id" BYTEA NOT NULL,
+    "receipt_type" TEXT NOT NULL,
+    -- Contract that emitted the receipt
+    "contract_id" BYTEA,
+    "to_contract" BYTEA,
+    "to_address" TEXT,
+    "amount" NUMERIC(20, 0),
+    "asset_id" BYTEA,
+    "gas" NUMERIC(20, 0),
+    "gas_used" NUMERIC(20, 0),
+    "param1" NUMERIC(20, 0),
+    "param2" NUMERIC(20, 0),
+    -- Value of the $is register
+    "instruction_start" NUMERIC(20, 0),
+    "ptr" NUMERIC(20, 0),
+    "len" NUMERIC(20, 0),
+    "val" NUMERIC(20, 0),
+    "ra" NUMERIC(20, 0),
+    "rb" NUMERIC(20, 0),
+    "rc" NUMERIC(20, 0),
+    "rd" NUMERIC(20, 0),
+    "digest" BYTEA,
+    "data" TEXT,
+    "result" NUMERIC(20, 0),
+    "panic_reason" SMALLINT,
+    "panic_instruction" BIGINT,
+    "sub_id" BYTEA,
+    "sender" BYTEA,
+    "recipient" BYTEA,
+    "nonce" BYTEA,
+    PRIMARY KEY ("block_height", "tx_index", "receipt_index")
+);
+
+CREATE INDEX IF NOT EXISTS idx_dune_receipts_contract_id ON dune.receipts ("contract_id");
+CREATE INDEX IF NOT EXISTS idx_dune_receipts_receipt_type ON dune.receipts ("receipt_type");
@@ -0,0 +1,295 @@
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use fuel_streams_domains::receipts::{
+    CallReceipt,
+    Receipt,
+};
+use fuel_streams_types::ContractId;
+use serde_json::{
+    Map,
+    Value,
+};
+
+use super::{
+    AbiDecodeError,
+    AbiRegistry,
+    ProgramAbi,
+    decode_args,
+};
+
+/// A call to a contract decoded with its ABI
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedCall {
+    /// Name of the called function, `None` when its selector wasn't found
+    pub function: Option<String>,
+    /// Arguments keyed by name
+    pub arguments: Result<Value, AbiDecodeError>,
+}
+
+impl DecodedCall {
+    fn not_found() -> Self {
+        Self {
+            function: None,
+            arguments: Err(AbiDecodeError::SelectorNotFound),
+        }
+    }
+}
+
+/// Selector of a function in the v1 encoding: its name encoded as a `str`
+fn encode_selector(name: &str) -> Vec<u8> {
+    let mut selector = (name.len() as u64).to_be_bytes().to_vec();
+    selector.extend(name.as_bytes());
+    selector
+}
+
+impl AbiRegistry {
+    /// Decodes the `Call` receipts of a transaction to contracts with an ABI,
+    /// paired with their receipt index.
+    ///
+    /// `param1` of a call made by the script is the address of the function
+    /// selector in the script data and `param2` that of the arguments. Calls
+    /// made by a contract, whose receipt has a non-zero `id`, point to its own
+    /// memory, so their function stays unknown.
+    pub fn decode_calls<'a>(
+        &self,
+        script_data: Option<&[u8]>,
+        receipts: &'a [Receipt],
+    ) -> Vec<(usize, &'a CallReceipt, DecodedCall)> {
+        let calls: Vec<_> = receipts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, receipt)| match receipt {
+                Receipt::Call(call) => Some((index, call, self.get(&call.to)?)),
+                _ => None,
+            })
+            .collect();
+        let script_data = script_data.unwrap_or_default();
+        let address = script_data_address(script_data, &calls);
+
+        calls
+            .into_iter()
+            .map(|(index, call, abi)| {
+                let decoded = match address {
+                    Some(address) if is_from_script(call) => abi.decode_call(
+                        script_data,
+                        address,
+                        call.param1.0,
+                        call.param2.0,
+                    ),
+                    _ => DecodedCall::not_found(),
+                };
+                (index, call, decoded)
+            })
+            .collect()
+    }
+}
+
+fn is_from_script(call: &CallReceipt) -> bool {
+    call.id == ContractId::zeroed()
+}
+
+/// Memory address of the script data, which depends on the consensus
+/// parameters and the layout of the transaction.
+///
+/// Every selector of the called contract found in the script data gives a
+/// candidate address for a call made by the script. The address that places
+/// the most calls on a selector is kept, and a tie leaves it unknown.
+fn script_data_address(
+    script_data: &[u8],
+    calls: &[(usize, &CallReceipt, &ProgramAbi)],
+) -> Option<u64> {
+    let mut votes: HashMap<u64, usize> = HashMap::new();
+    for (_, call, abi) in calls.iter().filter(|(_, call, _)| is_from_script(call)) {
+        let mut candidates = HashSet::new();
+        for name in abi.functions.keys() {
+            let selector = encode_selector(name);
+            let positions = script_data
+                .windows(selector.len())
+                .enumerate()
+                .filter(|(_, window)| *window == selector.as_slice());
+            for (position, _) in positions {
+                if let Some(address) = call.param1.0.checked_sub(position as u64) {
+                    candidates.insert(address);
+                }
+            }
+        }
+        for address in candidates {
+            *votes.entry(address).or_default() += 1;
+        }
+    }
+
+    let most = votes.values().max()?;
+    let mut best = votes.iter().filter(|(_, count)| *count == most);
+    let (address, _) = best.next()?;
+    best.next().is_none().then_some(*address)
+}
+
+/// Offset in the script data of the memory address `address`
+fn offset(script_data: &[u8], base: u64, address: u64) -> Option<usize> {
+    let offset = usize::try_from(address.checked_sub(base)?).ok()?;
+    (offset < script_data.len()).then_some(offset)
+}
+
+/// Reads the function name encoded at `offset`
+fn read_selector(script_data: &[u8], offset: usize) -> Option<String> {
+    let len = script_data.get(offset..offset.checked_add(8)?)?;
+    let len = usize::try_from(u64::from_be_bytes(len.try_into().ok()?)).ok()?;
+    let start = offset + 8;
+    let name = script_data.get(start..start.checked_add(len)?)?;
+    String::from_utf8(name.to_vec()).ok()
+}
+
+impl ProgramAbi {
+    /// Decodes a call whose selector is at `param1` and arguments at `param2`,
+    /// with the script data at the memory address `base`
+    fn decode_call(
+        &self,
+        script_data: &[u8],
+        base: u64,
+        param1: u64,
+        param2: u64,
+    ) -> DecodedCall {
+        let Some(name) = offset(script_data, base, param1)
+            .and_then(|offset| read_selector(script_data, offset))
+        else {
+            return DecodedCall::not_found();
+        };
+        let arguments = match self.functions.get(&name) {
+            None => Err(AbiDecodeError::UnknownFunction(name.clone())),
+            Some(Err(reason)) => Err(AbiDecodeError::UnsupportedType(reason.clone())),
+            // The arguments of a function without any may point anywhere
+            Some(Ok(inputs)) if inputs.is_empty() => Ok(Value::Object(Map::new())),
+            Some(Ok(inputs)) => match offset(script_data, base, param2) {
+                Some(offset) => decode_args(inputs, &script_data[offset..]),
+                None => Err(AbiDecodeError::ArgumentsNotFound),
+            },
+        };
+        DecodedCall {
+            function: Some(name),
+            arguments,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_streams_domains::mocks::MockReceipt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    const BASE: u64 = 10_240;
+
+    fn abi() -> ProgramAbi {
+        let json = json!({
+            "encodingVersion": "1",
+            "concreteTypes": [
+                { "type": "b256", "concreteTypeId": "aa" },
+                { "type": "u64", "concreteTypeId": "bb" },
+                { "type": "()", "concreteTypeId": "cc" }
+            ],
+            "functions": [
+                {
+                    "name": "transfer",
+                    "inputs": [
+                        { "name": "to", "concreteTypeId": "aa" },
+                        { "name": "amount", "concreteTypeId": "bb" }
+                    ],
+                    "output": "cc"
+                },
+                { "name": "pause", "inputs": [], "output": "cc" }
+            ]
+        });
+        ProgramAbi::from_json(json.to_string().as_bytes()).unwrap()
+    }
+
+    fn call(to: &ContractId, param1: u64, param2: u64) -> Receipt {
+        let Receipt::Call(mut call) = MockReceipt::call() else {
+            unreachable!()
+        };
+        call.id = ContractId::zeroed();
+        call.to = to.clone();
+        call.param1 = param1.into();
+        call.param2 = param2.into();
+        Receipt::Call(call)
+    }
+
+    #[test]
+    fn test_decode_calls() {
+        let contract_id = ContractId::from([1u8; 32]);
+        let mut abis = AbiRegistry::default();
+        abis.insert(contract_id.clone(), abi());
+
+        // Call data of the SDK scripts: a header, then the selector and the
+        // arguments of each call
+        let mut script_data = vec![0u8; 16];
+        let transfer = script_data.len() as u64;
+        script_data.extend(encode_selector("transfer"));
+        let transfer_args = script_data.len() as u64;
+        script_data.extend([0xcd; 32]);
+        script_data.extend(9u64.to_be_bytes());
+        let pause = script_data.len() as u64;
+        script_data.extend(encode_selector("pause"));
+        let unknown = script_data.len() as u64;
+        script_data.extend(encode_selector("mint"));
+
+        let receipts = vec![
+            call(&contract_id, BASE + transfer, BASE + transfer_args),
+            MockReceipt::log_data(),
+            call(&contract_id, BASE + pause, 0),
+            // A contract without an ABI
+            call(&ContractId::from([2u8; 32]), BASE + pause, 0),
+            call(&contract_id, BASE + unknown, 0),
+            // A call made by a contract, from its own memory
+            Receipt::Call(CallReceipt {
+                id: ContractId::from([3u8; 32]),
+                ..call(&contract_id, BASE + transfer, BASE + transfer_args).as_call()
+            }),
+        ];
+        let decoded = abis.decode_calls(Some(&script_data), &receipts);
+        let indexes: Vec<_> = decoded.iter().map(|(index, ..)| *index).collect();
+        assert_eq!(indexes, vec![0, 2, 4, 5]);
+
+        let calls: Vec<_> = decoded.into_iter().map(|(.., call)| call).collect();
+        assert_eq!(calls[0].function.as_deref(), Some("transfer"));
+        assert_eq!(
+            calls[0].arguments,
+            Ok(json!({ "to": format!("0x{}", "cd".repeat(32)), "amount": 9 }))
+        );
+        assert_eq!(calls[1].function.as_deref(), Some("pause"));
+        assert_eq!(calls[1].arguments, Ok(json!({})));
+        assert_eq!(calls[2].function.as_deref(), Some("mint"));
+        assert_eq!(
+            calls[2].arguments,
+            Err(AbiDecodeError::UnknownFunction("mint".to_string()))
+        );
+        assert_eq!(calls[3], DecodedCall::not_found());
+
+        // Without script data no selector can be found
+        let decoded = abis.decode_calls(None, &receipts);
+        assert!(
+            decoded
+                .iter()
+                .all(|(.., call)| *call == DecodedCall::not_found())
+        );
+    }
+
+    #[test]
+    fn test_script_data_address_tie() {
+        let contract_id = ContractId::from([1u8; 32]);
+        let mut abis = AbiRegistry::default();
+        abis.insert(contract_id.clone(), abi());
+
+        // A single call with its selector twice in the script data can't
+        // tell where the script data is
+        let mut script_data = encode_selector("pause");
+        script_data.extend(encode_selector("pause"));
+        let receipts = vec![call(&contract_id, BASE, 0)];
+        let decoded = abis.decode_calls(Some(&script_data), &receipts);
+        assert_eq!(decoded[0].2, DecodedCall::not_found());
+    }
+}
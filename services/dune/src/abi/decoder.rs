@@ -0,0 +1,296 @@
+use serde_json::{
+    Map,
+    Value,
+};
+
+use super::AbiType;
+use crate::verify::hex;
+
+/// Why a payload could not be decoded into its ABI type
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AbiDecodeError {
+    #[error("unknown log id {0}")]
+    UnknownLogId(u64),
+    #[error("unsupported logged type: {0}")]
+    UnsupportedType(String),
+    #[error("receipt has no data")]
+    MissingData,
+    #[error("unexpected end of data at byte {0}")]
+    UnexpectedEnd(usize),
+    #[error("{0} trailing bytes")]
+    TrailingBytes(usize),
+    #[error("invalid bool {0}")]
+    InvalidBool(u8),
+    #[error("invalid utf-8 string at byte {0}")]
+    InvalidUtf8(usize),
+    #[error("unknown variant {variant} of enum {name}")]
+    UnknownVariant { name: String, variant: u64 },
+    #[error("function selector not found in the script data")]
+    SelectorNotFound,
+    #[error("unknown function {0}")]
+    UnknownFunction(String),
+    #[error("call arguments not found in the script data")]
+    ArgumentsNotFound,
+}
+
+/// Decodes `data`, a value encoded with the v1 encoding of Sway, into JSON.
+///
+/// Integers are big-endian and the length of dynamic types is a leading
+/// `u64`. Integers up to `u64` become JSON numbers, `u256`, `b256` and byte
+/// sequences `0x` hex strings, structs objects keyed by field. Enums follow
+/// the externally tagged form of serde: the name of a unit variant, otherwise
+/// an object with the variant as its only key.
+pub fn decode(ty: &AbiType, data: &[u8]) -> Result<Value, AbiDecodeError> {
+    let mut reader = Reader { data, pos: 0 };
+    let value = reader.value(ty)?;
+    match data.len() - reader.pos {
+        0 => Ok(value),
+        trailing => Err(AbiDecodeError::TrailingBytes(trailing)),
+    }
+}
+
+/// Decodes the arguments of a call into an object keyed by argument name.
+///
+/// The arguments are read from the start of `data`, which may go on with the
+/// data of other calls.
+pub fn decode_args(
+    inputs: &[(String, AbiType)],
+    data: &[u8],
+) -> Result<Value, AbiDecodeError> {
+    let mut reader = Reader { data, pos: 0 };
+    let mut object = Map::new();
+    for (name, ty) in inputs {
+        object.insert(name.clone(), reader.value(ty)?);
+    }
+    Ok(Value::Object(object))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AbiDecodeError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(AbiDecodeError::UnexpectedEnd(self.pos))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, AbiDecodeError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_be_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
+    /// Length prefix of a dynamic type, which cannot exceed the data left
+    fn length_prefix(&mut self) -> Result<usize, AbiDecodeError> {
+        let start = self.pos;
+        let len = self.u64()?;
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.data.len() - self.pos)
+            .ok_or(AbiDecodeError::UnexpectedEnd(start))
+    }
+
+    fn str(&mut self, len: usize) -> Result<Value, AbiDecodeError> {
+        let start = self.pos;
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes)
+            .map(|s| Value::String(s.to_string()))
+            .map_err(|_| AbiDecodeError::InvalidUtf8(start))
+    }
+
+    fn values(&mut self, ty: &AbiType, len: usize) -> Result<Value, AbiDecodeError> {
+        // Not trusting `len` for the allocation, which the data may not back
+        let mut values = Vec::with_capacity(len.min(self.data.len() - self.pos));
+        for _ in 0..len {
+            values.push(self.value(ty)?);
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn value(&mut self, ty: &AbiType) -> Result<Value, AbiDecodeError> {
+        let value = match ty {
+            AbiType::Unit => Value::Null,
+            AbiType::Bool => match self.take(1)?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                other => return Err(AbiDecodeError::InvalidBool(other)),
+            },
+            AbiType::U8 => self.take(1)?[0].into(),
+            AbiType::U16 => {
+                u16::from_be_bytes(self.take(2)?.try_into().expect("2 bytes")).into()
+            }
+            AbiType::U32 => {
+                u32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes")).into()
+            }
+            AbiType::U64 => self.u64()?.into(),
+            AbiType::U256 | AbiType::B256 => Value::String(hex(self.take(32)?)),
+            AbiType::StrArray(len) => self.str(*len)?,
+            AbiType::Str | AbiType::String => {
+                let len = self.length_prefix()?;
+                self.str(len)?
+            }
+            AbiType::RawSlice | AbiType::Bytes => {
+                let len = self.length_prefix()?;
+                Value::String(hex(self.take(len)?))
+            }
+            AbiType::Vec(element) => {
+                let len = self.length_prefix()?;
+                self.values(element, len)?
+            }
+            AbiType::Array(element, len) => self.values(element, *len)?,
+            AbiType::Tuple(elements) => Value::Array(
+                elements
+                    .iter()
+                    .map(|element| self.value(element))
+                    .collect::<Result<_, _>>()?,
+            ),
+            AbiType::Struct { fields, .. } => {
+                let mut object = Map::new();
+                for (name, ty) in fields {
+                    object.insert(name.clone(), self.value(ty)?);
+                }
+                Value::Object(object)
+            }
+            AbiType::Enum { name, variants } => {
+                let variant = self.u64()?;
+                let (variant_name, ty) = usize::try_from(variant)
+                    .ok()
+                    .and_then(|index| variants.get(index))
+                    .ok_or_else(|| AbiDecodeError::UnknownVariant {
+                        name: name.clone(),
+                        variant,
+                    })?;
+                match ty {
+                    AbiType::Unit => Value::String(variant_name.clone()),
+                    ty => {
+                        let mut object = Map::new();
+                        object.insert(variant_name.clone(), self.value(ty)?);
+                        Value::Object(object)
+                    }
+                }
+            }
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn word(value: u64) -> Vec<u8> {
+        value.to_be_bytes().to_vec()
+    }
+
+    #[test]
+    fn test_decode_struct() {
+        let ty = AbiType::Struct {
+            name: "events::Deposit".to_string(),
+            fields: vec![
+                ("amount".to_string(), AbiType::U64),
+                ("flag".to_string(), AbiType::Bool),
+                ("tag".to_string(), AbiType::StrArray(3)),
+                ("items".to_string(), AbiType::Vec(Box::new(AbiType::U16))),
+                ("memo".to_string(), AbiType::Bytes),
+            ],
+        };
+        let mut data = word(u64::MAX);
+        data.push(1);
+        data.extend(b"abc");
+        data.extend(word(2));
+        data.extend([0, 7, 1, 0]);
+        data.extend(word(2));
+        data.extend([0xbe, 0xef]);
+
+        assert_eq!(
+            decode(&ty, &data).unwrap(),
+            json!({
+                "amount": u64::MAX,
+                "flag": true,
+                "tag": "abc",
+                "items": [7, 256],
+                "memo": "0xbeef",
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_enum() {
+        let ty = AbiType::Enum {
+            name: "std::option::Option".to_string(),
+            variants: vec![
+                ("None".to_string(), AbiType::Unit),
+                ("Some".to_string(), AbiType::U8),
+            ],
+        };
+        assert_eq!(decode(&ty, &word(0)).unwrap(), json!("None"));
+        let mut some = word(1);
+        some.push(42);
+        assert_eq!(decode(&ty, &some).unwrap(), json!({ "Some": 42 }));
+        assert_eq!(
+            decode(&ty, &word(2)),
+            Err(AbiDecodeError::UnknownVariant {
+                name: "std::option::Option".to_string(),
+                variant: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_args() {
+        let inputs = vec![
+            ("to".to_string(), AbiType::B256),
+            ("amount".to_string(), AbiType::U64),
+        ];
+        let mut data = vec![0xab; 32];
+        data.extend(word(5));
+        // The data of the next call is left alone
+        data.extend(word(6));
+        assert_eq!(
+            decode_args(&inputs, &data).unwrap(),
+            json!({ "to": format!("0x{}", "ab".repeat(32)), "amount": 5 })
+        );
+        assert_eq!(
+            decode_args(&inputs, &data[..36]),
+            Err(AbiDecodeError::UnexpectedEnd(32))
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode(&AbiType::U64, &[0; 4]),
+            Err(AbiDecodeError::UnexpectedEnd(0))
+        );
+        assert_eq!(
+            decode(&AbiType::U8, &[1, 2]),
+            Err(AbiDecodeError::TrailingBytes(1))
+        );
+        assert_eq!(
+            decode(&AbiType::Bool, &[2]),
+            Err(AbiDecodeError::InvalidBool(2))
+        );
+        // A length beyond the data is rejected before allocating
+        let vec = AbiType::Vec(Box::new(AbiType::U64));
+        assert_eq!(
+            decode(&vec, &word(u64::MAX)),
+            Err(AbiDecodeError::UnexpectedEnd(0))
+        );
+        let mut string = word(1);
+        string.push(0xff);
+        assert_eq!(
+            decode(&AbiType::String, &string),
+            Err(AbiDecodeError::InvalidUtf8(8))
+        );
+    }
+}
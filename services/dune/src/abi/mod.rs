@@ -0,0 +1,332 @@
+//! Decoding of the logs of Sway programs with their JSON ABI.
+//!
+//! The ABIs are read on startup from a directory holding one
+//! `<contract id>.json` file per contract, as written by `forc build`. Only
+//! ABIs of the v1 encoding, the default since forc 0.60, are supported. A
+//! `LogData` receipt of a contract with a known ABI is decoded with the type
+//! its ABI logs under the receipt's log id (`rb`), and a `Call` receipt to it
+//! with the function whose selector the call points to.
+
+mod calls;
+mod decoder;
+mod types;
+
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+pub use calls::*;
+pub use decoder::*;
+use fuel_streams_types::ContractId;
+use serde_json::Value;
+pub use types::AbiType;
+use types::{
+    AbiFile,
+    TypeResolver,
+    type_display_name,
+};
+
+/// ABI loading error types.
+#[derive(Debug, thiserror::Error)]
+pub enum AbiError {
+    #[error("Failed to read ABI {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid ABI {path}: {reason}")]
+    Invalid { path: PathBuf, reason: String },
+}
+
+/// Type logged under a log id
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LoggedType {
+    name: String,
+    /// Why the type could not be resolved, e.g. an unsupported type
+    ty: Result<AbiType, String>,
+}
+
+/// Arguments of a function, with the reason they could not be resolved
+type FunctionInputs = Result<Vec<(String, AbiType)>, String>;
+
+/// Logged types and functions of one program
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgramAbi {
+    logged_types: HashMap<u64, LoggedType>,
+    functions: HashMap<String, FunctionInputs>,
+}
+
+impl ProgramAbi {
+    /// Parses an ABI in the JSON ABI format
+    pub fn from_json(json: &[u8]) -> Result<Self, String> {
+        let abi: AbiFile = serde_json::from_slice(json).map_err(|e| e.to_string())?;
+        match abi.encoding_version.as_deref() {
+            Some("1") => {}
+            Some(version) => {
+                return Err(format!("unsupported encoding version {version}"));
+            }
+            None => return Err("missing encoding version".to_string()),
+        }
+
+        let resolver = TypeResolver::new(&abi);
+        let mut logged_types = HashMap::new();
+        for logged in &abi.logged_types {
+            let log_id = logged
+                .log_id
+                .parse()
+                .map_err(|_| format!("invalid log id {}", logged.log_id))?;
+            let name = abi
+                .concrete_types
+                .iter()
+                .find(|ty| ty.concrete_type_id == logged.concrete_type_id)
+                .map(|ty| type_display_name(&ty.type_name))
+                .ok_or_else(|| {
+                    format!("unknown concrete type {}", logged.concrete_type_id)
+                })?;
+            let ty = resolver.concrete(&logged.concrete_type_id);
+            logged_types.insert(log_id, LoggedType { name, ty });
+        }
+        let functions = abi
+            .functions
+            .iter()
+            .map(|function| {
+                let inputs = function
+                    .inputs
+                    .iter()
+                    .map(|input| {
+                        let ty = resolver.concrete(&input.concrete_type_id)?;
+                        Ok((input.name.clone(), ty))
+                    })
+                    .collect();
+                (function.name.clone(), inputs)
+            })
+            .collect();
+        Ok(Self {
+            logged_types,
+            functions,
+        })
+    }
+
+    /// Decodes the data of a log with id `log_id`
+    pub fn decode_log(&self, log_id: u64, data: Option<&[u8]>) -> DecodedLog {
+        let Some(logged) = self.logged_types.get(&log_id) else {
+            return DecodedLog {
+                event_type: None,
+                value: Err(AbiDecodeError::UnknownLogId(log_id)),
+            };
+        };
+        let value = match (&logged.ty, data) {
+            (Err(reason), _) => Err(AbiDecodeError::UnsupportedType(reason.clone())),
+            (Ok(_), None) => Err(AbiDecodeError::MissingData),
+            (Ok(ty), Some(data)) => decode(ty, data),
+        };
+        DecodedLog {
+            event_type: Some(logged.name.clone()),
+            value,
+        }
+    }
+}
+
+/// A log decoded with the ABI of its contract
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedLog {
+    /// Name of the logged type, `None` for an unknown log id
+    pub event_type: Option<String>,
+    pub value: Result<Value, AbiDecodeError>,
+}
+
+/// ABIs of the contracts whose logs are decoded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AbiRegistry {
+    programs: HashMap<ContractId, ProgramAbi>,
+}
+
+impl AbiRegistry {
+    /// Loads every `<contract id>.json` file of `dir`
+    pub fn load_dir(dir: &Path) -> Result<Self, AbiError> {
+        let io_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| AbiError::Io { path, source }
+        };
+        let mut registry = Self::default();
+        for entry in std::fs::read_dir(dir).map_err(io_error(dir))? {
+            let path = entry.map_err(io_error(dir))?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let invalid = |reason: String| AbiError::Invalid {
+                path: path.clone(),
+                reason,
+            };
+            let contract_id: ContractId = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .parse()
+                .map_err(|e| invalid(format!("file name is not a contract id: {e}")))?;
+            let json = std::fs::read(&path).map_err(io_error(&path))?;
+            let abi = ProgramAbi::from_json(&json).map_err(invalid)?;
+            registry.insert(contract_id, abi);
+        }
+        Ok(registry)
+    }
+
+    pub fn insert(&mut self, contract_id: ContractId, abi: ProgramAbi) {
+        self.programs.insert(contract_id, abi);
+    }
+
+    pub fn get(&self, contract_id: &ContractId) -> Option<&ProgramAbi> {
+        self.programs.get(contract_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    /// ABI of a contract logging a generic struct, an enum and a pointer,
+    /// which cannot be logged
+    fn test_abi_json() -> Vec<u8> {
+        json!({
+            "programType": "contract",
+            "specVersion": "1",
+            "encodingVersion": "1",
+            "concreteTypes": [
+                {
+                    "type": "struct events::Deposit<u64>",
+                    "concreteTypeId": "aa",
+                    "metadataTypeId": 0,
+                    "typeArguments": ["cc"]
+                },
+                {
+                    "type": "enum events::Status",
+                    "concreteTypeId": "bb",
+                    "metadataTypeId": 2
+                },
+                { "type": "u64", "concreteTypeId": "cc" },
+                { "type": "()", "concreteTypeId": "()" },
+                { "type": "raw untyped ptr", "concreteTypeId": "dd" }
+            ],
+            "metadataTypes": [
+                {
+                    "type": "struct events::Deposit",
+                    "metadataTypeId": 0,
+                    "components": [
+                        { "name": "amount", "typeId": 1 },
+                        {
+                            "name": "owners",
+                            "typeId": 3,
+                            "typeArguments": [{ "name": "", "typeId": 6 }]
+                        }
+                    ],
+                    "typeParameters": [1]
+                },
+                { "type": "generic T", "metadataTypeId": 1 },
+                {
+                    "type": "enum events::Status",
+                    "metadataTypeId": 2,
+                    "components": [
+                        { "name": "Open", "typeId": "()" },
+                        { "name": "Closed", "typeId": "cc" }
+                    ]
+                },
+                {
+                    "type": "struct std::vec::Vec",
+                    "metadataTypeId": 3,
+                    "components": [
+                        { "name": "buf", "typeId": 5 },
+                        { "name": "len", "typeId": "cc" }
+                    ],
+                    "typeParameters": [4]
+                },
+                { "type": "generic T", "metadataTypeId": 4 },
+                { "type": "struct std::vec::RawVec", "metadataTypeId": 5 },
+                { "type": "b256", "metadataTypeId": 6 }
+            ],
+            "loggedTypes": [
+                { "logId": "100", "concreteTypeId": "aa" },
+                { "logId": "200", "concreteTypeId": "bb" },
+                { "logId": "300", "concreteTypeId": "dd" }
+            ]
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_program_abi_decode_log() {
+        let abi = ProgramAbi::from_json(&test_abi_json()).unwrap();
+
+        let mut data = 7u64.to_be_bytes().to_vec();
+        data.extend(1u64.to_be_bytes());
+        data.extend([0x11; 32]);
+        let decoded = abi.decode_log(100, Some(&data));
+        assert_eq!(decoded.event_type.as_deref(), Some("Deposit<u64>"));
+        assert_eq!(
+            decoded.value.unwrap(),
+            json!({ "amount": 7, "owners": [format!("0x{}", "11".repeat(32))] })
+        );
+
+        let decoded = abi.decode_log(200, Some(&0u64.to_be_bytes()));
+        assert_eq!(decoded.event_type.as_deref(), Some("Status"));
+        assert_eq!(decoded.value.unwrap(), json!("Open"));
+
+        let decoded = abi.decode_log(200, None);
+        assert_eq!(decoded.value, Err(AbiDecodeError::MissingData));
+
+        let decoded = abi.decode_log(300, Some(&[]));
+        assert_eq!(decoded.event_type.as_deref(), Some("raw untyped ptr"));
+        assert!(matches!(
+            decoded.value,
+            Err(AbiDecodeError::UnsupportedType(_))
+        ));
+
+        let decoded = abi.decode_log(1, Some(&[]));
+        assert_eq!(decoded.event_type, None);
+        assert_eq!(decoded.value, Err(AbiDecodeError::UnknownLogId(1)));
+    }
+
+    #[test]
+    fn test_program_abi_rejects_legacy_encoding() {
+        let mut json: Value = serde_json::from_slice(&test_abi_json()).unwrap();
+        json["encodingVersion"] = json!("0");
+        assert!(ProgramAbi::from_json(json.to_string().as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_abi_registry_load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let contract_id = ContractId::from([7u8; 32]);
+        std::fs::write(
+            dir.path().join(format!("{contract_id}.json")),
+            test_abi_json(),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "not an ABI").unwrap();
+
+        let registry = AbiRegistry::load_dir(dir.path()).unwrap();
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get(&contract_id).is_some());
+
+        std::fs::write(dir.path().join("unknown.json"), test_abi_json()).unwrap();
+        assert!(matches!(
+            AbiRegistry::load_dir(dir.path()),
+            Err(AbiError::Invalid { .. })
+        ));
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Deepest type nesting resolved, so a self-referencing ABI cannot overflow
+/// the stack
+const MAX_DEPTH: usize = 64;
+
+/// A Sway program ABI, as written by `forc build` (JSON ABI spec v1)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AbiFile {
+    pub encoding_version: Option<String>,
+    #[serde(default)]
+    pub concrete_types: Vec<ConcreteType>,
+    #[serde(default)]
+    pub metadata_types: Vec<MetadataType>,
+    #[serde(default)]
+    pub logged_types: Vec<LoggedType>,
+    #[serde(default)]
+    pub functions: Vec<Function>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConcreteType {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub concrete_type_id: String,
+    pub metadata_type_id: Option<usize>,
+    #[serde(default)]
+    pub type_arguments: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MetadataType {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub metadata_type_id: usize,
+    #[serde(default)]
+    pub components: Vec<Component>,
+    #[serde(default)]
+    pub type_parameters: Vec<usize>,
+}
+
+/// A struct field, enum variant, tuple or array element, or type argument
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Component {
+    #[serde(default)]
+    pub name: String,
+    pub type_id: TypeRef,
+    #[serde(default)]
+    pub type_arguments: Vec<Component>,
+}
+
+/// Components point to metadata types by index and to concrete types by id
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum TypeRef {
+    Metadata(usize),
+    Concrete(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LoggedType {
+    pub log_id: String,
+    pub concrete_type_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Function {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<FunctionInput>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FunctionInput {
+    pub name: String,
+    pub concrete_type_id: String,
+}
+
+/// A fully resolved ABI type, with its generic parameters substituted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    Unit,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U256,
+    B256,
+    /// `str[N]`
+    StrArray(usize),
+    /// `str`
+    Str,
+    RawSlice,
+    Bytes,
+    String,
+    Vec(Box<AbiType>),
+    Array(Box<AbiType>, usize),
+    Tuple(Vec<AbiType>),
+    Struct {
+        name: String,
+        fields: Vec<(String, AbiType)>,
+    },
+    Enum {
+        name: String,
+        variants: Vec<(String, AbiType)>,
+    },
+}
+
+/// Name of a logged type as written in Sway, without the `struct` or `enum`
+/// keyword and the module path, e.g. `Deposit<u64>` for
+/// `struct events::Deposit<u64>`
+pub(crate) fn type_display_name(type_name: &str) -> String {
+    let name = type_name
+        .strip_prefix("struct ")
+        .or_else(|| type_name.strip_prefix("enum "))
+        .unwrap_or(type_name);
+    let (path, args) = name.split_at(name.find('<').unwrap_or(name.len()));
+    let name = path.rsplit("::").next().unwrap_or(path);
+    format!("{name}{args}")
+}
+
+impl AbiType {
+    /// Parses the primitive types, which have no metadata
+    fn primitive(type_name: &str) -> Option<Self> {
+        let ty = match type_name {
+            "()" => AbiType::Unit,
+            "bool" => AbiType::Bool,
+            "u8" => AbiType::U8,
+            "u16" => AbiType::U16,
+            "u32" => AbiType::U32,
+            "u64" => AbiType::U64,
+            "u256" => AbiType::U256,
+            "b256" => AbiType::B256,
+            "str" => AbiType::Str,
+            "raw untyped slice" => AbiType::RawSlice,
+            "struct std::bytes::Bytes" => AbiType::Bytes,
+            "struct std::string::String" => AbiType::String,
+            _ => {
+                let len = type_name.strip_prefix("str[")?.strip_suffix(']')?;
+                AbiType::StrArray(len.parse().ok()?)
+            }
+        };
+        Some(ty)
+    }
+}
+
+/// Resolves the concrete types of an ABI file into [`AbiType`]s
+pub(crate) struct TypeResolver<'a> {
+    concrete: HashMap<&'a str, &'a ConcreteType>,
+    metadata: HashMap<usize, &'a MetadataType>,
+}
+
+impl<'a> TypeResolver<'a> {
+    pub fn new(abi: &'a AbiFile) -> Self {
+        Self {
+            concrete: abi
+                .concrete_types
+                .iter()
+                .map(|ty| (ty.concrete_type_id.as_str(), ty))
+                .collect(),
+            metadata: abi
+                .metadata_types
+                .iter()
+                .map(|ty| (ty.metadata_type_id, ty))
+                .collect(),
+        }
+    }
+
+    /// Resolves the concrete type with id `id`
+    pub fn concrete(&self, id: &str) -> Result<AbiType, String> {
+        self.resolve_concrete(id, 0)
+    }
+
+    fn resolve_concrete(&self, id: &str, depth: usize) -> Result<AbiType, String> {
+        let depth = Self::deeper(depth)?;
+        let ty = self
+            .concrete
+            .get(id)
+            .ok_or_else(|| format!("unknown concrete type {id}"))?;
+        if let Some(primitive) = AbiType::primitive(&ty.type_name) {
+            return Ok(primitive);
+        }
+        let metadata_id = ty
+            .metadata_type_id
+            .ok_or_else(|| format!("type {} has no metadata", ty.type_name))?;
+        let args = ty
+            .type_arguments
+            .iter()
+            .map(|arg| self.resolve_concrete(arg, depth))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.resolve_metadata(metadata_id, args, depth)
+    }
+
+    /// Resolves a metadata type, binding its type parameters to `args`
+    fn resolve_metadata(
+        &self,
+        id: usize,
+        args: Vec<AbiType>,
+        depth: usize,
+    ) -> Result<AbiType, String> {
+        let depth = Self::deeper(depth)?;
+        let ty = self.metadata(id)?;
+        if let Some(primitive) = AbiType::primitive(&ty.type_name) {
+            return Ok(primitive);
+        }
+        if ty.type_parameters.len() != args.len() {
+            return Err(format!(
+                "type {} takes {} type arguments, got {}",
+                ty.type_name,
+                ty.type_parameters.len(),
+                args.len()
+            ));
+        }
+        let bindings: HashMap<usize, AbiType> =
+            ty.type_parameters.iter().copied().zip(args).collect();
+
+        let type_name = ty.type_name.as_str();
+        if type_name == "struct std::vec::Vec" {
+            let element = ty
+                .type_parameters
+                .first()
+                .and_then(|param| bindings.get(param))
+                .ok_or_else(|| "Vec without an element type".to_string())?;
+            return Ok(AbiType::Vec(Box::new(element.clone())));
+        }
+
+        let components = ty
+            .components
+            .iter()
+            .map(|component| {
+                let resolved = self.resolve_component(component, &bindings, depth)?;
+                Ok((component.name.clone(), resolved))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if let Some(name) = type_name.strip_prefix("struct ") {
+            Ok(AbiType::Struct {
+                name: name.to_string(),
+                fields: components,
+            })
+        } else if let Some(name) = type_name.strip_prefix("enum ") {
+            Ok(AbiType::Enum {
+                name: name.to_string(),
+                variants: components,
+            })
+        } else if type_name.starts_with('(') {
+            Ok(AbiType::Tuple(
+                components.into_iter().map(|(_, ty)| ty).collect(),
+            ))
+        } else if let Some(len) = type_name
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|rest| rest.rsplit_once(';'))
+            .and_then(|(_, len)| len.trim().parse().ok())
+        {
+            let (_, element) = components
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("array {type_name} without an element type"))?;
+            Ok(AbiType::Array(Box::new(element), len))
+        } else {
+            Err(format!("unsupported type {type_name}"))
+        }
+    }
+
+    fn resolve_component(
+        &self,
+        component: &Component,
+        bindings: &HashMap<usize, AbiType>,
+        depth: usize,
+    ) -> Result<AbiType, String> {
+        match &component.type_id {
+            TypeRef::Concrete(id) => self.resolve_concrete(id, depth),
+            TypeRef::Metadata(id) => {
+                if self.metadata(*id)?.type_name.starts_with("generic ") {
+                    return bindings
+                        .get(id)
+                        .cloned()
+                        .ok_or_else(|| format!("unbound generic type {id}"));
+                }
+                let args = component
+                    .type_arguments
+                    .iter()
+                    .map(|arg| self.resolve_component(arg, bindings, depth))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.resolve_metadata(*id, args, depth)
+            }
+        }
+    }
+
+    fn metadata(&self, id: usize) -> Result<&'a MetadataType, String> {
+        self.metadata
+            .get(&id)
+            .copied()
+            .ok_or_else(|| format!("unknown metadata type {id}"))
+    }
+
+    fn deeper(depth: usize) -> Result<usize, String> {
+        if depth >= MAX_DEPTH {
+            return Err(format!("types nested deeper than {MAX_DEPTH} levels"));
+        }
+        Ok(depth + 1)
+    }
+}
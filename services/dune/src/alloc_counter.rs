@@ -31,6 +31,7 @@ define_counters! {
     BLOCK_STREAM,
     AVRO_FILE_WRITERS,
     AVRO_FILE_WRITER,
+    PARQUET_FILE_WRITER,
     FINALIZED_BATCH_FILES,
 }
 
@@ -0,0 +1,156 @@
+//! Running supply of the native assets minted and burned by contracts.
+//!
+//! The `asset_supplies` dimension carries the cumulative supply of every asset
+//! changed by a batch, so the supply of each asset is kept across batches and
+//! restarts. It is saved next to the latest block height after every uploaded
+//! batch and only advanced by blocks above the height it was saved at, so the
+//! blocks replayed after a reconnect or a restart are never counted twice.
+
+use std::collections::HashMap;
+
+use fuel_streams_domains::assets::AssetEvent;
+use fuel_streams_types::{
+    AssetId,
+    BlockHeight,
+    Bytes32,
+    ContractId,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// Supply of one asset since the first block the exporter saw it in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetSupply {
+    pub asset_id: AssetId,
+    pub contract_id: ContractId,
+    pub sub_id: Bytes32,
+    /// Height of the first mint, `None` when only burns were seen
+    pub first_mint_height: Option<u32>,
+    /// Minted minus burned amount. Negative when the export started after
+    /// the asset was minted.
+    pub supply: i128,
+}
+
+/// Supplies of every asset seen up to a block height
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetSupplies {
+    height: Option<BlockHeight>,
+    assets: HashMap<AssetId, AssetSupply>,
+}
+
+/// Serialized form, with the assets sorted by id
+#[derive(Serialize, Deserialize)]
+struct AssetSuppliesFile {
+    height: Option<u32>,
+    assets: Vec<AssetSupply>,
+}
+
+impl AssetSupplies {
+    /// Last block whose events were applied
+    pub fn height(&self) -> Option<BlockHeight> {
+        self.height
+    }
+
+    pub fn get(&self, asset_id: &AssetId) -> Option<&AssetSupply> {
+        self.assets.get(asset_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Applies the mints and burns of the block at `height`. Returns false,
+    /// leaving the supplies untouched, for a block at or below the last
+    /// applied one.
+    pub fn apply(&mut self, height: BlockHeight, events: &[AssetEvent]) -> bool {
+        if self.height.is_some_and(|last| height <= last) {
+            return false;
+        }
+        self.height = Some(height);
+
+        for event in events {
+            let supply = self
+                .assets
+                .entry(event.asset_id.clone())
+                .or_insert_with(|| AssetSupply {
+                    asset_id: event.asset_id.clone(),
+                    contract_id: event.contract_id.clone(),
+                    sub_id: event.sub_id.clone(),
+                    first_mint_height: None,
+                    supply: 0,
+                });
+            if event.r#type.is_mint() && supply.first_mint_height.is_none() {
+                supply.first_mint_height = Some(height.0);
+            }
+            supply.supply += event.signed_amount();
+        }
+        true
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        let mut assets: Vec<_> = self.assets.values().cloned().collect();
+        assets.sort_by(|a, b| a.asset_id.0.cmp(&b.asset_id.0));
+        serde_json::to_vec(&AssetSuppliesFile {
+            height: self.height.map(|height| height.0),
+            assets,
+        })
+    }
+
+    pub fn from_json(data: &[u8]) -> serde_json::Result<Self> {
+        let file: AssetSuppliesFile = serde_json::from_slice(data)?;
+        Ok(Self {
+            height: file.height.map(BlockHeight::from),
+            assets: file
+                .assets
+                .into_iter()
+                .map(|supply| (supply.asset_id.clone(), supply))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_streams_domains::{
+        assets::AssetEventType,
+        mocks::{
+            MockReceipt,
+            MockTransaction,
+        },
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_asset_supplies_apply() {
+        let receipts = vec![MockReceipt::mint(), MockReceipt::mint()];
+        let tx = MockTransaction::script(vec![], vec![], receipts);
+        let mints = AssetEvent::from_transaction(0, &tx);
+        let mut burn = mints[0].clone();
+        burn.r#type = AssetEventType::Burn;
+        burn.amount = 30.into();
+
+        let mut supplies = AssetSupplies::default();
+        assert!(supplies.apply(10.into(), &mints));
+        assert!(supplies.apply(11.into(), std::slice::from_ref(&burn)));
+        // Replayed blocks are skipped
+        assert!(!supplies.apply(11.into(), std::slice::from_ref(&burn)));
+
+        assert_eq!(supplies.len(), 2);
+        assert_eq!(supplies.height(), Some(11.into()));
+        let supply = supplies.get(&mints[0].asset_id).unwrap();
+        assert_eq!(supply.first_mint_height, Some(10));
+        assert_eq!(supply.supply, 70);
+
+        let json = supplies.to_json().unwrap();
+        assert_eq!(AssetSupplies::from_json(&json).unwrap(), supplies);
+    }
+}
@@ -1,49 +1,115 @@
 use std::{
+    any::Any,
+    collections::{
+        BTreeMap,
+        HashSet,
+    },
     fs,
     path::{
         Path,
         PathBuf,
     },
+    sync::Arc,
 };
 
-use fuel_streams_types::BlockHeight;
+use chrono::NaiveDate;
+use fuel_streams_types::{
+    AssetId,
+    BlockHeight,
+};
 
 use crate::{
     DuneError,
     DuneResult,
+    abi::AbiRegistry,
     alloc_counter,
+    assets::AssetSupplies,
     helpers::{
         AvroFileWriter,
         AvroParser,
+        FileFormat,
+        ParquetFileWriter,
+        TableCodecs,
+        TableFormats,
+    },
+    predicates::PredicateHashes,
+    s3::{
+        S3TableName,
+        TableRow,
+        with_table_row,
     },
     schemas::{
+        AvroAssetEvent,
+        AvroAssetSupply,
         AvroBlock,
+        AvroBlockStats,
+        AvroContract,
+        AvroDecodedCall,
+        AvroDecodedLog,
+        AvroInput,
+        AvroMessage,
+        AvroOutput,
+        AvroPredicate,
+        AvroPredicateBytecode,
         AvroReceipt,
         AvroTransaction,
+        AvroTransfer,
+        AvroUtxo,
         ReceiptMetadata,
     },
 };
 use fuel_streams_domains::{
+    assets::AssetEvent,
     blocks::Block,
+    messages::Message,
+    predicates::Predicate,
     transactions::Transaction,
 };
 
-/// The result of finalizing a batch to files, containing paths to Avro files.
+/// The result of finalizing a batch to files, containing paths to the table
+/// files in the format configured for each table.
 /// Files are streamed directly to S3 without loading into memory.
 pub struct FinalizedBatchFiles {
     pub first_height: BlockHeight,
     pub last_height: BlockHeight,
-    /// Path to the blocks Avro file
-    pub blocks_path: PathBuf,
-    /// Path to the transactions Avro file  
-    pub transactions_path: PathBuf,
-    /// Path to the receipts Avro file
-    pub receipts_path: PathBuf,
+    /// UTC day of the first block in the batch
+    pub partition_date: NaiveDate,
+    /// File of every table
+    tables: BTreeMap<S3TableName, TableFile>,
+    /// Asset supplies as of the last block, to be saved once the batch is
+    /// uploaded
+    pub asset_supplies: AssetSupplies,
+    /// Hashes of the predicate bytecodes written up to this batch, to be
+    /// saved once the batch is uploaded
+    pub predicate_hashes: PredicateHashes,
     /// Temporary directory containing the files (for cleanup)
     temp_dir: PathBuf,
 }
 
+/// Finalized file of one table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableFile {
+    pub path: PathBuf,
+    /// Number of rows in the file
+    pub rows: u64,
+}
+
 impl FinalizedBatchFiles {
+    /// Files of every table, in [`S3TableName`] order
+    pub fn tables(&self) -> impl Iterator<Item = (S3TableName, &TableFile)> {
+        self.tables.iter().map(|(table, file)| (*table, file))
+    }
+
+    /// Path to the file of `table`
+    pub fn path(&self, table: S3TableName) -> Option<&Path> {
+        self.tables.get(&table).map(|file| file.path.as_path())
+    }
+
+    /// Number of rows in the file of `table`
+    pub fn rows(&self, table: S3TableName) -> u64 {
+        self.tables.get(&table).map(|file| file.rows).unwrap_or(0)
+    }
+
     /// Cleans up all temporary files after upload
     pub fn cleanup(&self) {
         let _ = fs::remove_dir_all(&self.temp_dir);
@@ -58,19 +124,150 @@ impl Drop for FinalizedBatchFiles {
 }
 
 // ============================================================================
-// Avro file writers for disk-based buffering
+// Table file writers for disk-based buffering
 // ============================================================================
 
-/// Paths to finalized Avro files ready for upload.
+/// File writer for one table, in the format configured for that table.
+#[allow(clippy::large_enum_variant)]
+enum TableFileWriter<T> {
+    Avro(AvroFileWriter<T>),
+    Parquet(ParquetFileWriter<T>),
+}
+
+impl<T: TableRow> TableFileWriter<T> {
+    /// Creates the writer of the table of `T` as `{dir}/{table}.{extension}`
+    fn new(dir: &Path, codecs: &TableCodecs, formats: &TableFormats) -> DuneResult<Self> {
+        let table = T::TABLE;
+        let format = formats.for_table(table);
+        let path = dir.join(format!("{table}.{}", format.extension()));
+        let writer = match format {
+            FileFormat::Avro => AvroParser::new(codecs.for_table(table))
+                .file_writer_with_schema(path)
+                .map(Self::Avro)
+                .map_err(DuneError::from),
+            FileFormat::Parquet => ParquetFileWriter::new(path)
+                .map(Self::Parquet)
+                .map_err(DuneError::from),
+        };
+        writer.map_err(|e| {
+            DuneError::Other(anyhow::anyhow!("Failed to create {table} writer: {}", e))
+        })
+    }
+
+    fn append(&mut self, value: &T) -> DuneResult<()> {
+        match self {
+            Self::Avro(writer) => writer.append(value)?,
+            Self::Parquet(writer) => writer.append(value)?,
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> DuneResult<()> {
+        match self {
+            Self::Avro(writer) => writer.flush()?,
+            Self::Parquet(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+
+    fn finalize_path(self) -> DuneResult<PathBuf> {
+        Ok(match self {
+            Self::Avro(writer) => writer.finalize_path()?,
+            Self::Parquet(writer) => writer.finalize_path()?,
+        })
+    }
+}
+
+/// A [`TableFileWriter`] of any table, so the writers of every table can be
+/// kept in one map.
+trait AnyTableWriter: Send {
+    fn flush(&mut self) -> DuneResult<()>;
+
+    fn finalize_path(self: Box<Self>) -> DuneResult<PathBuf>;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: TableRow> AnyTableWriter for TableFileWriter<T> {
+    fn flush(&mut self) -> DuneResult<()> {
+        TableFileWriter::flush(self)
+    }
+
+    fn finalize_path(self: Box<Self>) -> DuneResult<PathBuf> {
+        TableFileWriter::finalize_path(*self)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// File writers of every table, with the number of rows written to each.
+struct TableWriters {
+    writers: BTreeMap<S3TableName, Box<dyn AnyTableWriter>>,
+    rows: BTreeMap<S3TableName, u64>,
+}
+
+impl TableWriters {
+    /// Creates the writer of every table in `dir`
+    fn new(dir: &Path, codecs: &TableCodecs, formats: &TableFormats) -> DuneResult<Self> {
+        let mut writers = BTreeMap::new();
+        for &table in S3TableName::ROW_TABLES {
+            let writer = with_table_row!(table, |Row| {
+                Box::new(TableFileWriter::<Row>::new(dir, codecs, formats)?)
+                    as Box<dyn AnyTableWriter>
+            });
+            writers.extend(writer.map(|writer| (table, writer)));
+        }
+        Ok(Self {
+            writers,
+            rows: BTreeMap::new(),
+        })
+    }
+
+    /// Appends `row` to the file of its table
+    fn append<T: TableRow>(&mut self, row: &T) -> DuneResult<()> {
+        let table = T::TABLE;
+        self.writers
+            .get_mut(&table)
+            .and_then(|writer| writer.as_any_mut().downcast_mut::<TableFileWriter<T>>())
+            .ok_or_else(|| {
+                DuneError::Other(anyhow::anyhow!("{table} writer not available"))
+            })?
+            .append(row)?;
+        *self.rows.entry(table).or_default() += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> DuneResult<()> {
+        for writer in self.writers.values_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Finalizes every writer, returning the file of each table
+    fn finalize(self) -> DuneResult<BTreeMap<S3TableName, TableFile>> {
+        let mut files = BTreeMap::new();
+        for (table, writer) in self.writers {
+            let path = writer.finalize_path()?;
+            let rows = self.rows.get(&table).copied().unwrap_or_default();
+            files.insert(table, TableFile { path, rows });
+        }
+        Ok(files)
+    }
+}
+
+/// Paths to finalized table files ready for upload.
 /// Note: Does NOT implement Drop - ownership of temp_dir is transferred to FinalizedBatchFiles.
 struct FinalizedAvroFiles {
     temp_dir: PathBuf,
-    blocks_path: PathBuf,
-    transactions_path: PathBuf,
-    receipts_path: PathBuf,
+    tables: BTreeMap<S3TableName, TableFile>,
+    asset_supplies: AssetSupplies,
+    predicate_hashes: PredicateHashes,
 }
 
-/// Manages Avro file writers for blocks, transactions, and receipts.
+/// Manages the file writers of every exported table.
 /// Writes directly to disk to avoid memory accumulation.
 ///
 /// Implements Drop to clean up temp directory on error. On success,
@@ -79,9 +276,19 @@ struct AvroFileWriters {
     /// Temp directory path. Set to None after successful finalize_to_paths()
     /// to transfer ownership and prevent cleanup on drop.
     temp_dir: Option<PathBuf>,
-    blocks_writer: Option<AvroFileWriter<AvroBlock>>,
-    transactions_writer: Option<AvroFileWriter<AvroTransaction>>,
-    receipts_writer: Option<AvroFileWriter<AvroReceipt>>,
+    /// Writers of every table. Taken by finalize_to_paths().
+    tables: Option<TableWriters>,
+    /// Hashes of the predicate bytecodes written in this batch or an
+    /// earlier one
+    predicate_hashes: PredicateHashes,
+    /// Supplies including the mints and burns of this batch
+    asset_supplies: AssetSupplies,
+    /// Assets minted or burned in this batch
+    changed_assets: HashSet<AssetId>,
+    /// ABIs of the contracts whose logs and calls are decoded
+    abis: Arc<AbiRegistry>,
+    /// Height and time of the last block of this batch
+    last_block: Option<(i64, i64)>,
 }
 
 impl Drop for AvroFileWriters {
@@ -95,8 +302,8 @@ impl Drop for AvroFileWriters {
 }
 
 impl AvroFileWriters {
-    /// Creates new Avro file writers in a temporary directory
-    fn new() -> DuneResult<Self> {
+    /// Creates new table file writers in a temporary directory
+    fn new(codecs: &TableCodecs, formats: &TableFormats) -> DuneResult<Self> {
         let temp_dir = std::env::temp_dir().join(format!(
             "dune-avro-{}-{}",
             std::process::id(),
@@ -105,17 +312,21 @@ impl AvroFileWriters {
                 .unwrap_or_default()
                 .as_nanos()
         ));
-        Self::with_dir(&temp_dir)
+        Self::with_dir(&temp_dir, codecs, formats)
     }
 
-    /// Creates new Avro file writers in the specified directory.
+    /// Creates new table file writers in the specified directory.
     /// Cleans up the directory if any writer creation fails.
-    fn with_dir(dir: impl AsRef<Path>) -> DuneResult<Self> {
+    fn with_dir(
+        dir: impl AsRef<Path>,
+        codecs: &TableCodecs,
+        formats: &TableFormats,
+    ) -> DuneResult<Self> {
         let temp_dir = dir.as_ref().to_path_buf();
         fs::create_dir_all(&temp_dir)?;
 
         // Use inner function to enable cleanup on any failure after dir creation
-        let result = Self::create_writers(&temp_dir);
+        let result = Self::create_writers(&temp_dir, codecs, formats);
 
         if result.is_err() {
             // Clean up the directory we created
@@ -126,59 +337,39 @@ impl AvroFileWriters {
     }
 
     /// Helper to create all writers. Called by with_dir().
-    fn create_writers(temp_dir: &Path) -> DuneResult<Self> {
-        let parser = AvroParser::default();
-
-        let blocks_writer = parser
-            .file_writer_with_schema(temp_dir.join("blocks.avro"))
-            .map_err(|e| {
-                DuneError::Other(anyhow::anyhow!("Failed to create blocks writer: {}", e))
-            })?;
-        let transactions_writer = parser
-            .file_writer_with_schema(temp_dir.join("transactions.avro"))
-            .map_err(|e| {
-                DuneError::Other(anyhow::anyhow!(
-                    "Failed to create transactions writer: {}",
-                    e
-                ))
-            })?;
-        let receipts_writer = parser
-            .file_writer_with_schema(temp_dir.join("receipts.avro"))
-            .map_err(|e| {
-                DuneError::Other(anyhow::anyhow!(
-                    "Failed to create receipts writer: {}",
-                    e
-                ))
-            })?;
+    fn create_writers(
+        temp_dir: &Path,
+        codecs: &TableCodecs,
+        formats: &TableFormats,
+    ) -> DuneResult<Self> {
+        let tables = TableWriters::new(temp_dir, codecs, formats)?;
 
         alloc_counter::inc(&alloc_counter::AVRO_FILE_WRITERS);
         Ok(Self {
             temp_dir: Some(temp_dir.to_path_buf()),
-            blocks_writer: Some(blocks_writer),
-            transactions_writer: Some(transactions_writer),
-            receipts_writer: Some(receipts_writer),
+            tables: Some(tables),
+            predicate_hashes: PredicateHashes::default(),
+            asset_supplies: AssetSupplies::default(),
+            changed_assets: HashSet::new(),
+            abis: Arc::default(),
+            last_block: None,
         })
     }
 
-    /// Appends block data directly to the Avro writers.
+    /// Appends block data directly to the table writers.
     ///
     /// After writing all data for a block, the writers are flushed to disk
     /// to prevent memory accumulation. Without flushing, the Avro Writer
     /// buffers all data in memory until finalize_to_paths() is called.
     fn append(&mut self, block: &Block, transactions: &[Transaction]) -> DuneResult<()> {
-        let blocks_writer = self.blocks_writer.as_mut().ok_or_else(|| {
-            DuneError::Other(anyhow::anyhow!("blocks_writer not available"))
-        })?;
-        let transactions_writer = self.transactions_writer.as_mut().ok_or_else(|| {
-            DuneError::Other(anyhow::anyhow!("transactions_writer not available"))
-        })?;
-        let receipts_writer = self.receipts_writer.as_mut().ok_or_else(|| {
-            DuneError::Other(anyhow::anyhow!("receipts_writer not available"))
-        })?;
+        let tables = self
+            .tables
+            .as_mut()
+            .ok_or_else(|| DuneError::Other(anyhow::anyhow!("Writers not available")))?;
 
         // Convert and write block
-        let avro_block = AvroBlock::new(block);
-        blocks_writer.append(&avro_block)?;
+        tables.append(&AvroBlock::new(block))?;
+        tables.append(&AvroBlockStats::new(block, transactions))?;
 
         // Convert and write transactions
         for tx in transactions {
@@ -190,7 +381,7 @@ impl AvroFileWriters {
                 Some(block.version.to_string()),
                 Some(block.producer.as_ref().to_vec().into()),
             );
-            transactions_writer.append(&avro_tx)?;
+            tables.append(&avro_tx)?;
         }
 
         // Convert and write receipts
@@ -203,41 +394,107 @@ impl AvroFileWriters {
                 transaction_id: Some(tx.id.clone().into()),
             };
             for receipt in &tx.receipts {
-                let avro_receipt = AvroReceipt::new(receipt, &receipt_metadata);
-                receipts_writer.append(&avro_receipt)?;
+                tables.append(&AvroReceipt::new(receipt, &receipt_metadata))?;
+            }
+        }
+
+        // Convert and write inputs and outputs
+        let mut asset_events = Vec::new();
+        for (tx_index, tx) in transactions.iter().enumerate() {
+            for avro_input in AvroInput::rows(block, tx_index, tx) {
+                tables.append(&avro_input)?;
+            }
+            for avro_output in AvroOutput::rows(block, tx_index, tx) {
+                tables.append(&avro_output)?;
+            }
+            for avro_utxo in AvroUtxo::rows(block, tx_index, tx) {
+                tables.append(&avro_utxo)?;
+            }
+
+            // Each bytecode is written to the dimension once
+            let predicates = Predicate::from_transaction(tx_index as i32, tx);
+            for avro_predicate in AvroPredicate::rows(block, &predicates) {
+                tables.append(&avro_predicate)?;
+            }
+            for predicate in &predicates {
+                if self.predicate_hashes.insert(predicate.bytecode_hash()) {
+                    tables.append(&AvroPredicateBytecode::new(block, predicate))?;
+                }
             }
+            for avro_contract in AvroContract::rows(block, tx_index, tx) {
+                tables.append(&avro_contract)?;
+            }
+            for avro_transfer in AvroTransfer::rows(block, tx_index, tx) {
+                tables.append(&avro_transfer)?;
+            }
+            for avro_log in AvroDecodedLog::rows(block, tx_index, tx, &self.abis) {
+                tables.append(&avro_log)?;
+            }
+            for avro_call in AvroDecodedCall::rows(block, tx_index, tx, &self.abis) {
+                tables.append(&avro_call)?;
+            }
+
+            let events = AssetEvent::from_transaction(tx_index as i32, tx);
+            for avro_event in AvroAssetEvent::rows(block, &events) {
+                tables.append(&avro_event)?;
+            }
+            asset_events.extend(events);
+        }
+
+        // Blocks replayed after a reconnect are already in the supplies
+        if self.asset_supplies.apply(block.height, &asset_events) {
+            self.changed_assets
+                .extend(asset_events.into_iter().map(|event| event.asset_id));
         }
+        self.last_block = Some((
+            block.height.0 as i64,
+            block.header.get_timestamp_utc().timestamp(),
+        ));
 
         // Flush all writers to disk after each block to prevent memory accumulation.
         // The Avro Writer buffers data internally for performance, but without
         // periodic flushing this buffer grows unboundedly until finalize.
-        blocks_writer.flush()?;
-        transactions_writer.flush()?;
-        receipts_writer.flush()?;
+        tables.flush()
+    }
 
-        Ok(())
+    /// Appends the bridge messages of a block to the messages writer and
+    /// flushes it.
+    fn append_messages(&mut self, block: &Block, messages: &[Message]) -> DuneResult<()> {
+        let tables = self
+            .tables
+            .as_mut()
+            .ok_or_else(|| DuneError::Other(anyhow::anyhow!("Writers not available")))?;
+
+        for avro_message in AvroMessage::rows(block, messages) {
+            tables.append(&avro_message)?;
+        }
+        tables.flush()
     }
 
-    /// Finalizes all writers and returns paths to the Avro files.
+    /// Finalizes all writers and returns paths to the table files.
     /// Does NOT load files into memory - use this for large batches.
     ///
     /// On success, ownership of temp_dir is transferred to FinalizedAvroFiles,
     /// and this struct's Drop will not clean up the directory.
     /// On error, Drop will clean up the temp directory.
     fn finalize_to_paths(&mut self) -> DuneResult<FinalizedAvroFiles> {
-        let blocks_writer = self.blocks_writer.take().ok_or_else(|| {
-            DuneError::Other(anyhow::anyhow!("blocks_writer already taken"))
-        })?;
-        let transactions_writer = self.transactions_writer.take().ok_or_else(|| {
-            DuneError::Other(anyhow::anyhow!("transactions_writer already taken"))
-        })?;
-        let receipts_writer = self.receipts_writer.take().ok_or_else(|| {
-            DuneError::Other(anyhow::anyhow!("receipts_writer already taken"))
-        })?;
+        let mut tables = self
+            .tables
+            .take()
+            .ok_or_else(|| DuneError::Other(anyhow::anyhow!("Writers already taken")))?;
+
+        // The batch ends with the supply of every asset it minted or burned
+        if let Some((height, time)) = self.last_block {
+            let mut changed: Vec<_> = self.changed_assets.iter().collect();
+            changed.sort_by(|a, b| a.0.cmp(&b.0));
+            for asset_id in changed {
+                if let Some(supply) = self.asset_supplies.get(asset_id) {
+                    tables.append(&AvroAssetSupply::new(height, time, supply))?;
+                }
+            }
+        }
 
-        let blocks_path = blocks_writer.finalize_path()?;
-        let transactions_path = transactions_writer.finalize_path()?;
-        let receipts_path = receipts_writer.finalize_path()?;
+        let tables = tables.finalize()?;
 
         // Take ownership of temp_dir so Drop won't clean it up
         let temp_dir = self
@@ -247,9 +504,9 @@ impl AvroFileWriters {
 
         Ok(FinalizedAvroFiles {
             temp_dir,
-            blocks_path,
-            transactions_path,
-            receipts_path,
+            tables,
+            asset_supplies: std::mem::take(&mut self.asset_supplies),
+            predicate_hashes: std::mem::take(&mut self.predicate_hashes),
         })
     }
 }
@@ -258,34 +515,72 @@ impl AvroFileWriters {
 // Disk-based buffer implementation
 // ============================================================================
 
-/// Disk-based block buffer that writes blocks directly to Avro files.
+/// Disk-based block buffer that writes blocks directly to table files.
 /// Uses minimal memory by streaming data directly to disk.
 pub struct DiskBuffer {
     writers: Option<AvroFileWriters>,
+    /// Codec used for each Avro table file, kept to recreate writers on reset
+    codecs: TableCodecs,
+    /// File format of each table, kept to recreate writers on reset
+    formats: TableFormats,
     first_height: Option<BlockHeight>,
     last_height: Option<BlockHeight>,
+    partition_date: Option<NaiveDate>,
     block_count: usize,
+    /// Asset supplies as of the last uploaded batch, the base of the
+    /// supplies of the next one
+    asset_supplies: AssetSupplies,
+    /// Predicate bytecodes written up to the last uploaded batch
+    predicate_hashes: PredicateHashes,
+    /// ABIs of the contracts whose logs and calls are decoded, kept across
+    /// resets
+    abis: Arc<AbiRegistry>,
 }
 
 impl DiskBuffer {
     pub fn new() -> DuneResult<Self> {
-        let writers = AvroFileWriters::new()?;
+        Self::with_codecs(TableCodecs::default())
+    }
+
+    /// Creates a buffer whose table files are compressed with the given codecs
+    pub fn with_codecs(codecs: TableCodecs) -> DuneResult<Self> {
+        Self::with_formats(codecs, TableFormats::default())
+    }
+
+    /// Creates a buffer writing each table in the given format. Codecs only
+    /// apply to the tables written as Avro.
+    pub fn with_formats(codecs: TableCodecs, formats: TableFormats) -> DuneResult<Self> {
+        let writers = AvroFileWriters::new(&codecs, &formats)?;
         Ok(Self {
             writers: Some(writers),
+            codecs,
+            formats,
             first_height: None,
             last_height: None,
+            partition_date: None,
             block_count: 0,
+            asset_supplies: AssetSupplies::default(),
+            predicate_hashes: PredicateHashes::default(),
+            abis: Arc::default(),
         })
     }
 
     #[cfg(test)]
     pub fn with_dir(dir: impl AsRef<Path>) -> DuneResult<Self> {
-        let writers = AvroFileWriters::with_dir(dir)?;
+        let codecs = TableCodecs::default();
+        let formats = TableFormats::default();
+        let writers = AvroFileWriters::with_dir(dir, &codecs, &formats)?;
         Ok(Self {
             writers: Some(writers),
+            codecs,
+            formats,
             first_height: None,
             last_height: None,
+            partition_date: None,
             block_count: 0,
+            asset_supplies: AssetSupplies::default(),
+            predicate_hashes: PredicateHashes::default(),
+            abis: Arc::default(),
         })
     }
 
@@ -309,8 +604,13 @@ impl DiskBuffer {
         self.last_height
     }
 
+    /// Returns the UTC day of the first block in the buffer
+    pub fn partition_date(&self) -> Option<NaiveDate> {
+        self.partition_date
+    }
+
     /// Appends a block and its transactions to the buffer.
-    /// Data is written directly to Avro files on disk.
+    /// Data is written directly to the table files on disk.
     pub fn append(
         &mut self,
         block: &Block,
@@ -320,6 +620,7 @@ impl DiskBuffer {
 
         if self.first_height.is_none() {
             self.first_height = Some(height);
+            self.partition_date = Some(block.header.get_timestamp_utc().date_naive());
         }
         self.last_height = Some(height);
 
@@ -334,7 +635,22 @@ impl DiskBuffer {
         Ok(())
     }
 
-    /// Finalizes the buffer, returning paths to the Avro files for upload.
+    /// Appends the messages imported and consumed by `block`, which must
+    /// already have been appended with [`Self::append`].
+    pub fn append_messages(
+        &mut self,
+        block: &Block,
+        messages: &[Message],
+    ) -> DuneResult<()> {
+        let writers = self
+            .writers
+            .as_mut()
+            .ok_or_else(|| DuneError::Other(anyhow::anyhow!("Writers not available")))?;
+
+        writers.append_messages(block, messages)
+    }
+
+    /// Finalizes the buffer, returning paths to the table files for upload.
     ///
     /// WARNING: This method consumes the internal writers and transfers ownership
     /// of the temp directory to `FinalizedBatchFiles`. Once called:
@@ -351,6 +667,9 @@ impl DiskBuffer {
         let last_height = self.last_height.ok_or_else(|| {
             DuneError::Other(anyhow::anyhow!("Cannot finalize empty buffer"))
         })?;
+        let partition_date = self.partition_date.ok_or_else(|| {
+            DuneError::Other(anyhow::anyhow!("Cannot finalize empty buffer"))
+        })?;
 
         let writers = self
             .writers
@@ -363,13 +682,40 @@ impl DiskBuffer {
         Ok(FinalizedBatchFiles {
             first_height,
             last_height,
-            blocks_path: avro_files.blocks_path,
-            transactions_path: avro_files.transactions_path,
-            receipts_path: avro_files.receipts_path,
+            partition_date,
+            tables: avro_files.tables,
+            asset_supplies: avro_files.asset_supplies,
+            predicate_hashes: avro_files.predicate_hashes,
             temp_dir: avro_files.temp_dir,
         })
     }
 
+    /// Sets the asset supplies the next batch starts from, e.g. those loaded
+    /// on startup or those of a batch once it is uploaded
+    pub fn set_asset_supplies(&mut self, supplies: AssetSupplies) {
+        if let Some(writers) = &mut self.writers {
+            writers.asset_supplies = supplies.clone();
+        }
+        self.asset_supplies = supplies;
+    }
+
+    /// Sets the predicate bytecodes already written, e.g. those loaded on
+    /// startup or those of a batch once it is uploaded
+    pub fn set_predicate_hashes(&mut self, hashes: PredicateHashes) {
+        if let Some(writers) = &mut self.writers {
+            writers.predicate_hashes = hashes.clone();
+        }
+        self.predicate_hashes = hashes;
+    }
+
+    /// Sets the ABIs used to decode contract logs and calls
+    pub fn set_abis(&mut self, abis: Arc<AbiRegistry>) {
+        if let Some(writers) = &mut self.writers {
+            writers.abis = abis.clone();
+        }
+        self.abis = abis;
+    }
+
     /// Resets the buffer for reuse, clearing all data.
     /// Call this after successful upload to prepare for the next batch.
     pub fn reset(&mut self) -> DuneResult<()> {
@@ -377,10 +723,15 @@ impl DiskBuffer {
         let _ = self.writers.take();
 
         // Create new writers
-        self.writers = Some(AvroFileWriters::new()?);
+        let mut writers = AvroFileWriters::new(&self.codecs, &self.formats)?;
+        writers.asset_supplies = self.asset_supplies.clone();
+        writers.predicate_hashes = self.predicate_hashes.clone();
+        writers.abis = self.abis.clone();
+        self.writers = Some(writers);
 
         self.first_height = None;
         self.last_height = None;
+        self.partition_date = None;
         self.block_count = 0;
 
         Ok(())
@@ -390,10 +741,30 @@ impl DiskBuffer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use fuel_streams_domains::mocks::{
-        MockBlock,
-        MockReceipt,
-        MockTransaction,
+    use crate::{
+        abi::ProgramAbi,
+        helpers::{
+            AvroCodec,
+            FileFormat,
+            read_codec_metadata,
+        },
+    };
+    use fuel_streams_domains::{
+        assets::asset_id,
+        mocks::{
+            MockBlock,
+            MockInput,
+            MockMessage,
+            MockOutput,
+            MockReceipt,
+            MockTransaction,
+        },
+        receipts::{
+            BurnReceipt,
+            CallReceipt,
+            Receipt,
+        },
+        utxos::Utxo,
     };
     use pretty_assertions::assert_eq;
     use tempfile::tempdir;
@@ -419,14 +790,17 @@ mod tests {
         assert_eq!(buffer.last_height(), Some(BlockHeight::from(10)));
 
         // Finalize and verify
+        let partition_date = buffer.partition_date();
+        assert!(partition_date.is_some());
         let finalized = buffer.finalize()?;
         assert_eq!(*finalized.first_height, 1);
         assert_eq!(*finalized.last_height, 10);
+        assert_eq!(Some(finalized.partition_date), partition_date);
 
         // Verify files exist
-        assert!(finalized.blocks_path.exists());
-        assert!(finalized.transactions_path.exists());
-        assert!(finalized.receipts_path.exists());
+        for table in S3TableName::ROW_TABLES {
+            assert!(finalized.path(*table).is_some_and(Path::exists));
+        }
 
         Ok(())
     }
@@ -452,6 +826,7 @@ mod tests {
         assert!(buffer.is_empty());
         assert_eq!(buffer.first_height(), None);
         assert_eq!(buffer.last_height(), None);
+        assert_eq!(buffer.partition_date(), None);
 
         // Can add more blocks after reset
         let mut block = MockBlock::random();
@@ -478,19 +853,304 @@ mod tests {
         }
 
         let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::Blocks), 3);
+        assert_eq!(finalized.rows(S3TableName::BlockStats), 3);
+        assert_eq!(finalized.rows(S3TableName::Transactions), 3);
+        assert_eq!(
+            finalized.rows(S3TableName::Receipts),
+            3 * MockReceipt::all().len() as u64
+        );
 
         // Verify receipts file exists and has content
-        assert!(finalized.receipts_path.exists());
-        let receipts_size = std::fs::metadata(&finalized.receipts_path)?.len();
+        assert!(finalized.path(S3TableName::Receipts).unwrap().exists());
+        let receipts_size =
+            std::fs::metadata(finalized.path(S3TableName::Receipts).unwrap())?.len();
         assert!(receipts_size > 0, "Receipts file should not be empty");
 
         Ok(())
     }
 
+    #[test]
+    fn test_disk_buffer_with_inputs_and_outputs() -> DuneResult<()> {
+        let dir = tempdir().unwrap();
+        let mut buffer = DiskBuffer::with_dir(dir.path())?;
+
+        let mut utxos = 0;
+        for i in 1..=3 {
+            let mut block = MockBlock::random();
+            block.height = BlockHeight::from(i);
+            let txs = vec![
+                MockTransaction::script(MockInput::all(), MockOutput::all(), vec![]),
+                MockTransaction::script(vec![], vec![], vec![]),
+            ];
+            utxos += txs
+                .iter()
+                .map(|tx| Utxo::from_transaction(tx).len() as u64)
+                .sum::<u64>();
+            buffer.append(&block, &txs)?;
+        }
+
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::Transactions), 6);
+        assert_eq!(
+            finalized.rows(S3TableName::Inputs),
+            3 * MockInput::all().len() as u64
+        );
+        assert_eq!(
+            finalized.rows(S3TableName::Outputs),
+            3 * MockOutput::all().len() as u64
+        );
+        assert_eq!(finalized.rows(S3TableName::Utxos), utxos);
+        assert_eq!(finalized.rows(S3TableName::Contracts), 0);
+        assert!(
+            std::fs::metadata(finalized.path(S3TableName::Inputs).unwrap())?.len() > 0
+        );
+        assert!(
+            std::fs::metadata(finalized.path(S3TableName::Outputs).unwrap())?.len() > 0
+        );
+        assert!(
+            std::fs::metadata(finalized.path(S3TableName::Utxos).unwrap())?.len() > 0
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_buffer_with_messages() -> DuneResult<()> {
+        let dir = tempdir().unwrap();
+        let mut buffer = DiskBuffer::with_dir(dir.path())?;
+
+        for i in 1..=3 {
+            let mut block = MockBlock::random();
+            block.height = BlockHeight::from(i);
+            buffer.append(&block, &[])?;
+            buffer.append_messages(&block, &MockMessage::all())?;
+        }
+
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::Blocks), 3);
+        assert_eq!(
+            finalized.rows(S3TableName::Messages),
+            3 * MockMessage::all().len() as u64
+        );
+        assert!(
+            std::fs::metadata(finalized.path(S3TableName::Messages).unwrap())?.len() > 0
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_buffer_deduplicates_predicate_bytecodes() -> DuneResult<()> {
+        let dir = tempdir().unwrap();
+        let mut buffer = DiskBuffer::with_dir(dir.path())?;
+        let inputs = vec![MockInput::coin_predicate(), MockInput::coin_predicate()];
+
+        for i in 1..=3 {
+            let mut block = MockBlock::random();
+            block.height = BlockHeight::from(i);
+            let txs = vec![MockTransaction::script(inputs.clone(), vec![], vec![])];
+            buffer.append(&block, &txs)?;
+        }
+
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::Predicates), 6);
+        assert_eq!(finalized.rows(S3TableName::PredicateBytecodes), 1);
+
+        // A batch that was never uploaded writes the bytecode again
+        let hashes = finalized.predicate_hashes.clone();
+        assert_eq!(hashes.len(), 1);
+        buffer.reset()?;
+        let txs = vec![MockTransaction::script(inputs, vec![], vec![])];
+        buffer.append(&MockBlock::random(), &txs)?;
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::PredicateBytecodes), 1);
+
+        // After an upload, later batches skip it
+        buffer.reset()?;
+        buffer.set_predicate_hashes(hashes);
+        buffer.append(&MockBlock::random(), &txs)?;
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::Predicates), 2);
+        assert_eq!(finalized.rows(S3TableName::PredicateBytecodes), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_buffer_asset_supplies_across_batches() -> DuneResult<()> {
+        let dir = tempdir().unwrap();
+        let mut buffer = DiskBuffer::with_dir(dir.path())?;
+        let mint = MockReceipt::mint();
+        let Receipt::Mint(minted) = &mint else {
+            unreachable!()
+        };
+        let burn = Receipt::Burn(BurnReceipt {
+            sub_id: minted.sub_id.clone(),
+            contract_id: minted.contract_id.clone(),
+            val: 30.into(),
+            pc: 0.into(),
+            is: 0.into(),
+        });
+        let asset_id = asset_id(&minted.contract_id, &minted.sub_id);
+        let block_at = |height: u32| {
+            let mut block = MockBlock::random();
+            block.height = BlockHeight::from(height);
+            block
+        };
+
+        let txs = vec![MockTransaction::script(
+            vec![],
+            vec![],
+            vec![mint.clone(), mint.clone()],
+        )];
+        buffer.append(&block_at(1), &txs)?;
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::Assets), 2);
+        assert_eq!(finalized.rows(S3TableName::AssetSupplies), 1);
+        let supplies = finalized.asset_supplies.clone();
+        assert_eq!(supplies.get(&asset_id).unwrap().supply, 200);
+        buffer.reset()?;
+        buffer.set_asset_supplies(supplies);
+
+        // Block 1 is replayed, as after a reconnect, and only counted once
+        buffer.append(&block_at(1), &txs)?;
+        let txs = vec![MockTransaction::script(vec![], vec![], vec![burn])];
+        buffer.append(&block_at(2), &txs)?;
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::Assets), 3);
+        assert_eq!(finalized.rows(S3TableName::AssetSupplies), 1);
+        let supply = finalized.asset_supplies.get(&asset_id).unwrap();
+        assert_eq!(supply.supply, 170);
+        assert_eq!(supply.first_mint_height, Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_buffer_decoded_rows_across_batches() -> DuneResult<()> {
+        let dir = tempdir().unwrap();
+        let mut buffer = DiskBuffer::with_dir(dir.path())?;
+        let log = MockReceipt::log_data();
+        let Receipt::LogData(log_data) = &log else {
+            unreachable!()
+        };
+        let call = Receipt::Call(CallReceipt {
+            to: log_data.id.clone(),
+            ..MockReceipt::call().as_call()
+        });
+        let txs = vec![MockTransaction::script(
+            vec![],
+            vec![],
+            vec![log.clone(), call],
+        )];
+
+        // Logs and calls are only decoded for the contracts with an ABI
+        buffer.append(&MockBlock::random(), &txs)?;
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::DecodedLogs), 0);
+        assert_eq!(finalized.rows(S3TableName::DecodedCalls), 0);
+
+        let abi = r#"{"encodingVersion": "1", "loggedTypes": []}"#;
+        let mut abis = AbiRegistry::default();
+        abis.insert(
+            log_data.id.clone(),
+            ProgramAbi::from_json(abi.as_bytes()).unwrap(),
+        );
+        buffer.reset()?;
+        buffer.set_abis(Arc::new(abis));
+        buffer.append(&MockBlock::random(), &txs)?;
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::DecodedLogs), 1);
+        assert_eq!(finalized.rows(S3TableName::DecodedCalls), 1);
+
+        // The ABIs are kept for the next batch
+        buffer.reset()?;
+        buffer.append(&MockBlock::random(), &txs)?;
+        let finalized = buffer.finalize()?;
+        assert_eq!(finalized.rows(S3TableName::DecodedLogs), 1);
+        assert_eq!(finalized.rows(S3TableName::DecodedCalls), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn test_disk_buffer_new() -> DuneResult<()> {
         let buffer = DiskBuffer::new()?;
         assert!(buffer.is_empty());
         Ok(())
     }
+
+    #[test]
+    fn test_disk_buffer_table_codecs() -> DuneResult<()> {
+        let codecs = TableCodecs::new(AvroCodec::Snappy)
+            .with_table(S3TableName::Receipts, AvroCodec::Zstandard);
+        let mut buffer = DiskBuffer::with_codecs(codecs)?;
+
+        let mut block = MockBlock::random();
+        block.height = BlockHeight::from(1);
+        let txs = vec![MockTransaction::script(vec![], vec![], MockReceipt::all())];
+        buffer.append(&block, &txs)?;
+
+        let finalized = buffer.finalize()?;
+        let codec_of = |path: &Path| -> DuneResult<_> {
+            Ok(read_codec_metadata(&std::fs::read(path)?)?)
+        };
+        assert_eq!(
+            codec_of(finalized.path(S3TableName::Blocks).unwrap())?,
+            Some(AvroCodec::Snappy)
+        );
+        assert_eq!(
+            codec_of(finalized.path(S3TableName::Transactions).unwrap())?,
+            Some(AvroCodec::Snappy)
+        );
+        assert_eq!(
+            codec_of(finalized.path(S3TableName::Receipts).unwrap())?,
+            Some(AvroCodec::Zstandard)
+        );
+
+        // Codecs survive a reset
+        buffer.reset()?;
+        buffer.append(&block, &txs)?;
+        let finalized = buffer.finalize()?;
+        assert_eq!(
+            codec_of(finalized.path(S3TableName::Receipts).unwrap())?,
+            Some(AvroCodec::Zstandard)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_buffer_table_formats() -> DuneResult<()> {
+        let formats = TableFormats::new(FileFormat::Avro)
+            .with_table(S3TableName::Receipts, FileFormat::Parquet);
+        let mut buffer = DiskBuffer::with_formats(TableCodecs::default(), formats)?;
+
+        for i in 1..=3 {
+            let mut block = MockBlock::random();
+            block.height = BlockHeight::from(i);
+            let txs = vec![MockTransaction::script(vec![], vec![], MockReceipt::all())];
+            buffer.append(&block, &txs)?;
+        }
+
+        let finalized = buffer.finalize()?;
+        assert!(
+            finalized
+                .path(S3TableName::Blocks)
+                .unwrap()
+                .ends_with("blocks.avro")
+        );
+        assert!(
+            finalized
+                .path(S3TableName::Receipts)
+                .unwrap()
+                .ends_with("receipts.parquet")
+        );
+
+        let receipts = std::fs::read(finalized.path(S3TableName::Receipts).unwrap())?;
+        assert!(receipts.starts_with(b"PAR1") && receipts.ends_with(b"PAR1"));
+
+        Ok(())
+    }
 }
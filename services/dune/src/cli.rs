@@ -1,9 +1,74 @@
-use crate::processor::StorageTypeConfig;
-use clap::Parser;
+use crate::{
+    helpers::{
+        AvroCodec,
+        TableCodecs,
+        TableFormats,
+    },
+    postgres::OutputMode,
+    processor::StorageTypeConfig,
+    s3::{
+        KeyLayout,
+        S3TableName,
+    },
+};
+use clap::{
+    Args,
+    CommandFactory,
+    Parser,
+    Subcommand,
+};
+use std::{
+    ffi::OsString,
+    path::PathBuf,
+};
 use url::Url;
 
 #[derive(Debug, Clone, Parser)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+impl Cli {
+    /// Parses the process arguments. Arguments that don't start with a
+    /// subcommand run the exporter, as they did before the subcommands.
+    pub fn parse_or_run() -> Self {
+        Self::parse_from(with_default_command(std::env::args_os()))
+    }
+}
+
+/// Inserts the `run` subcommand after the binary name, unless the arguments
+/// already start with a subcommand or ask for help
+fn with_default_command(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    let mut args: Vec<OsString> = args.into_iter().collect();
+    let explicit = args.get(1).and_then(|arg| arg.to_str()).is_some_and(|arg| {
+        matches!(arg, "-h" | "--help") || Cli::command().find_subcommand(arg).is_some()
+    });
+    if !explicit {
+        args.insert(args.len().min(1), "run".into());
+    }
+    args
+}
+
+#[derive(Debug, Clone, Subcommand)]
+#[allow(clippy::large_enum_variant)]
+pub enum Command {
+    /// Run the exporter service.
+    Run(RunArgs),
+    /// Encode a sample block range with each Avro codec and report size and throughput.
+    CodecBench(CodecBenchArgs),
+    /// Rewrite existing flat range files into the date-partitioned layout.
+    MigrateLayout(MigrateLayoutArgs),
+    /// Merge adjacent small range files into larger ones.
+    Compact(CompactArgs),
+    /// Compare exported rows against the blocks of a node.
+    Verify(VerifyArgs),
+    /// Recompute the Merkle roots of stored blocks from their exported rows.
+    Validate(ValidateArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RunArgs {
     #[arg(long, env)]
     pub url: Url,
 
@@ -14,7 +79,7 @@ pub struct Cli {
         long,
         value_name = "STORAGE_TYPE",
         env = "STORAGE_TYPE",
-        default_value = "StorageTypeConfig::File",
+        default_value = "File",
         help = "Type of storage to use. Options are 'S3' or 'File'."
     )]
     pub storage_type: StorageTypeConfig,
@@ -33,4 +98,201 @@ pub struct Cli {
     /// The number of unordered pending blocks to buffer.
     #[arg(long, env, default_value = "10000")]
     pub pending_blocks: usize,
+
+    /// Avro codecs as a default plus per-table overrides, e.g. `deflate,receipts=zstandard`.
+    /// Options are 'null', 'deflate', 'snappy', 'zstandard' or 'bzip2'.
+    #[arg(long, env, default_value = "deflate")]
+    pub avro_codecs: TableCodecs,
+
+    /// File format as a default plus per-table overrides, e.g. `avro,receipts=parquet`.
+    /// Options are 'avro' or 'parquet'. Parquet files are always zstd compressed.
+    #[arg(long, env, default_value = "avro")]
+    pub file_formats: TableFormats,
+
+    /// Object key layout. Options are 'flat' or 'date' (`table/date=YYYY-MM-DD/start-end.avro`).
+    #[arg(long, env, default_value = "flat")]
+    pub key_layout: KeyLayout,
+
+    /// Commit an Apache Iceberg snapshot for every uploaded batch, with the table
+    /// metadata stored under `{table}/metadata/`.
+    #[arg(long, env)]
+    pub iceberg: bool,
+
+    /// Where blocks are written. Options are 'files' (the configured storage),
+    /// 'postgres' or 'both'.
+    #[arg(long, env, default_value = "files")]
+    pub output: OutputMode,
+
+    /// PostgreSQL connection URL, required by the 'postgres' and 'both' outputs.
+    #[arg(long, env)]
+    pub database_url: Option<Url>,
+
+    /// The number of blocks written to PostgreSQL per transaction.
+    #[arg(long, env, default_value = "100")]
+    pub postgres_batch_size: usize,
+
+    /// Publish every ingested block to NATS JetStream, under
+    /// `fuel.{network}.{payloads|blocks|transactions|receipts}.{height}...`.
+    #[arg(long, env)]
+    pub nats_url: Option<Url>,
+
+    /// Recompute the transactions and receipts roots of every block from its
+    /// rows before writing them, logging an error on mismatch.
+    #[arg(long, env)]
+    pub validate_roots: bool,
+
+    /// Directory of Sway program ABIs named `<contract id>.json`. The logs of
+    /// and calls to these contracts are decoded into the `decoded_logs` and
+    /// `decoded_calls` tables.
+    #[arg(long, env)]
+    pub abi_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CodecBenchArgs {
+    #[arg(long, env)]
+    pub url: Url,
+
+    /// First block height of the sample range.
+    #[arg(long)]
+    pub from: u32,
+
+    /// Last block height of the sample range (inclusive).
+    #[arg(long)]
+    pub to: u32,
+
+    /// Codecs to compare. Defaults to all supported codecs.
+    #[arg(long, value_delimiter = ',')]
+    pub codecs: Vec<AvroCodec>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MigrateLayoutArgs {
+    /// Tables to migrate.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "blocks,transactions,receipts"
+    )]
+    pub tables: Vec<S3TableName>,
+
+    /// Delete each flat file once its partitioned files are written.
+    #[arg(long)]
+    pub delete_source: bool,
+
+    /// Only log the planned rewrites without touching storage.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CompactArgs {
+    /// Tables to compact. Defaults to every table.
+    #[arg(long, value_delimiter = ',')]
+    pub tables: Vec<S3TableName>,
+
+    /// Upper bound for the size of a merged file, in MiB.
+    #[arg(long, env = "COMPACT_TARGET_SIZE_MB", default_value = "128")]
+    pub target_size_mb: u64,
+
+    /// Only log the planned merges without touching storage.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct VerifyArgs {
+    #[arg(long, env)]
+    pub url: Url,
+
+    /// First block height to verify.
+    #[arg(long, default_value = "0")]
+    pub from: u32,
+
+    /// Last block height to verify (inclusive). Defaults to the last height
+    /// committed by the exporter.
+    #[arg(long)]
+    pub to: Option<u32>,
+
+    /// Tables to verify. Defaults to every table that can be rebuilt from the
+    /// node.
+    #[arg(long, value_delimiter = ',')]
+    pub tables: Vec<S3TableName>,
+
+    /// Verify this many random windows of `--sample-size` blocks instead of
+    /// the whole range.
+    #[arg(long)]
+    pub samples: Option<usize>,
+
+    /// Number of blocks in each sampled window.
+    #[arg(long, default_value = "100")]
+    pub sample_size: u32,
+
+    /// Number of blocks fetched from the node and compared at once.
+    #[arg(long, default_value = "100")]
+    pub chunk_size: u32,
+
+    /// Keep auditing random windows of the committed range until stopped,
+    /// logging every discrepancy instead of failing.
+    #[arg(long)]
+    pub continuous: bool,
+
+    /// Seconds between two audit rounds with `--continuous`.
+    #[arg(long, env = "VERIFY_INTERVAL_SECS", default_value = "300")]
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ValidateArgs {
+    /// First block height to validate.
+    #[arg(long)]
+    pub from: u32,
+
+    /// Last block height to validate (inclusive).
+    #[arg(long)]
+    pub to: u32,
+
+    /// Number of blocks read from each table and validated at once.
+    #[arg(long, default_value = "1000")]
+    pub chunk_size: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Command {
+        let args = args.iter().map(OsString::from);
+        Cli::parse_from(with_default_command(args)).command
+    }
+
+    #[test]
+    fn test_run_is_the_default_command() {
+        let args = [
+            "sv-dune",
+            "--url",
+            "http://localhost:4000",
+            "--starting-block",
+            "7",
+        ];
+        let Command::Run(run) = parse(&args) else {
+            panic!("expected the run command");
+        };
+        assert_eq!(run.starting_block, 7);
+
+        let args = [
+            "sv-dune",
+            "run",
+            "--url",
+            "http://localhost:4000",
+            "--starting-block",
+            "7",
+        ];
+        assert!(matches!(parse(&args), Command::Run(_)));
+
+        let args = ["sv-dune", "verify", "--url", "http://localhost:4000"];
+        assert!(matches!(parse(&args), Command::Verify(_)));
+    }
 }
@@ -0,0 +1,204 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use fuel_streams_domains::{
+    blocks::Block,
+    transactions::Transaction,
+};
+
+use crate::{
+    CodecBenchArgs,
+    DuneResult,
+    helpers::{
+        AvroCodec,
+        AvroParser,
+    },
+    s3::{
+        S3TableName,
+        TableRow,
+        with_table_row,
+    },
+    service::fetch_blocks_range,
+    verify::TableRows,
+};
+
+/// Size and timing of one table encoded with one codec.
+#[derive(Debug, Clone)]
+pub struct CodecBenchResult {
+    pub codec: AvroCodec,
+    pub table: S3TableName,
+    pub rows: usize,
+    pub bytes: usize,
+    pub elapsed: Duration,
+}
+
+impl CodecBenchResult {
+    /// Rows encoded per second
+    pub fn rows_per_sec(&self) -> f64 {
+        self.rows as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Encodes every table of the given blocks once per codec and measures the
+/// resulting file size and the time spent encoding and compressing.
+/// Rows are converted up front so only the writer is timed.
+pub fn bench_codecs(
+    blocks_and_txs: &[(Block, Vec<Transaction>)],
+    codecs: &[AvroCodec],
+) -> DuneResult<Vec<CodecBenchResult>> {
+    let rows = TableRows::new(blocks_and_txs);
+    let tables: Vec<_> = rows.tables().collect();
+
+    let mut results = Vec::with_capacity(codecs.len() * tables.len());
+    for &codec in codecs {
+        for &table in &tables {
+            let result = with_table_row!(table, |Row| {
+                bench_table(codec, rows.get::<Row>().unwrap_or_default())?
+            });
+            results.extend(result);
+        }
+    }
+    Ok(results)
+}
+
+fn bench_table<T: TableRow>(
+    codec: AvroCodec,
+    rows: &[T],
+) -> DuneResult<CodecBenchResult> {
+    let start = Instant::now();
+    let mut writer = AvroParser::new(codec).writer_with_schema::<T>()?;
+    for row in rows {
+        writer.append(row)?;
+    }
+    let bytes = writer.into_inner()?.len();
+
+    Ok(CodecBenchResult {
+        codec,
+        table: T::TABLE,
+        rows: rows.len(),
+        bytes,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Renders the results as a plain text table, one line per table and codec.
+/// The ratio column compares each size against the uncompressed (`null`)
+/// output when it is part of the run.
+pub fn format_report(results: &[CodecBenchResult]) -> String {
+    let mut report = format!(
+        "{:<14}{:<11}{:>10}{:>14}{:>8}{:>12}{:>14}\n",
+        "table", "codec", "rows", "bytes", "ratio", "ms", "rows/s"
+    );
+    for result in results {
+        let baseline = results
+            .iter()
+            .find(|r| r.table == result.table && r.codec == AvroCodec::Null)
+            .map(|r| r.bytes);
+        let ratio = baseline
+            .map(|b| format!("{:.2}", b as f64 / result.bytes.max(1) as f64))
+            .unwrap_or_else(|| "-".to_string());
+        report.push_str(&format!(
+            "{:<14}{:<11}{:>10}{:>14}{:>8}{:>12.1}{:>14.0}\n",
+            result.table.to_string(),
+            result.codec.to_string(),
+            result.rows,
+            result.bytes,
+            ratio,
+            result.elapsed.as_secs_f64() * 1000.0,
+            result.rows_per_sec()
+        ));
+    }
+    report
+}
+
+/// Entry point of the `codec-bench` command
+pub async fn run(args: CodecBenchArgs) -> anyhow::Result<()> {
+    let codecs = if args.codecs.is_empty() {
+        AvroCodec::ALL.to_vec()
+    } else {
+        args.codecs
+    };
+
+    tracing::info!(
+        "Fetching blocks {} to {} for codec benchmark",
+        args.from,
+        args.to
+    );
+    let blocks_and_txs =
+        fetch_blocks_range(&args.url, args.from.into(), args.to.into()).await?;
+
+    let results = bench_codecs(&blocks_and_txs, &codecs)?;
+    println!("{}", format_report(&results));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_streams_domains::{
+        assets::AssetEvent,
+        mocks::{
+            MockBlock,
+            MockInput,
+            MockOutput,
+            MockReceipt,
+            MockTransaction,
+        },
+        predicates::Predicate,
+        transfers::Transfer,
+        utxos::Utxo,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_bench_codecs() -> DuneResult<()> {
+        let blocks_and_txs: Vec<_> = (1..=5)
+            .map(|i| {
+                let mut block = MockBlock::random();
+                block.height = i.into();
+                let txs = vec![MockTransaction::script(
+                    MockInput::all(),
+                    MockOutput::all(),
+                    MockReceipt::all(),
+                )];
+                (block, txs)
+            })
+            .collect();
+
+        let utxos_per_tx = Utxo::from_transaction(&blocks_and_txs[0].1[0]).len();
+        let asset_events_per_tx =
+            AssetEvent::from_transaction(0, &blocks_and_txs[0].1[0]).len();
+        let transfers_per_tx =
+            Transfer::from_transaction(0, &blocks_and_txs[0].1[0]).len();
+        let predicates_per_tx =
+            Predicate::from_transaction(0, &blocks_and_txs[0].1[0]).len();
+        let results = bench_codecs(&blocks_and_txs, &AvroCodec::ALL)?;
+        assert_eq!(results.len(), AvroCodec::ALL.len() * 11);
+
+        for result in &results {
+            let expected_rows = match result.table {
+                S3TableName::Blocks | S3TableName::BlockStats => 5,
+                S3TableName::Transactions => 5,
+                S3TableName::Inputs => 5 * MockInput::all().len(),
+                S3TableName::Outputs => 5 * MockOutput::all().len(),
+                S3TableName::Utxos => 5 * utxos_per_tx,
+                S3TableName::Predicates => 5 * predicates_per_tx,
+                S3TableName::Contracts => 0,
+                S3TableName::Assets => 5 * asset_events_per_tx,
+                S3TableName::Transfers => 5 * transfers_per_tx,
+                _ => 5 * MockReceipt::all().len(),
+            };
+            assert_eq!(result.rows, expected_rows);
+            assert!(result.bytes > 0);
+        }
+
+        let report = format_report(&results);
+        assert_eq!(report.lines().count(), results.len() + 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,616 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use fuel_streams_types::BlockHeight;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    CompactArgs,
+    DuneError,
+    DuneResult,
+    helpers::{
+        AvroFileWriter,
+        AvroParser,
+        FileFormat,
+        count_records,
+        read_codec_metadata_from,
+    },
+    iceberg::version_hint_key,
+    s3::{
+        FuelNetwork,
+        S3KeyBuilder,
+        S3Storage,
+        S3StorageOpts,
+        S3TableName,
+        Storage,
+        StorageConfig,
+        StoredObject,
+        TableRow,
+        parse_range_key,
+        range_filename,
+        with_table_row,
+    },
+};
+
+/// Directory that holds merged files until they are committed. Query engines
+/// following Hive conventions skip paths starting with `_`.
+pub const STAGING_DIR: &str = "_staging";
+
+/// A range file listed from storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeObject {
+    pub key: String,
+    pub start: BlockHeight,
+    pub end: BlockHeight,
+    pub size: u64,
+}
+
+impl RangeObject {
+    /// Returns the range object for a listed key, skipping objects that are not
+    /// range files or live under a hidden (`_` or `.` prefixed) directory.
+    pub fn from_stored(object: StoredObject) -> Option<Self> {
+        let hidden = object
+            .key
+            .split('/')
+            .any(|segment| segment.starts_with('_') || segment.starts_with('.'));
+        if hidden {
+            return None;
+        }
+        let (start, end) = parse_range_key(&object.key)?;
+        Some(Self {
+            key: object.key,
+            start,
+            end,
+            size: object.size,
+        })
+    }
+
+    /// Directory of the object, including the trailing slash
+    pub fn directory(&self) -> &str {
+        let index = self.key.rfind('/').map(|i| i + 1).unwrap_or_default();
+        &self.key[..index]
+    }
+}
+
+/// Drops the range files whose heights are within those of another listed
+/// file, keeping one of files with the same range. A merged file covers its
+/// sources, so readers listing a table between the commit copying it into
+/// place and the deletion of the sources read each range once.
+pub fn drop_covered(mut objects: Vec<RangeObject>) -> Vec<RangeObject> {
+    objects.sort_by(|a, b| {
+        a.start
+            .cmp(&b.start)
+            .then(b.end.cmp(&a.end))
+            .then(a.key.cmp(&b.key))
+    });
+    let mut covered_to = None;
+    objects.retain(|object| {
+        if covered_to.is_some_and(|end| object.end <= end) {
+            return false;
+        }
+        covered_to = Some(object.end);
+        true
+    });
+    objects
+}
+
+/// Groups adjacent range files of the same directory into merges whose
+/// combined size stays within `target_size`. Files already at or above the
+/// target are left alone, and only groups of two or more files are returned.
+pub fn plan_compaction(
+    mut objects: Vec<RangeObject>,
+    target_size: u64,
+) -> Vec<Vec<RangeObject>> {
+    objects.sort_by(|a, b| a.directory().cmp(b.directory()).then(a.start.cmp(&b.start)));
+
+    let mut groups = Vec::new();
+    let mut current: Vec<RangeObject> = Vec::new();
+    let mut current_size = 0;
+
+    for object in objects {
+        let extends_current = current.last().is_some_and(|last| {
+            last.directory() == object.directory()
+                && *last.end + 1 == *object.start
+                && current_size + object.size <= target_size
+        });
+
+        if !extends_current {
+            if current.len() > 1 {
+                groups.push(std::mem::take(&mut current));
+            }
+            current.clear();
+            current_size = 0;
+        }
+
+        if object.size < target_size {
+            current_size += object.size;
+            current.push(object);
+        }
+    }
+
+    if current.len() > 1 {
+        groups.push(current);
+    }
+
+    groups
+}
+
+/// Record of a merge whose merged file is staged and verified. It is written
+/// before the merged file is copied into place so an interrupted run can
+/// finish the commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionJournal {
+    pub staged_key: String,
+    pub target_key: String,
+    pub sources: Vec<String>,
+    pub rows: usize,
+    /// Set once the staged file has been copied to `target_key`
+    #[serde(default)]
+    pub copied: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct CompactionReport {
+    pub resumed: usize,
+    pub merged_files: usize,
+    pub source_files: usize,
+    pub rows: usize,
+}
+
+struct Compactor<'a> {
+    storage: &'a S3Storage,
+    table: S3TableName,
+    journal_prefix: String,
+}
+
+impl<'a> Compactor<'a> {
+    fn new(storage: &'a S3Storage, network: FuelNetwork, table: S3TableName) -> Self {
+        let journal_prefix = S3KeyBuilder::new(network)
+            .with_table(S3TableName::Metadata)
+            .build_key(&format!("compaction/{table}/"));
+        Self {
+            storage,
+            table,
+            journal_prefix,
+        }
+    }
+
+    fn journal_key(&self, target_key: &str) -> String {
+        let filename = target_key.rsplit('/').next().unwrap_or(target_key);
+        format!("{}{filename}.json", self.journal_prefix)
+    }
+
+    async fn store_journal(&self, journal: &CompactionJournal) -> DuneResult<()> {
+        let data = serde_json::to_vec(journal)
+            .map_err(|e| DuneError::Other(anyhow::anyhow!(e)))?;
+        self.storage
+            .store(&self.journal_key(&journal.target_key), data)
+            .await?;
+        Ok(())
+    }
+
+    /// Commits a journaled merge. Every step tolerates having already run:
+    /// the staged file is copied into place and the copy is journaled before
+    /// any source is deleted. Readers switch to the merged file once the copy
+    /// lands, as [`drop_covered`] hides the sources it covers until they are
+    /// deleted.
+    async fn commit(&self, journal: &CompactionJournal) -> DuneResult<()> {
+        if !journal.copied {
+            let staged_exists = !self
+                .storage
+                .list_keys(&journal.staged_key)
+                .await?
+                .is_empty();
+            if !staged_exists {
+                return Err(DuneError::Other(anyhow::anyhow!(
+                    "Staged file {} of {} is missing",
+                    journal.staged_key,
+                    journal.target_key
+                )));
+            }
+            self.storage
+                .copy(&journal.staged_key, &journal.target_key)
+                .await?;
+            self.store_journal(&CompactionJournal {
+                copied: true,
+                ..journal.clone()
+            })
+            .await?;
+        }
+        for source in &journal.sources {
+            self.storage.delete(source).await?;
+        }
+        self.storage.delete(&journal.staged_key).await?;
+        self.storage
+            .delete(&self.journal_key(&journal.target_key))
+            .await?;
+        tracing::info!(
+            "Committed {} ({} sources, {} rows)",
+            journal.target_key,
+            journal.sources.len(),
+            journal.rows
+        );
+        Ok(())
+    }
+
+    /// Finishes merges left behind by an interrupted run and removes staged
+    /// files that never got a journal.
+    async fn resume(&self, table_prefix: &str) -> DuneResult<usize> {
+        let journal_keys = self.storage.list_keys(&self.journal_prefix).await?;
+        for key in &journal_keys {
+            let Some(data) = self.storage.retrieve(key).await? else {
+                continue;
+            };
+            let journal: CompactionJournal =
+                serde_json::from_slice(&data).map_err(|e| {
+                    DuneError::Other(anyhow::anyhow!("Invalid journal {key}: {e}"))
+                })?;
+            tracing::info!("Resuming compaction of {}", journal.target_key);
+            self.commit(&journal).await?;
+        }
+
+        let orphans = self
+            .storage
+            .list_keys(table_prefix)
+            .await?
+            .into_iter()
+            .filter(|key| key.contains(&format!("/{STAGING_DIR}/")));
+        for orphan in orphans {
+            tracing::info!("Removing orphaned staged file {orphan}");
+            self.storage.delete(&orphan).await?;
+        }
+
+        Ok(journal_keys.len())
+    }
+
+    /// Stream-merges `group` into one file: sources are downloaded and
+    /// appended one record at a time, the merged file is staged, the record
+    /// counts of its Avro blocks are verified against the rows streamed from
+    /// the sources, and only then is the merge committed.
+    async fn merge<T: TableRow>(
+        &self,
+        group: &[RangeObject],
+    ) -> DuneResult<CompactionJournal> {
+        let first = group.first().ok_or_else(|| {
+            DuneError::Other(anyhow::anyhow!("Cannot merge an empty group"))
+        })?;
+        let last = &group[group.len() - 1];
+        let filename = range_filename(first.start, last.end, FileFormat::Avro);
+        let target_key = format!("{}{filename}", first.directory());
+        let staged_key = format!("{}{STAGING_DIR}/{filename}", first.directory());
+
+        let merged_path = std::env::temp_dir().join(format!(
+            "dune-compact-{}-{}-{filename}",
+            std::process::id(),
+            self.table
+        ));
+        let (merged_path, rows) = match self.merge_to_file::<T>(group, &merged_path).await
+        {
+            Ok(merged) => merged,
+            Err(e) => {
+                let _ = std::fs::remove_file(&merged_path);
+                return Err(e);
+            }
+        };
+
+        let uploaded = match count_file_records(&merged_path) {
+            Ok(merged_rows) if merged_rows != rows => {
+                Err(row_count_mismatch(&target_key, rows, merged_rows))
+            }
+            Ok(_) => self
+                .storage
+                .store_from_file(&staged_key, &merged_path)
+                .await
+                .map_err(DuneError::from),
+            Err(e) => Err(e),
+        };
+        let _ = std::fs::remove_file(&merged_path);
+        uploaded?;
+
+        // The staged copy is checked the same way, without decoding it
+        let staged_rows = match self
+            .storage
+            .retrieve_to_file(&staged_key, &merged_path)
+            .await
+        {
+            Ok(true) => count_file_records(&merged_path),
+            Ok(false) => Ok(0),
+            Err(e) => Err(e.into()),
+        };
+        let _ = std::fs::remove_file(&merged_path);
+        let staged_rows = staged_rows?;
+        if staged_rows != rows {
+            self.storage.delete(&staged_key).await?;
+            return Err(row_count_mismatch(&target_key, rows, staged_rows));
+        }
+
+        let journal = CompactionJournal {
+            staged_key,
+            target_key,
+            sources: group.iter().map(|object| object.key.clone()).collect(),
+            rows,
+            copied: false,
+        };
+        self.store_journal(&journal).await?;
+
+        self.commit(&journal).await?;
+        Ok(journal)
+    }
+
+    async fn merge_to_file<T: TableRow>(
+        &self,
+        group: &[RangeObject],
+        path: &Path,
+    ) -> DuneResult<(PathBuf, usize)> {
+        let mut writer = None;
+        let mut rows = 0;
+        let source_path = path.with_extension("source");
+
+        for object in group {
+            let appended = match self
+                .storage
+                .retrieve_to_file(&object.key, &source_path)
+                .await
+            {
+                Ok(true) => append_source::<T>(&source_path, path, &mut writer),
+                Ok(false) => Err(DuneError::Other(anyhow::anyhow!(
+                    "Source {} disappeared",
+                    object.key
+                ))),
+                Err(e) => Err(e.into()),
+            };
+            let _ = std::fs::remove_file(&source_path);
+            rows += appended?;
+        }
+
+        let writer = writer.ok_or_else(|| {
+            DuneError::Other(anyhow::anyhow!("Cannot merge an empty group"))
+        })?;
+        Ok((writer.finalize_path()?, rows))
+    }
+}
+
+/// Streams the records of the downloaded `source` into the merged file at
+/// `path`, creating its writer on the first source. Returns the rows appended.
+fn append_source<T: TableRow>(
+    source: &Path,
+    path: &Path,
+    writer: &mut Option<AvroFileWriter<T>>,
+) -> DuneResult<usize> {
+    if writer.is_none() {
+        // Keep the codec of the first source for the merged file
+        let codec = read_codec_metadata_from(File::open(source)?)?.unwrap_or_default();
+        *writer = Some(AvroParser::new(codec).file_writer_with_schema::<T>(path)?);
+    }
+    let writer = writer.as_mut().expect("Writer was just created");
+
+    let records = AvroParser::default()
+        .reader_with_schema::<T>()?
+        .records(BufReader::new(File::open(source)?))?;
+    let mut rows = 0;
+    for record in records {
+        let mut record = record?;
+        // Older sources gain the columns derived from their own
+        record.upgrade();
+        writer.append(&record)?;
+        rows += 1;
+    }
+    writer.flush()?;
+    Ok(rows)
+}
+
+/// Rows of a local Avro file, counted from its block headers
+fn count_file_records(path: &Path) -> DuneResult<usize> {
+    Ok(count_records(BufReader::new(File::open(path)?))? as usize)
+}
+
+fn row_count_mismatch(target_key: &str, rows: usize, merged_rows: usize) -> DuneError {
+    DuneError::Other(anyhow::anyhow!(
+        "Row count mismatch for {target_key}: sources have {rows}, merged file has {merged_rows}"
+    ))
+}
+
+/// Compacts every range file of `table`, resuming unfinished merges first.
+pub async fn compact_table<T: TableRow>(
+    storage: &S3Storage,
+    network: FuelNetwork,
+    table: S3TableName,
+    target_size: u64,
+    dry_run: bool,
+) -> DuneResult<CompactionReport> {
+    let compactor = Compactor::new(storage, network, table);
+    let table_prefix = S3KeyBuilder::new(network).with_table(table).table_prefix();
+
+    // Merging would delete files that Iceberg snapshots still reference
+    if storage
+        .retrieve(&version_hint_key(&table_prefix))
+        .await?
+        .is_some()
+    {
+        return Err(DuneError::Other(anyhow::anyhow!(
+            "Table {table} has Iceberg metadata and cannot be compacted"
+        )));
+    }
+
+    let mut report = CompactionReport::default();
+    if !dry_run {
+        report.resumed = compactor.resume(&table_prefix).await?;
+    }
+
+    let objects = storage
+        .list_objects(&table_prefix)
+        .await?
+        .into_iter()
+        .filter_map(RangeObject::from_stored)
+        .collect();
+
+    for group in plan_compaction(objects, target_size) {
+        tracing::info!(
+            "Merging {} files of {table} from {} to {}",
+            group.len(),
+            group[0].start,
+            group[group.len() - 1].end
+        );
+        report.source_files += group.len();
+        if dry_run {
+            continue;
+        }
+        let journal = compactor.merge::<T>(&group).await?;
+        report.merged_files += 1;
+        report.rows += journal.rows;
+    }
+
+    Ok(report)
+}
+
+/// Entry point of the `compact` command
+pub async fn run(args: CompactArgs) -> anyhow::Result<()> {
+    let storage = S3Storage::new(S3StorageOpts::admin_opts()).await?;
+    let network = FuelNetwork::load_from_env();
+    let target_size = args.target_size_mb * 1024 * 1024;
+
+    let tables = if args.tables.is_empty() {
+        S3TableName::ROW_TABLES.to_vec()
+    } else {
+        args.tables
+    };
+
+    for table in tables {
+        let report = with_table_row!(table, |Row| {
+            compact_table::<Row>(&storage, network, table, target_size, args.dry_run)
+                .await?
+        })
+        .ok_or_else(|| anyhow::anyhow!("The metadata table has no range files"))?;
+        tracing::info!(
+            "Compacted {table}: {} resumed, {} source files into {} files, {} rows",
+            report.resumed,
+            report.source_files,
+            report.merged_files,
+            report.rows
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn object(key: &str, size: u64) -> StoredObject {
+        StoredObject {
+            key: key.to_string(),
+            size,
+        }
+    }
+
+    #[test]
+    fn test_range_object_skips_hidden_and_foreign_keys() {
+        assert!(
+            RangeObject::from_stored(object(
+                "v1/local/blocks/0000000001-0000000010.avro",
+                1
+            ))
+            .is_some()
+        );
+        assert!(
+            RangeObject::from_stored(object(
+                "v1/local/blocks/_staging/0000000001-0000000010.avro",
+                1
+            ))
+            .is_none()
+        );
+        assert!(
+            RangeObject::from_stored(object(
+                "v1/local/metadata/latest_block_height.txt",
+                1
+            ))
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_plan_compaction_groups_adjacent_small_files() {
+        let objects = [
+            object("v1/local/blocks/0000000001-0000000010.avro", 10),
+            object("v1/local/blocks/0000000011-0000000020.avro", 10),
+            object("v1/local/blocks/0000000021-0000000030.avro", 10),
+            // Too large: stays as is and breaks the run
+            object("v1/local/blocks/0000000031-0000000040.avro", 100),
+            object("v1/local/blocks/0000000041-0000000050.avro", 10),
+            // Gap in heights
+            object("v1/local/blocks/0000000061-0000000070.avro", 10),
+            object("v1/local/blocks/0000000071-0000000080.avro", 10),
+            // Different partition
+            object(
+                "v1/local/blocks/date=2025-04-19/0000000081-0000000090.avro",
+                10,
+            ),
+        ]
+        .into_iter()
+        .filter_map(RangeObject::from_stored)
+        .collect();
+
+        let groups = plan_compaction(objects, 25);
+        let ranges: Vec<_> = groups
+            .iter()
+            .map(|group| (*group[0].start, *group[group.len() - 1].end, group.len()))
+            .collect();
+
+        // 1..=30 exceeds the target with three files, so it merges two of them
+        assert_eq!(ranges, vec![(1, 20, 2), (61, 80, 2)]);
+    }
+
+    #[test]
+    fn test_drop_covered_keeps_merged_files() {
+        let objects = [
+            object("v1/local/blocks/0000000001-0000000010.avro", 10),
+            object("v1/local/blocks/0000000011-0000000020.avro", 10),
+            // Merged file copied into place, sources not deleted yet
+            object("v1/local/blocks/0000000001-0000000020.avro", 20),
+            object("v1/local/blocks/0000000021-0000000030.avro", 10),
+            // Same range in two places
+            object(
+                "v1/local/blocks/date=2025-04-19/0000000031-0000000040.avro",
+                10,
+            ),
+            object("v1/local/blocks/0000000031-0000000040.avro", 10),
+        ]
+        .into_iter()
+        .filter_map(RangeObject::from_stored)
+        .collect();
+
+        let keys: Vec<_> = drop_covered(objects)
+            .into_iter()
+            .map(|object| object.key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "v1/local/blocks/0000000001-0000000020.avro",
+                "v1/local/blocks/0000000021-0000000030.avro",
+                "v1/local/blocks/0000000031-0000000040.avro",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_journal_without_copied_flag_is_not_copied() {
+        let journal: CompactionJournal = serde_json::from_str(
+            r#"{"staged_key":"a/_staging/f","target_key":"a/f","sources":["a/s"],"rows":3}"#,
+        )
+        .unwrap();
+        assert!(!journal.copied);
+    }
+}
@@ -0,0 +1,440 @@
+//! Reads exported range files back as typed rows.
+//!
+//! [`DataLakeReader`] lists the range files of a table that overlap a height
+//! range, downloads them to disk a few at a time ahead of the consumer, and
+//! decodes their rows one by one, so neither whole files nor whole ranges are
+//! held in memory. Staging directories, table metadata and Parquet files are
+//! skipped.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use fuel_streams_types::BlockHeight;
+use futures::{
+    Stream,
+    StreamExt,
+    TryStreamExt,
+    future,
+    stream::{
+        self,
+        BoxStream,
+    },
+};
+
+use crate::{
+    compact::{
+        RangeObject,
+        drop_covered,
+    },
+    helpers::{
+        AvroParser,
+        AvroParserError,
+        AvroRecords,
+    },
+    s3::{
+        FuelNetwork,
+        S3KeyBuilder,
+        S3TableName,
+        Storage,
+        StorageError,
+        TableRow,
+    },
+};
+
+/// Default number of range files downloaded ahead of the consumer
+pub const DEFAULT_PREFETCH: usize = 4;
+
+/// Directory of the Iceberg metadata under a table prefix
+const METADATA_DIR: &str = "metadata/";
+
+#[derive(Debug, thiserror::Error)]
+pub enum DataLakeError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Avro(#[from] AvroParserError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Range file {0} was listed but no longer exists")]
+    Missing(String),
+}
+
+/// Stream of decoded rows.
+pub type RowStream<'a, T> = BoxStream<'a, Result<T, DataLakeError>>;
+
+/// A range file downloaded to disk, removed when dropped.
+#[derive(Debug)]
+pub struct RangeFile {
+    pub object: RangeObject,
+    path: PathBuf,
+}
+
+impl RangeFile {
+    /// Decodes the rows of the file one at a time
+    pub fn into_rows<T: TableRow>(self) -> Result<RangeRows<T>, DataLakeError> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let records = AvroParser::default()
+            .reader_with_schema::<T>()?
+            .records(reader)?;
+        Ok(RangeRows {
+            records,
+            _file: self,
+        })
+    }
+}
+
+impl Drop for RangeFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Iterator over the rows of a [`RangeFile`], keeping the file until done.
+pub struct RangeRows<T> {
+    records: AvroRecords<T, BufReader<File>>,
+    _file: RangeFile,
+}
+
+impl<T: TableRow> Iterator for RangeRows<T> {
+    type Item = Result<T, DataLakeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut row = match self.records.next()? {
+            Ok(row) => row,
+            Err(e) => return Some(Err(e.into())),
+        };
+        row.upgrade();
+        Some(Ok(row))
+    }
+}
+
+/// Reads the range files of a network's tables from storage.
+#[derive(Debug)]
+pub struct DataLakeReader<S> {
+    storage: Arc<S>,
+    network: FuelNetwork,
+    prefetch: usize,
+    download_dir: PathBuf,
+}
+
+impl<S: Storage> DataLakeReader<S> {
+    pub fn new(storage: Arc<S>, network: FuelNetwork) -> Self {
+        Self {
+            storage,
+            network,
+            prefetch: DEFAULT_PREFETCH,
+            download_dir: std::env::temp_dir(),
+        }
+    }
+
+    /// Number of range files downloaded concurrently ahead of the consumer
+    pub fn with_prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch.max(1);
+        self
+    }
+
+    /// Directory the range files are downloaded to while being read
+    pub fn with_download_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.download_dir = dir.into();
+        self
+    }
+
+    /// Range files of `table` overlapping `from..=to`, in height order
+    pub async fn discover(
+        &self,
+        table: S3TableName,
+        from: BlockHeight,
+        to: BlockHeight,
+    ) -> Result<Vec<RangeObject>, DataLakeError> {
+        let prefix = S3KeyBuilder::new(self.network)
+            .with_table(table)
+            .table_prefix();
+
+        let objects = self
+            .storage
+            .list_objects(&prefix)
+            .await?
+            .into_iter()
+            .filter(|object| {
+                !object
+                    .key
+                    .strip_prefix(&prefix)
+                    .is_some_and(|key| key.starts_with(METADATA_DIR))
+            })
+            .filter_map(RangeObject::from_stored)
+            .filter(|object| object.start <= to && object.end >= from)
+            .collect();
+        // Sources of a merge being committed are covered by the merged file
+        Ok(drop_covered(objects))
+    }
+
+    /// Downloads `objects` in order, up to the prefetch count at a time
+    pub fn files(
+        &self,
+        objects: Vec<RangeObject>,
+    ) -> impl Stream<Item = Result<RangeFile, DataLakeError>> + Send + '_ {
+        stream::iter(objects)
+            .map(|object| self.download(object))
+            .buffered(self.prefetch)
+    }
+
+    async fn download(&self, object: RangeObject) -> Result<RangeFile, DataLakeError> {
+        let filename = object.key.replace('/', "_");
+        let path = self.download_dir.join(format!(
+            "dune-lake-{}-{}-{filename}",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+
+        if !self.storage.retrieve_to_file(&object.key, &path).await? {
+            return Err(DataLakeError::Missing(object.key));
+        }
+        Ok(RangeFile { object, path })
+    }
+
+    /// Rows of the table of `T` whose block height is within `from..=to`, in
+    /// file order
+    pub async fn rows<T: TableRow>(
+        &self,
+        from: BlockHeight,
+        to: BlockHeight,
+    ) -> Result<RowStream<'_, T>, DataLakeError> {
+        let objects = self.discover(T::TABLE, from, to).await?;
+        Ok(self.read_rows(objects, from, to))
+    }
+
+    /// Rows of the already discovered `objects` whose block height is within
+    /// `from..=to`, in file order
+    pub fn read_rows<T: TableRow>(
+        &self,
+        objects: Vec<RangeObject>,
+        from: BlockHeight,
+        to: BlockHeight,
+    ) -> RowStream<'_, T> {
+        let range = i64::from(*from)..=i64::from(*to);
+
+        self.files(objects)
+            .map(|file| match file.and_then(RangeFile::into_rows::<T>) {
+                Ok(rows) => stream::iter(rows).left_stream(),
+                Err(e) => stream::once(future::ready(Err(e))).right_stream(),
+            })
+            .flatten()
+            // Range files may extend past the requested heights
+            .try_filter(move |row| {
+                future::ready(row.block_height().is_some_and(|h| range.contains(&h)))
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::Mutex,
+    };
+
+    use async_trait::async_trait;
+    use fuel_streams_domains::mocks::MockBlock;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        s3::{
+            S3StorageOpts,
+            StoredObject,
+        },
+        schemas::AvroBlock,
+    };
+
+    #[derive(Debug, Default)]
+    struct MemoryStorage {
+        objects: Mutex<BTreeMap<String, Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl Storage for MemoryStorage {
+        type Config = S3StorageOpts;
+
+        async fn new(_config: S3StorageOpts) -> Result<Self, StorageError> {
+            Ok(Self::default())
+        }
+
+        async fn store(&self, key: &str, data: Vec<u8>) -> Result<(), StorageError> {
+            self.objects.lock().unwrap().insert(key.to_string(), data);
+            Ok(())
+        }
+
+        async fn retrieve(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+            Ok(self.objects.lock().unwrap().get(key).cloned())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), StorageError> {
+            self.objects.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        async fn list_objects(
+            &self,
+            prefix: &str,
+        ) -> Result<Vec<StoredObject>, StorageError> {
+            Ok(self
+                .objects
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, data)| StoredObject {
+                    key: key.clone(),
+                    size: data.len() as u64,
+                })
+                .collect())
+        }
+    }
+
+    fn blocks_file(heights: std::ops::RangeInclusive<u32>) -> Vec<u8> {
+        let mut writer = AvroParser::default()
+            .writer_with_schema::<AvroBlock>()
+            .unwrap();
+        for height in heights {
+            writer
+                .append(&AvroBlock::new(&MockBlock::build(height.into())))
+                .unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    async fn test_reader() -> DataLakeReader<MemoryStorage> {
+        let storage = MemoryStorage::default();
+        let prefix = S3KeyBuilder::new(FuelNetwork::Local)
+            .with_table(S3TableName::Blocks)
+            .table_prefix();
+        for (key, heights) in [
+            ("0000000001-0000000010.avro", 1..=10),
+            ("date=2025-04-18/0000000011-0000000020.avro", 11..=20),
+            ("0000000021-0000000030.avro", 21..=30),
+            // Skipped: staged merge, metadata and Parquet copies
+            ("_staging/0000000001-0000000020.avro", 1..=20),
+            ("metadata/0000000001-0000000010.avro", 1..=10),
+        ] {
+            storage
+                .store(&format!("{prefix}{key}"), blocks_file(heights))
+                .await
+                .unwrap();
+        }
+        storage
+            .store(&format!("{prefix}0000000001-0000000010.parquet"), vec![1])
+            .await
+            .unwrap();
+
+        DataLakeReader::new(Arc::new(storage), FuelNetwork::Local).with_prefetch(2)
+    }
+
+    #[tokio::test]
+    async fn test_discover_range_files() {
+        let reader = test_reader().await;
+
+        let ranges = |objects: Vec<RangeObject>| {
+            objects
+                .iter()
+                .map(|object| (*object.start, *object.end))
+                .collect::<Vec<_>>()
+        };
+        let objects = reader
+            .discover(S3TableName::Blocks, 5.into(), 25.into())
+            .await
+            .unwrap();
+        assert_eq!(ranges(objects), vec![(1, 10), (11, 20), (21, 30)]);
+
+        let objects = reader
+            .discover(S3TableName::Blocks, 11.into(), 11.into())
+            .await
+            .unwrap();
+        assert_eq!(ranges(objects), vec![(11, 20)]);
+
+        let objects = reader
+            .discover(S3TableName::Blocks, 31.into(), 40.into())
+            .await
+            .unwrap();
+        assert!(objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_discover_during_compaction_commit() {
+        let reader = test_reader().await;
+        let prefix = S3KeyBuilder::new(FuelNetwork::Local)
+            .with_table(S3TableName::Blocks)
+            .table_prefix();
+        // The merge of two sources is copied into place, and the sources are
+        // not deleted yet
+        for (key, heights) in [
+            ("0000000031-0000000035.avro", 31..=35),
+            ("0000000036-0000000040.avro", 36..=40),
+            ("0000000031-0000000040.avro", 31..=40),
+        ] {
+            reader
+                .storage
+                .store(&format!("{prefix}{key}"), blocks_file(heights))
+                .await
+                .unwrap();
+        }
+
+        let objects = reader
+            .discover(S3TableName::Blocks, 1.into(), 40.into())
+            .await
+            .unwrap();
+        let ranges: Vec<_> = objects
+            .iter()
+            .map(|object| (*object.start, *object.end))
+            .collect();
+        assert_eq!(ranges, vec![(1, 10), (11, 20), (21, 30), (31, 40)]);
+
+        let heights = reader
+            .rows::<AvroBlock>(1.into(), 40.into())
+            .await
+            .unwrap()
+            .map_ok(|block| block.height.unwrap())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(heights, (1..=40).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_rows_are_filtered_to_the_range() {
+        let reader = test_reader().await;
+        let heights = reader
+            .rows::<AvroBlock>(8.into(), 22.into())
+            .await
+            .unwrap()
+            .map_ok(|block| block.height.unwrap())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(heights, (8..=22).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_downloads_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let reader = test_reader().await.with_download_dir(dir.path());
+
+        let objects = reader
+            .discover(S3TableName::Blocks, 1.into(), 30.into())
+            .await
+            .unwrap();
+        let files: Vec<_> = reader.files(objects).try_collect().await.unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 3);
+
+        let rows = files
+            .into_iter()
+            .map(|file| file.into_rows::<AvroBlock>().unwrap().count())
+            .sum::<usize>();
+        assert_eq!(rows, 30);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
@@ -1,8 +1,16 @@
 use thiserror::Error as ThisError;
 
 use crate::{
-    helpers::AvroParserError,
+    abi::AbiError,
+    data_lake::DataLakeError,
+    helpers::{
+        AvroParserError,
+        ParquetWriterError,
+    },
+    postgres::PostgresError,
     s3::StorageError,
+    schema_registry::SchemaRegistryError,
+    schemas::RowDecodeError,
 };
 
 #[derive(ThisError, Debug)]
@@ -17,8 +25,20 @@ pub enum DuneError {
     #[error(transparent)]
     Avro(#[from] AvroParserError),
     #[error(transparent)]
+    Parquet(#[from] ParquetWriterError),
+    #[error(transparent)]
     S3(#[from] StorageError),
     #[error(transparent)]
+    Schema(#[from] SchemaRegistryError),
+    #[error(transparent)]
+    Postgres(#[from] PostgresError),
+    #[error(transparent)]
+    DataLake(#[from] DataLakeError),
+    #[error(transparent)]
+    Decode(#[from] RowDecodeError),
+    #[error(transparent)]
+    Abi(#[from] AbiError),
+    #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
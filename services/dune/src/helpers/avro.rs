@@ -2,15 +2,18 @@ use std::{
     any::TypeId,
     collections::HashMap,
     fs::File,
-    io::{BufWriter, Write},
+    io::{BufWriter, ErrorKind, Read, Write},
     path::{Path, PathBuf},
     sync::RwLock,
 };
 
-use crate::alloc_counter;
+use crate::{
+    alloc_counter,
+    helpers::{AvroCodec, CODEC_METADATA_KEY},
+};
 
 use apache_avro::{
-    AvroSchema, Codec, Reader, Schema, Writer, from_value,
+    AvroSchema, Reader, Schema, Writer, from_value,
     schema::{Namespace, derive::AvroSchemaComponent},
 };
 use serde::{Serialize, de::DeserializeOwned};
@@ -77,17 +80,18 @@ impl<T> AvroWriter<T>
 where
     T: AvroSchema + AvroSchemaComponent + Serialize + Send + Sync + 'static,
 {
-    pub fn new(codec: Codec) -> Self {
+    pub fn new(codec: AvroCodec) -> Result<Self, AvroParserError> {
         let schema = get_cached_schema::<T>();
-        let writer = Writer::builder()
+        let mut writer = Writer::builder()
             .schema(schema)
-            .codec(codec)
+            .codec(codec.into())
             .writer(Vec::new())
             .build();
-        Self {
+        writer.add_user_metadata(CODEC_METADATA_KEY.to_string(), codec.to_string())?;
+        Ok(Self {
             writer,
             _phantom: std::marker::PhantomData,
-        }
+        })
     }
 
     pub fn append(&mut self, value: &T) -> Result<(), AvroParserError> {
@@ -112,19 +116,24 @@ impl<T> AvroFileWriter<T>
 where
     T: AvroSchema + AvroSchemaComponent + Serialize + Send + Sync + 'static,
 {
-    /// Creates a new file-based Avro writer at the specified path
-    pub fn new(path: impl AsRef<Path>, codec: Codec) -> Result<Self, AvroParserError> {
+    /// Creates a new file-based Avro writer at the specified path.
+    /// The codec name is recorded in the file header metadata.
+    pub fn new(
+        path: impl AsRef<Path>,
+        codec: AvroCodec,
+    ) -> Result<Self, AvroParserError> {
         let file_path = path.as_ref().to_path_buf();
         let file = File::create(&file_path)
             .map_err(|e| AvroParserError::Io(format!("Failed to create file: {}", e)))?;
         let buf_writer = BufWriter::new(file);
 
         let schema = get_cached_schema::<T>();
-        let writer = Writer::builder()
+        let mut writer = Writer::builder()
             .schema(schema)
-            .codec(codec)
+            .codec(codec.into())
             .writer(buf_writer)
             .build();
+        writer.add_user_metadata(CODEC_METADATA_KEY.to_string(), codec.to_string())?;
 
         alloc_counter::inc(&alloc_counter::AVRO_FILE_WRITER);
         Ok(Self {
@@ -185,26 +194,26 @@ impl<T> Drop for AvroFileWriter<T> {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct AvroParser {
-    codec: Option<Codec>,
+    codec: AvroCodec,
 }
 
-impl Default for AvroParser {
-    fn default() -> Self {
-        Self {
-            codec: Some(Codec::Deflate),
-        }
+impl AvroParser {
+    pub fn new(codec: AvroCodec) -> Self {
+        Self { codec }
+    }
+
+    pub fn codec(&self) -> AvroCodec {
+        self.codec
     }
-}
 
-impl AvroParser {
     pub fn writer_with_schema<
         T: AvroSchema + AvroSchemaComponent + Serialize + Send + Sync + 'static,
     >(
         &self,
     ) -> Result<AvroWriter<T>, AvroParserError> {
-        Ok(AvroWriter::new(self.codec.unwrap_or(Codec::Deflate)))
+        AvroWriter::new(self.codec)
     }
 
     /// Creates a file-based Avro writer that writes directly to disk.
@@ -215,7 +224,7 @@ impl AvroParser {
         &self,
         path: impl AsRef<Path>,
     ) -> Result<AvroFileWriter<T>, AvroParserError> {
-        AvroFileWriter::new(path, self.codec.unwrap_or(Codec::Deflate))
+        AvroFileWriter::new(path, self.codec)
     }
 
     pub fn reader_with_schema<
@@ -264,10 +273,145 @@ where
         }
         Ok(list)
     }
+
+    /// Decodes the records of `reader` one at a time, so only the Avro block
+    /// being read is held in memory rather than the whole file.
+    pub fn records<R: Read>(self, reader: R) -> Result<AvroRecords<T, R>, AvroParserError> {
+        let reader = Reader::with_schema(get_cached_schema::<T>(), reader)?;
+        Ok(AvroRecords {
+            reader,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+}
+
+/// Iterator over the decoded records of an Avro file.
+pub struct AvroRecords<T, R: Read> {
+    reader: Reader<'static, R>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, R> Iterator for AvroRecords<T, R>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    type Item = Result<T, AvroParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.reader.next()?;
+        Some(record.and_then(|record| from_value::<T>(&record)).map_err(Into::into))
+    }
+}
+
+/// Reads the codec recorded in an Avro file header by [`AvroWriter`] or
+/// [`AvroFileWriter`]. Returns `None` for files written without it.
+pub fn read_codec_metadata(data: &[u8]) -> Result<Option<AvroCodec>, AvroParserError> {
+    read_codec_metadata_from(data)
+}
+
+/// Same as [`read_codec_metadata`], reading only the header from `reader`.
+pub fn read_codec_metadata_from<R: Read>(reader: R) -> Result<Option<AvroCodec>, AvroParserError> {
+    let reader = Reader::new(reader)?;
+    reader
+        .user_metadata()
+        .get(CODEC_METADATA_KEY)
+        .map(|value| {
+            String::from_utf8_lossy(value)
+                .parse()
+                .map_err(|e: anyhow::Error| AvroParserError::Io(e.to_string()))
+        })
+        .transpose()
+}
+
+/// Counts the records of an Avro object container file from the count that
+/// heads each of its blocks, without decompressing or decoding them.
+pub fn count_records<R: Read>(reader: R) -> Result<u64, AvroParserError> {
+    count_container_records(reader).map_err(|e| AvroParserError::Io(e.to_string()))
+}
+
+fn count_container_records<R: Read>(mut reader: R) -> std::io::Result<u64> {
+    let invalid = |reason: &str| std::io::Error::new(ErrorKind::InvalidData, reason);
+
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != *b"Obj\x01" {
+        return Err(invalid("not an Avro object container file"));
+    }
+    // The metadata map: blocks of key and value pairs, ending with an empty
+    // block. A negative count is followed by the size of the block.
+    loop {
+        let count = match expect_long(&mut reader)? {
+            0 => break,
+            count if count < 0 => {
+                expect_long(&mut reader)?;
+                count.unsigned_abs()
+            }
+            count => count as u64,
+        };
+        for _ in 0..count * 2 {
+            let len = expect_long(&mut reader)?;
+            skip(&mut reader, len)?;
+        }
+    }
+    let mut sync = [0u8; 16];
+    reader.read_exact(&mut sync)?;
+
+    let mut records = 0u64;
+    let mut block_sync = [0u8; 16];
+    while let Some(count) = read_long(&mut reader)? {
+        let size = expect_long(&mut reader)?;
+        skip(&mut reader, size)?;
+        reader.read_exact(&mut block_sync)?;
+        if count < 0 || block_sync != sync {
+            return Err(invalid("corrupt Avro block"));
+        }
+        records += count as u64;
+    }
+    Ok(records)
+}
+
+/// Reads a zigzag encoded long, `None` at the end of the input.
+fn read_long<R: Read>(reader: &mut R) -> std::io::Result<Option<i64>> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    let mut byte = [0u8; 1];
+    loop {
+        if reader.read(&mut byte)? == 0 {
+            return match shift {
+                0 => Ok(None),
+                _ => Err(ErrorKind::UnexpectedEof.into()),
+            };
+        }
+        if shift > 63 {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, "long overflow"));
+        }
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Ok(Some((value >> 1) as i64 ^ -((value & 1) as i64)))
+}
+
+fn expect_long<R: Read>(reader: &mut R) -> std::io::Result<i64> {
+    read_long(reader)?.ok_or_else(|| ErrorKind::UnexpectedEof.into())
+}
+
+fn skip<R: Read>(reader: &mut R, len: i64) -> std::io::Result<()> {
+    let len = u64::try_from(len)
+        .map_err(|_| std::io::Error::new(ErrorKind::InvalidData, "negative length"))?;
+    let skipped = std::io::copy(&mut reader.take(len), &mut std::io::sink())?;
+    match skipped == len {
+        true => Ok(()),
+        false => Err(ErrorKind::UnexpectedEof.into()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
     use serde::Deserialize;
 
     use super::*;
@@ -302,4 +446,83 @@ mod tests {
         assert!(deserialized.len() == 1);
         assert!(test == deserialized[0]);
     }
+
+    #[test]
+    fn test_avro_parser_codecs() {
+        let test = Test {
+            a: 42,
+            b: "bar".to_owned(),
+        };
+
+        for codec in AvroCodec::ALL {
+            let parser = AvroParser::new(codec);
+            let mut avro_writer = parser.writer_with_schema::<Test>().unwrap();
+            avro_writer.append(&test).unwrap();
+            let serialized = avro_writer.into_inner().unwrap();
+
+            assert_eq!(read_codec_metadata(&serialized).unwrap(), Some(codec));
+            let deserialized = parser
+                .reader_with_schema::<Test>()
+                .unwrap()
+                .deserialize(&serialized)
+                .unwrap();
+            assert_eq!(
+                deserialized,
+                vec![Test {
+                    a: 42,
+                    b: "bar".to_owned(),
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_avro_records_stream_from_reader() {
+        let parser = AvroParser::default();
+        let mut avro_writer = parser.writer_with_schema::<Test>().unwrap();
+        for a in 0..3 {
+            avro_writer
+                .append(&Test {
+                    a,
+                    b: a.to_string(),
+                })
+                .unwrap();
+        }
+        let serialized = avro_writer.into_inner().unwrap();
+
+        let records = parser
+            .reader_with_schema::<Test>()
+            .unwrap()
+            .records(std::io::Cursor::new(serialized))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.iter().map(|t| t.a).collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_count_records() {
+        let dir = tempfile::tempdir().unwrap();
+        for codec in AvroCodec::ALL {
+            let path = dir.path().join(format!("{codec}.avro"));
+            let mut writer = AvroParser::new(codec).file_writer_with_schema::<Test>(&path).unwrap();
+            for a in 0..5 {
+                writer.append(&Test { a, b: a.to_string() }).unwrap();
+                if a % 2 == 0 {
+                    writer.flush().unwrap();
+                }
+            }
+            writer.finalize_path().unwrap();
+
+            let data = std::fs::read(&path).unwrap();
+            assert_eq!(count_records(data.as_slice()).unwrap(), 5);
+            assert_eq!(read_codec_metadata_from(File::open(&path).unwrap()).unwrap(), Some(codec));
+            assert!(count_records(&data[..data.len() - 1]).is_err());
+        }
+
+        let empty =
+            AvroParser::default().writer_with_schema::<Test>().unwrap().into_inner().unwrap();
+        assert_eq!(count_records(empty.as_slice()).unwrap(), 0);
+        assert!(count_records(&b"not avro"[..]).is_err());
+    }
 }
@@ -0,0 +1,114 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+use apache_avro::Codec;
+
+use super::TableSettings;
+
+/// User metadata key under which the codec name is recorded in every Avro
+/// file header, so consumers can tell how a file was written without
+/// decoding any block.
+pub const CODEC_METADATA_KEY: &str = "dune.codec";
+
+/// Compression codecs supported for the exported Avro files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AvroCodec {
+    Null,
+    #[default]
+    Deflate,
+    Snappy,
+    Zstandard,
+    Bzip2,
+}
+
+impl AvroCodec {
+    pub const ALL: [AvroCodec; 5] = [
+        AvroCodec::Null,
+        AvroCodec::Deflate,
+        AvroCodec::Snappy,
+        AvroCodec::Zstandard,
+        AvroCodec::Bzip2,
+    ];
+}
+
+impl Display for AvroCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AvroCodec::Null => write!(f, "null"),
+            AvroCodec::Deflate => write!(f, "deflate"),
+            AvroCodec::Snappy => write!(f, "snappy"),
+            AvroCodec::Zstandard => write!(f, "zstandard"),
+            AvroCodec::Bzip2 => write!(f, "bzip2"),
+        }
+    }
+}
+
+impl FromStr for AvroCodec {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "null" | "none" => Ok(AvroCodec::Null),
+            "deflate" => Ok(AvroCodec::Deflate),
+            "snappy" => Ok(AvroCodec::Snappy),
+            "zstandard" | "zstd" => Ok(AvroCodec::Zstandard),
+            "bzip2" | "bzip" => Ok(AvroCodec::Bzip2),
+            _ => Err(anyhow::anyhow!("Unknown Avro codec {input}")),
+        }
+    }
+}
+
+impl From<AvroCodec> for Codec {
+    fn from(codec: AvroCodec) -> Self {
+        match codec {
+            AvroCodec::Null => Codec::Null,
+            AvroCodec::Deflate => Codec::Deflate,
+            AvroCodec::Snappy => Codec::Snappy,
+            AvroCodec::Zstandard => Codec::Zstandard,
+            AvroCodec::Bzip2 => Codec::Bzip2,
+        }
+    }
+}
+
+/// Codec selection per exported table, e.g. `deflate,receipts=zstandard`.
+pub type TableCodecs = TableSettings<AvroCodec>;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::s3::S3TableName;
+
+    #[test]
+    fn test_codec_round_trip() {
+        for codec in AvroCodec::ALL {
+            assert_eq!(codec.to_string().parse::<AvroCodec>().unwrap(), codec);
+        }
+        assert!("lz4".parse::<AvroCodec>().is_err());
+    }
+
+    #[test]
+    fn test_table_codecs_parsing() {
+        let codecs: TableCodecs =
+            "snappy,receipts=zstandard,blocks=null".parse().unwrap();
+        assert_eq!(codecs.for_table(S3TableName::Blocks), AvroCodec::Null);
+        assert_eq!(
+            codecs.for_table(S3TableName::Transactions),
+            AvroCodec::Snappy
+        );
+        assert_eq!(
+            codecs.for_table(S3TableName::Receipts),
+            AvroCodec::Zstandard
+        );
+        assert_eq!(codecs.to_string(), "snappy,blocks=null,receipts=zstandard");
+
+        let default: TableCodecs = "".parse().unwrap();
+        assert_eq!(default, TableCodecs::default());
+        assert_eq!(default.for_table(S3TableName::Blocks), AvroCodec::Deflate);
+        assert!("receipts=lz4".parse::<TableCodecs>().is_err());
+        assert!("nope=deflate".parse::<TableCodecs>().is_err());
+    }
+}
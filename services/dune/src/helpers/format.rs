@@ -0,0 +1,65 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+use super::TableSettings;
+
+/// File formats supported for the exported tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FileFormat {
+    #[default]
+    Avro,
+    Parquet,
+}
+
+impl FileFormat {
+    /// Extension used for the object keys, without the leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FileFormat::Avro => "avro",
+            FileFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl Display for FileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+impl FromStr for FileFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "avro" => Ok(FileFormat::Avro),
+            "parquet" => Ok(FileFormat::Parquet),
+            _ => Err(anyhow::anyhow!("Unknown file format {input}")),
+        }
+    }
+}
+
+/// File format selection per exported table, e.g. `avro,receipts=parquet`.
+pub type TableFormats = TableSettings<FileFormat>;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::s3::S3TableName;
+
+    #[test]
+    fn test_table_formats_parsing() {
+        let formats: TableFormats = "avro,receipts=parquet".parse().unwrap();
+        assert_eq!(formats.for_table(S3TableName::Blocks), FileFormat::Avro);
+        assert_eq!(
+            formats.for_table(S3TableName::Receipts),
+            FileFormat::Parquet
+        );
+        assert_eq!(formats.to_string(), "avro,receipts=parquet");
+        assert!("orc".parse::<TableFormats>().is_err());
+    }
+}
@@ -1,7 +1,15 @@
 mod avro;
 mod avro_bytes;
+mod codec;
+mod format;
+mod parquet_writer;
+mod table_settings;
 mod test_helpers;
 
 pub use avro::*;
 pub use avro_bytes::*;
+pub use codec::*;
+pub use format::*;
+pub use parquet_writer::*;
+pub use table_settings::*;
 pub use test_helpers::*;
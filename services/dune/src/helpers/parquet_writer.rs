@@ -0,0 +1,258 @@
+use std::{
+    fs::File,
+    io::{
+        BufWriter,
+        Write,
+    },
+    marker::PhantomData,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::Arc,
+};
+
+use arrow::datatypes::{
+    FieldRef,
+    Schema,
+};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{
+        Compression,
+        ZstdLevel,
+    },
+    file::properties::{
+        EnabledStatistics,
+        WriterProperties,
+    },
+    schema::types::ColumnPath,
+};
+use serde::{
+    Serialize,
+    de::DeserializeOwned,
+};
+use serde_arrow::{
+    ArrayBuilder,
+    schema::{
+        SchemaLike,
+        TracingOptions,
+    },
+};
+
+use crate::{
+    alloc_counter,
+    schemas::AvroRow,
+};
+
+/// Maximum number of rows per row group. The in-progress row group is kept
+/// in memory by the writer, so this also bounds the memory used per table.
+pub const PARQUET_ROW_GROUP_SIZE: usize = 64 * 1024;
+
+/// Parquet writer error types.
+#[derive(Debug, thiserror::Error)]
+pub enum ParquetWriterError {
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error(transparent)]
+    Arrow(#[from] serde_arrow::Error),
+    #[error("IO error: {0}")]
+    Io(String),
+}
+
+/// Arrow fields of a row type, traced from its serde representation.
+/// Column names match the Avro field names, nested structs and vectors
+/// become Parquet structs and lists, and `AvroBytes` becomes binary.
+pub fn parquet_fields<T: DeserializeOwned>() -> Result<Vec<FieldRef>, ParquetWriterError>
+{
+    Ok(Vec::<FieldRef>::from_type::<T>(TracingOptions::default())?)
+}
+
+/// Writer properties for a table. Statistics are only kept for the block
+/// height and time columns: engines prune row groups on them, while min/max
+/// values of the many hash columns would only bloat the footer.
+fn writer_properties<T: AvroRow>() -> WriterProperties {
+    WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .set_max_row_group_size(PARQUET_ROW_GROUP_SIZE)
+        .set_statistics_enabled(EnabledStatistics::None)
+        .set_column_statistics_enabled(
+            ColumnPath::from(T::HEIGHT_COLUMN),
+            EnabledStatistics::Page,
+        )
+        .set_column_statistics_enabled(
+            ColumnPath::from(T::TIME_COLUMN),
+            EnabledStatistics::Page,
+        )
+        .build()
+}
+
+/// A Parquet writer that writes directly to a file on disk, driven by the
+/// same row types as [`super::AvroFileWriter`].
+pub struct ParquetFileWriter<T> {
+    writer: Option<ArrowWriter<BufWriter<File>>>,
+    builder: ArrayBuilder,
+    pending_rows: usize,
+    file_path: PathBuf,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ParquetFileWriter<T>
+where
+    T: AvroRow + Serialize + DeserializeOwned,
+{
+    /// Creates a new file-based Parquet writer at the specified path.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, ParquetWriterError> {
+        let file_path = path.as_ref().to_path_buf();
+        let file = File::create(&file_path).map_err(|e| {
+            ParquetWriterError::Io(format!("Failed to create file: {}", e))
+        })?;
+
+        let fields = parquet_fields::<T>()?;
+        let schema = Arc::new(Schema::new(fields.clone()));
+        let writer = ArrowWriter::try_new(
+            BufWriter::new(file),
+            schema,
+            Some(writer_properties::<T>()),
+        )?;
+
+        alloc_counter::inc(&alloc_counter::PARQUET_FILE_WRITER);
+        Ok(Self {
+            writer: Some(writer),
+            builder: ArrayBuilder::from_arrow(&fields)?,
+            pending_rows: 0,
+            file_path,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Appends a value to the pending rows.
+    ///
+    /// Rows are kept as Arrow arrays until `flush()` encodes them.
+    pub fn append(&mut self, value: &T) -> Result<(), ParquetWriterError> {
+        self.builder.push(value)?;
+        self.pending_rows += 1;
+        Ok(())
+    }
+
+    /// Encodes the pending rows into the current row group. The row group is
+    /// written to disk once it reaches [`PARQUET_ROW_GROUP_SIZE`] rows.
+    pub fn flush(&mut self) -> Result<(), ParquetWriterError> {
+        if self.pending_rows == 0 {
+            return Ok(());
+        }
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| ParquetWriterError::Io("Writer already finalized".into()))?;
+        let batch = self.builder.to_record_batch()?;
+        writer.write(&batch)?;
+        self.pending_rows = 0;
+        Ok(())
+    }
+
+    /// Finalizes the file and returns just the path.
+    /// Pending rows are encoded and the footer is written.
+    pub fn finalize_path(mut self) -> Result<PathBuf, ParquetWriterError> {
+        self.flush()?;
+        let writer = self
+            .writer
+            .take()
+            .ok_or_else(|| ParquetWriterError::Io("Writer already finalized".into()))?;
+        let mut inner = writer.into_inner()?;
+        inner.flush().map_err(|e| {
+            ParquetWriterError::Io(format!("Failed to flush final data: {}", e))
+        })?;
+        Ok(self.file_path.clone())
+    }
+}
+
+impl<T> Drop for ParquetFileWriter<T> {
+    fn drop(&mut self) {
+        alloc_counter::dec(&alloc_counter::PARQUET_FILE_WRITER);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_streams_domains::{
+        inputs::types::MockInput,
+        outputs::types::MockOutput,
+        transactions::MockTransaction,
+    };
+    use parquet::{
+        arrow::arrow_reader::ParquetRecordBatchReaderBuilder,
+        file::{
+            reader::FileReader,
+            serialized_reader::SerializedFileReader,
+        },
+    };
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::schemas::AvroTransaction;
+
+    fn transaction(height: i64) -> AvroTransaction {
+        let inputs = vec![MockInput::contract(), MockInput::coin_signed(None)];
+        let outputs = vec![MockOutput::coin(1000), MockOutput::contract()];
+        let tx = MockTransaction::script(inputs, outputs, vec![]);
+        AvroTransaction::new(
+            &tx,
+            Some(height),
+            Some(1_700_000_000 + height),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_parquet_writer_round_trip_and_statistics() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("transactions.parquet");
+        let rows: Vec<_> = (10..20).map(transaction).collect();
+
+        let mut writer = ParquetFileWriter::<AvroTransaction>::new(&path).unwrap();
+        for row in &rows[..5] {
+            writer.append(row).unwrap();
+        }
+        writer.flush().unwrap();
+        for row in &rows[5..] {
+            writer.append(row).unwrap();
+        }
+        let path = writer.finalize_path().unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut decoded = Vec::new();
+        for batch in reader {
+            let batch = batch.unwrap();
+            decoded.extend(
+                serde_arrow::from_record_batch::<Vec<AvroTransaction>>(&batch).unwrap(),
+            );
+        }
+        assert_eq!(decoded, rows);
+
+        let file = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let row_group = file.metadata().row_group(0);
+        let stats_of = |name: &str| {
+            row_group
+                .columns()
+                .iter()
+                .find(|column| column.column_path().string() == name)
+                .and_then(|column| column.statistics().cloned())
+        };
+        let Some(parquet::file::statistics::Statistics::Int64(height)) =
+            stats_of("blockHeight")
+        else {
+            panic!("blockHeight should have int64 statistics");
+        };
+        assert_eq!(height.min_opt(), Some(&10));
+        assert_eq!(height.max_opt(), Some(&19));
+        assert!(stats_of("blockTime").is_some());
+        assert!(stats_of("id").is_none());
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    str::FromStr,
+};
+
+use crate::s3::S3TableName;
+
+/// A per-table export option: a default plus table overrides.
+///
+/// Parsed from a comma separated list where a bare value sets the default
+/// and `table=value` entries override it, e.g. `deflate,receipts=zstandard`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableSettings<T> {
+    default: T,
+    overrides: HashMap<S3TableName, T>,
+}
+
+impl<T: Copy> TableSettings<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            default,
+            overrides: HashMap::new(),
+        }
+    }
+
+    pub fn with_table(mut self, table: S3TableName, value: T) -> Self {
+        self.overrides.insert(table, value);
+        self
+    }
+
+    /// Returns the value configured for `table`, falling back to the default.
+    pub fn for_table(&self, table: S3TableName) -> T {
+        self.overrides.get(&table).copied().unwrap_or(self.default)
+    }
+}
+
+impl<T: Display> Display for TableSettings<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.default)?;
+        let mut overrides: Vec<_> = self
+            .overrides
+            .iter()
+            .map(|(table, value)| format!("{table}={value}"))
+            .collect();
+        overrides.sort();
+        for entry in overrides {
+            write!(f, ",{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromStr for TableSettings<T>
+where
+    T: Default + FromStr<Err = anyhow::Error>,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut settings = Self::default();
+        for entry in input.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.split_once('=') {
+                Some((table, value)) => {
+                    settings
+                        .overrides
+                        .insert(table.trim().parse()?, value.trim().parse()?);
+                }
+                None => settings.default = entry.parse()?,
+            }
+        }
+        Ok(settings)
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    DuneResult,
+    s3::{
+        S3Storage,
+        Storage,
+    },
+};
+
+/// Object store holding Iceberg metadata files, addressed by relative keys.
+///
+/// Tables follow the file-system catalog convention: metadata versions are
+/// `metadata/v{N}.metadata.json` and a commit succeeds only if its version
+/// did not exist yet, which is what makes a commit atomic.
+#[async_trait]
+pub trait Catalog: Send + Sync {
+    /// Absolute URI of `key`, as recorded in the table metadata
+    fn location(&self, key: &str) -> String;
+
+    async fn read(&self, key: &str) -> DuneResult<Option<Vec<u8>>>;
+
+    async fn write(&self, key: &str, data: Vec<u8>) -> DuneResult<()>;
+
+    /// Writes `key` only if it does not exist yet. Returns `false` when
+    /// another writer created it first.
+    async fn write_new(&self, key: &str, data: Vec<u8>) -> DuneResult<bool>;
+
+    /// Inverse of [`Catalog::location`] for locations inside this catalog
+    fn key_of<'a>(&self, location: &'a str) -> Option<&'a str> {
+        let root = self.location("");
+        location.strip_prefix(root.as_str())
+    }
+}
+
+/// Catalog on the local file system, for development and tests.
+#[derive(Debug, Clone)]
+pub struct LocalCatalog {
+    root: PathBuf,
+}
+
+impl LocalCatalog {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl Catalog for LocalCatalog {
+    fn location(&self, key: &str) -> String {
+        format!("file://{}/{key}", self.root.display())
+    }
+
+    async fn read(&self, key: &str) -> DuneResult<Option<Vec<u8>>> {
+        match std::fs::read(self.root.join(key)) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn write(&self, key: &str, data: Vec<u8>) -> DuneResult<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    async fn write_new(&self, key: &str, data: Vec<u8>) -> DuneResult<bool> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path);
+        match file {
+            Ok(mut file) => {
+                file.write_all(&data)?;
+                Ok(true)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Catalog in an S3-compatible bucket, committing with conditional writes.
+#[derive(Debug, Clone)]
+pub struct S3Catalog {
+    storage: Arc<S3Storage>,
+    bucket: String,
+}
+
+impl S3Catalog {
+    pub fn new(storage: Arc<S3Storage>) -> Self {
+        let bucket = storage.bucket();
+        Self { storage, bucket }
+    }
+}
+
+#[async_trait]
+impl Catalog for S3Catalog {
+    fn location(&self, key: &str) -> String {
+        format!("s3://{}/{key}", self.bucket)
+    }
+
+    async fn read(&self, key: &str) -> DuneResult<Option<Vec<u8>>> {
+        Ok(self.storage.retrieve(key).await?)
+    }
+
+    async fn write(&self, key: &str, data: Vec<u8>) -> DuneResult<()> {
+        Ok(self.storage.store(key, data).await?)
+    }
+
+    async fn write_new(&self, key: &str, data: Vec<u8>) -> DuneResult<bool> {
+        Ok(self.storage.store_if_absent(key, data).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_catalog_write_new() -> DuneResult<()> {
+        let dir = tempdir().unwrap();
+        let catalog = LocalCatalog::new(dir.path());
+
+        assert!(
+            catalog
+                .write_new("t/metadata/v1.json", b"one".to_vec())
+                .await?
+        );
+        assert!(
+            !catalog
+                .write_new("t/metadata/v1.json", b"two".to_vec())
+                .await?
+        );
+        assert_eq!(
+            catalog.read("t/metadata/v1.json").await?,
+            Some(b"one".to_vec())
+        );
+        assert_eq!(catalog.read("t/metadata/v2.json").await?, None);
+
+        let location = catalog.location("t/metadata/v1.json");
+        assert_eq!(catalog.key_of(&location), Some("t/metadata/v1.json"));
+
+        Ok(())
+    }
+}
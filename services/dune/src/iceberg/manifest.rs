@@ -0,0 +1,315 @@
+use std::sync::LazyLock;
+
+use apache_avro::{
+    Reader,
+    Schema,
+    Writer,
+    from_value,
+    types::Value,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    FORMAT_VERSION,
+    PartitionSpec,
+    TableSchema,
+};
+use crate::{
+    DuneError,
+    DuneResult,
+    helpers::{
+        AvroParserError,
+        FileFormat,
+    },
+};
+
+/// Manifest entry status of files added by a snapshot
+const STATUS_ADDED: i32 = 1;
+/// Manifest and data file content type of data files
+const CONTENT_DATA: i32 = 0;
+
+/// A data file added by a snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataFile {
+    /// Absolute location of the file
+    pub file_path: String,
+    pub file_format: FileFormat,
+    pub record_count: u64,
+    pub file_size_in_bytes: u64,
+    /// Value of the partition field, if the table is partitioned
+    pub partition: Option<i64>,
+}
+
+/// Entry of a manifest list, pointing at one manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub manifest_path: String,
+    pub manifest_length: i64,
+    pub partition_spec_id: i32,
+    pub content: i32,
+    pub sequence_number: i64,
+    pub min_sequence_number: i64,
+    pub added_snapshot_id: i64,
+    pub added_files_count: i32,
+    pub existing_files_count: i32,
+    pub deleted_files_count: i32,
+    pub added_rows_count: i64,
+    pub existing_rows_count: i64,
+    pub deleted_rows_count: i64,
+}
+
+static MANIFEST_LIST_SCHEMA: LazyLock<Schema> = LazyLock::new(|| {
+    Schema::parse_str(
+        r#"{
+            "type": "record",
+            "name": "manifest_file",
+            "fields": [
+                {"name": "manifest_path", "type": "string", "field-id": 500},
+                {"name": "manifest_length", "type": "long", "field-id": 501},
+                {"name": "partition_spec_id", "type": "int", "field-id": 502},
+                {"name": "content", "type": "int", "field-id": 517},
+                {"name": "sequence_number", "type": "long", "field-id": 515},
+                {"name": "min_sequence_number", "type": "long", "field-id": 516},
+                {"name": "added_snapshot_id", "type": "long", "field-id": 503},
+                {"name": "added_files_count", "type": "int", "field-id": 504},
+                {"name": "existing_files_count", "type": "int", "field-id": 505},
+                {"name": "deleted_files_count", "type": "int", "field-id": 506},
+                {"name": "added_rows_count", "type": "long", "field-id": 512},
+                {"name": "existing_rows_count", "type": "long", "field-id": 513},
+                {"name": "deleted_rows_count", "type": "long", "field-id": 514}
+            ]
+        }"#,
+    )
+    .expect("Manifest list schema is valid")
+});
+
+/// Avro schema of the entries of a manifest written with `spec`
+fn manifest_schema(spec: &PartitionSpec) -> DuneResult<Schema> {
+    let partition_fields: Vec<_> = spec
+        .fields
+        .iter()
+        .map(|field| {
+            serde_json::json!({
+                "name": field.name,
+                "type": ["null", "long"],
+                "default": null,
+                "field-id": field.field_id,
+            })
+        })
+        .collect();
+    let schema = serde_json::json!({
+        "type": "record",
+        "name": "manifest_entry",
+        "fields": [
+            {"name": "status", "type": "int", "field-id": 0},
+            {"name": "snapshot_id", "type": ["null", "long"], "default": null, "field-id": 1},
+            {"name": "sequence_number", "type": ["null", "long"], "default": null, "field-id": 3},
+            {"name": "file_sequence_number", "type": ["null", "long"], "default": null, "field-id": 4},
+            {
+                "name": "data_file",
+                "field-id": 2,
+                "type": {
+                    "type": "record",
+                    "name": "r2",
+                    "fields": [
+                        {"name": "content", "type": "int", "field-id": 134},
+                        {"name": "file_path", "type": "string", "field-id": 100},
+                        {"name": "file_format", "type": "string", "field-id": 101},
+                        {
+                            "name": "partition",
+                            "field-id": 102,
+                            "type": {"type": "record", "name": "r102", "fields": partition_fields}
+                        },
+                        {"name": "record_count", "type": "long", "field-id": 103},
+                        {"name": "file_size_in_bytes", "type": "long", "field-id": 104}
+                    ]
+                }
+            }
+        ]
+    });
+    Ok(Schema::parse(&schema).map_err(AvroParserError::from)?)
+}
+
+fn optional_long(value: Option<i64>) -> Value {
+    match value {
+        Some(value) => Value::Union(1, Box::new(Value::Long(value))),
+        None => Value::Union(0, Box::new(Value::Null)),
+    }
+}
+
+fn json_string(value: &impl Serialize) -> DuneResult<String> {
+    serde_json::to_string(value).map_err(|e| DuneError::Other(anyhow::anyhow!(e)))
+}
+
+/// Encodes a manifest listing `files` as added by `snapshot_id`.
+pub fn write_manifest(
+    schema: &TableSchema,
+    spec: &PartitionSpec,
+    snapshot_id: i64,
+    sequence_number: i64,
+    files: &[DataFile],
+) -> DuneResult<Vec<u8>> {
+    let avro_schema = manifest_schema(spec)?;
+    let mut writer = Writer::new(&avro_schema, Vec::new());
+    let metadata = [
+        ("schema", json_string(schema)?),
+        ("schema-id", schema.schema_id.to_string()),
+        ("partition-spec", json_string(&spec.fields)?),
+        ("partition-spec-id", spec.spec_id.to_string()),
+        ("format-version", FORMAT_VERSION.to_string()),
+        ("content", "data".to_string()),
+    ];
+    for (key, value) in metadata {
+        writer
+            .add_user_metadata(key.to_string(), value)
+            .map_err(AvroParserError::from)?;
+    }
+
+    for file in files {
+        let partition = spec
+            .fields
+            .iter()
+            .map(|field| (field.name.clone(), optional_long(file.partition)))
+            .collect();
+        let data_file = Value::Record(vec![
+            ("content".into(), Value::Int(CONTENT_DATA)),
+            ("file_path".into(), Value::String(file.file_path.clone())),
+            (
+                "file_format".into(),
+                Value::String(file.file_format.to_string().to_uppercase()),
+            ),
+            ("partition".into(), Value::Record(partition)),
+            ("record_count".into(), Value::Long(file.record_count as i64)),
+            (
+                "file_size_in_bytes".into(),
+                Value::Long(file.file_size_in_bytes as i64),
+            ),
+        ]);
+        let entry = Value::Record(vec![
+            ("status".into(), Value::Int(STATUS_ADDED)),
+            ("snapshot_id".into(), optional_long(Some(snapshot_id))),
+            (
+                "sequence_number".into(),
+                optional_long(Some(sequence_number)),
+            ),
+            (
+                "file_sequence_number".into(),
+                optional_long(Some(sequence_number)),
+            ),
+            ("data_file".into(), data_file),
+        ]);
+        writer.append(entry).map_err(AvroParserError::from)?;
+    }
+
+    Ok(writer.into_inner().map_err(AvroParserError::from)?)
+}
+
+/// Encodes the manifest list of a snapshot.
+pub fn write_manifest_list(
+    snapshot_id: i64,
+    parent_snapshot_id: Option<i64>,
+    sequence_number: i64,
+    manifests: &[ManifestFile],
+) -> DuneResult<Vec<u8>> {
+    let mut writer = Writer::new(&MANIFEST_LIST_SCHEMA, Vec::new());
+    let mut metadata = vec![
+        ("snapshot-id", snapshot_id.to_string()),
+        ("sequence-number", sequence_number.to_string()),
+        ("format-version", FORMAT_VERSION.to_string()),
+    ];
+    if let Some(parent) = parent_snapshot_id {
+        metadata.push(("parent-snapshot-id", parent.to_string()));
+    }
+    for (key, value) in metadata {
+        writer
+            .add_user_metadata(key.to_string(), value)
+            .map_err(AvroParserError::from)?;
+    }
+
+    for manifest in manifests {
+        writer.append_ser(manifest).map_err(AvroParserError::from)?;
+    }
+
+    Ok(writer.into_inner().map_err(AvroParserError::from)?)
+}
+
+/// Decodes a manifest list written by [`write_manifest_list`].
+pub fn read_manifest_list(data: &[u8]) -> DuneResult<Vec<ManifestFile>> {
+    let reader = Reader::with_schema(&MANIFEST_LIST_SCHEMA, data)
+        .map_err(AvroParserError::from)?;
+    let mut manifests = Vec::new();
+    for value in reader {
+        let value = value.map_err(AvroParserError::from)?;
+        manifests.push(from_value(&value).map_err(AvroParserError::from)?);
+    }
+    Ok(manifests)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::iceberg::PartitionField;
+
+    #[test]
+    fn test_manifest_and_list_encoding() -> DuneResult<()> {
+        let spec = PartitionSpec {
+            spec_id: 0,
+            fields: vec![PartitionField {
+                source_id: 2,
+                field_id: 1000,
+                name: "blockTime_trunc".to_string(),
+                transform: "truncate[86400]".to_string(),
+            }],
+        };
+        let files = vec![DataFile {
+            file_path: "s3://bucket/v1/local/receipts/0000000001-0000000010.parquet"
+                .to_string(),
+            file_format: FileFormat::Parquet,
+            record_count: 42,
+            file_size_in_bytes: 1024,
+            partition: Some(1_744_934_400),
+        }];
+        let schema = TableSchema {
+            schema_id: 0,
+            fields: vec![],
+        };
+        let manifest = write_manifest(&schema, &spec, 7, 1, &files)?;
+
+        let reader = Reader::new(&manifest[..]).map_err(AvroParserError::from)?;
+        assert_eq!(
+            reader.user_metadata().get("content"),
+            Some(&b"data".to_vec())
+        );
+        let entries: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(entries.len(), 1);
+        let Value::Record(fields) = &entries[0] else {
+            panic!("manifest entries are records");
+        };
+        assert_eq!(fields[0], ("status".to_string(), Value::Int(STATUS_ADDED)));
+
+        let manifests = vec![ManifestFile {
+            manifest_path: "s3://bucket/v1/local/receipts/metadata/a-m0.avro".to_string(),
+            manifest_length: manifest.len() as i64,
+            partition_spec_id: 0,
+            content: CONTENT_DATA,
+            sequence_number: 1,
+            min_sequence_number: 1,
+            added_snapshot_id: 7,
+            added_files_count: 1,
+            existing_files_count: 0,
+            deleted_files_count: 0,
+            added_rows_count: 42,
+            existing_rows_count: 0,
+            deleted_rows_count: 0,
+        }];
+        let list = write_manifest_list(7, None, 1, &manifests)?;
+        assert_eq!(read_manifest_list(&list)?, manifests);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+//! Apache Iceberg metadata for the exported tables.
+//!
+//! The range files stay where the service uploads them; every uploaded batch
+//! is committed as a new snapshot appending those files, so engines such as
+//! Spark, Trino or DuckDB can query a table without listing the bucket.
+
+mod catalog;
+mod manifest;
+mod metadata;
+mod schema;
+mod table;
+
+pub use catalog::*;
+pub use manifest::*;
+pub use metadata::*;
+pub use schema::*;
+pub use table::*;
+
+/// Random version 4 UUID, formatted as a hyphenated string
+pub(crate) fn random_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
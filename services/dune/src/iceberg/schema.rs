@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::{
+    Value,
+    json,
+};
+
+use crate::{
+    DuneError,
+    DuneResult,
+};
+
+/// Iceberg field types used by the exported tables.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IcebergType {
+    Primitive(String),
+    Struct(StructType),
+    List(Box<ListType>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "struct")]
+pub struct StructType {
+    pub fields: Vec<NestedField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "list", rename_all = "kebab-case")]
+pub struct ListType {
+    pub element_id: i32,
+    pub element: IcebergType,
+    pub element_required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NestedField {
+    pub id: i32,
+    pub name: String,
+    pub required: bool,
+    #[serde(rename = "type")]
+    pub field_type: IcebergType,
+}
+
+/// Top level schema of a table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "struct", rename_all = "kebab-case")]
+pub struct TableSchema {
+    pub schema_id: i32,
+    pub fields: Vec<NestedField>,
+}
+
+impl TableSchema {
+    /// Derives the Iceberg schema of an Avro record schema. Field ids are
+    /// assigned breadth first per struct, nullable unions become optional
+    /// fields and named types referenced again are expanded. Returns the
+    /// schema and the highest assigned field id.
+    pub fn from_avro(avro: &apache_avro::Schema) -> DuneResult<(Self, i32)> {
+        let json = serde_json::to_value(avro)
+            .map_err(|e| DuneError::Other(anyhow::anyhow!(e)))?;
+        let mut converter = AvroConverter::default();
+        let IcebergType::Struct(root) = converter.convert(&json)?.0 else {
+            return Err(DuneError::Other(anyhow::anyhow!(
+                "Table schemas must be Avro records"
+            )));
+        };
+        let schema = Self {
+            schema_id: 0,
+            fields: root.fields,
+        };
+        Ok((schema, converter.last_id))
+    }
+
+    /// Finds a top level field by name
+    pub fn field(&self, name: &str) -> Option<&NestedField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Name mapping for the `schema.name-mapping.default` table property.
+    /// The exported files carry no field ids, so readers resolve columns
+    /// through this mapping instead.
+    pub fn name_mapping(&self) -> Value {
+        Value::Array(self.fields.iter().map(map_field).collect())
+    }
+}
+
+fn map_field(field: &NestedField) -> Value {
+    let mut mapped = json!({ "field-id": field.id, "names": [field.name] });
+    if let Some(nested) = map_nested(&field.field_type) {
+        mapped["fields"] = nested;
+    }
+    mapped
+}
+
+fn map_nested(field_type: &IcebergType) -> Option<Value> {
+    match field_type {
+        IcebergType::Primitive(_) => None,
+        IcebergType::Struct(fields) => {
+            Some(Value::Array(fields.fields.iter().map(map_field).collect()))
+        }
+        IcebergType::List(list) => {
+            // Avro and Parquet writers name list elements differently
+            let mut element = json!({
+                "field-id": list.element_id,
+                "names": ["element", "item"],
+            });
+            if let Some(nested) = map_nested(&list.element) {
+                element["fields"] = nested;
+            }
+            Some(json!([element]))
+        }
+    }
+}
+
+#[derive(Default)]
+struct AvroConverter {
+    last_id: i32,
+    named: HashMap<String, Value>,
+}
+
+impl AvroConverter {
+    fn next_id(&mut self) -> i32 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    /// Converts an Avro schema in its JSON form, returning the type and
+    /// whether a value is required.
+    fn convert(&mut self, avro: &Value) -> DuneResult<(IcebergType, bool)> {
+        match avro {
+            Value::String(name) => match primitive(name) {
+                Some(primitive) => Ok((IcebergType::Primitive(primitive.into()), true)),
+                None => {
+                    let named = self.named.get(name).cloned().ok_or_else(|| {
+                        DuneError::Other(anyhow::anyhow!("Unknown Avro type {name}"))
+                    })?;
+                    self.convert(&named)
+                }
+            },
+            Value::Array(variants) => {
+                let non_null: Vec<_> = variants
+                    .iter()
+                    .filter(|variant| variant.as_str() != Some("null"))
+                    .collect();
+                match non_null.as_slice() {
+                    [variant] => {
+                        let (field_type, _) = self.convert(variant)?;
+                        Ok((field_type, non_null.len() == variants.len()))
+                    }
+                    _ => Err(DuneError::Other(anyhow::anyhow!(
+                        "Only nullable unions are supported, got {avro}"
+                    ))),
+                }
+            }
+            Value::Object(object) => {
+                let kind = object.get("type").cloned().unwrap_or_default();
+                match kind.as_str() {
+                    Some("record") => self.convert_record(object),
+                    Some("array") => {
+                        let items = object.get("items").ok_or_else(|| {
+                            DuneError::Other(anyhow::anyhow!("Array without items"))
+                        })?;
+                        let element_id = self.next_id();
+                        let (element, element_required) = self.convert(items)?;
+                        let list = ListType {
+                            element_id,
+                            element,
+                            element_required,
+                        };
+                        Ok((IcebergType::List(Box::new(list)), true))
+                    }
+                    Some("enum") => Ok((IcebergType::Primitive("string".into()), true)),
+                    Some("fixed") => Ok((IcebergType::Primitive("binary".into()), true)),
+                    _ => self.convert(&kind),
+                }
+            }
+            _ => Err(DuneError::Other(anyhow::anyhow!(
+                "Unsupported Avro schema {avro}"
+            ))),
+        }
+    }
+
+    fn convert_record(
+        &mut self,
+        record: &serde_json::Map<String, Value>,
+    ) -> DuneResult<(IcebergType, bool)> {
+        if let Some(name) = record.get("name").and_then(Value::as_str) {
+            let definition = Value::Object(record.clone());
+            if let Some(namespace) = record.get("namespace").and_then(Value::as_str) {
+                self.named
+                    .insert(format!("{namespace}.{name}"), definition.clone());
+            }
+            self.named.insert(name.to_string(), definition);
+        }
+
+        let avro_fields = record
+            .get("fields")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let ids: Vec<_> = avro_fields.iter().map(|_| self.next_id()).collect();
+
+        let mut fields = Vec::with_capacity(avro_fields.len());
+        for (id, field) in ids.into_iter().zip(&avro_fields) {
+            let name = field.get("name").and_then(Value::as_str).ok_or_else(|| {
+                DuneError::Other(anyhow::anyhow!("Record field without name"))
+            })?;
+            let avro_type = field.get("type").ok_or_else(|| {
+                DuneError::Other(anyhow::anyhow!("Record field {name} without type"))
+            })?;
+            let (field_type, required) = self.convert(avro_type)?;
+            fields.push(NestedField {
+                id,
+                name: name.to_string(),
+                required,
+                field_type,
+            });
+        }
+
+        Ok((IcebergType::Struct(StructType { fields }), true))
+    }
+}
+
+fn primitive(avro: &str) -> Option<&'static str> {
+    match avro {
+        "boolean" => Some("boolean"),
+        "int" => Some("int"),
+        "long" => Some("long"),
+        "float" => Some("float"),
+        "double" => Some("double"),
+        "bytes" => Some("binary"),
+        "string" => Some("string"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::schemas::{
+        AvroBlock,
+        AvroTransaction,
+    };
+
+    #[test]
+    fn test_schema_from_avro() -> DuneResult<()> {
+        let (schema, last_id) = TableSchema::from_avro(&AvroBlock::get_schema())?;
+        let height = schema.field("height").unwrap();
+        assert_eq!(height.id, 1);
+        assert!(!height.required);
+        assert_eq!(height.field_type, IcebergType::Primitive("long".into()));
+        assert_eq!(
+            schema.field("producer").unwrap().field_type,
+            IcebergType::Primitive("binary".into())
+        );
+        assert_eq!(last_id, schema.fields.len() as i32);
+
+        let (schema, last_id) = TableSchema::from_avro(&AvroTransaction::get_schema())?;
+        let IcebergType::Struct(inputs) = &schema.field("inputs").unwrap().field_type
+        else {
+            panic!("inputs should be a struct");
+        };
+        let coin_inputs = inputs
+            .fields
+            .iter()
+            .find(|field| field.name == "coinInputs")
+            .unwrap();
+        assert!(matches!(coin_inputs.field_type, IcebergType::List(_)));
+
+        // Every id is unique and within the reported range
+        let mut ids = Vec::new();
+        fn collect(field_type: &IcebergType, ids: &mut Vec<i32>) {
+            match field_type {
+                IcebergType::Primitive(_) => {}
+                IcebergType::Struct(fields) => {
+                    for field in &fields.fields {
+                        ids.push(field.id);
+                        collect(&field.field_type, ids);
+                    }
+                }
+                IcebergType::List(list) => {
+                    ids.push(list.element_id);
+                    collect(&list.element, ids);
+                }
+            }
+        }
+        collect(
+            &IcebergType::Struct(StructType {
+                fields: schema.fields.clone(),
+            }),
+            &mut ids,
+        );
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+        assert_eq!(ids.last().copied(), Some(last_id));
+
+        let round_trip: TableSchema =
+            serde_json::from_value(serde_json::to_value(&schema).unwrap()).unwrap();
+        assert_eq!(round_trip, schema);
+
+        Ok(())
+    }
+}
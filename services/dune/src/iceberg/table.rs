@@ -0,0 +1,458 @@
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+};
+
+use apache_avro::AvroSchema;
+use chrono::NaiveDate;
+use fuel_streams_types::BlockHeight;
+
+use super::{
+    Catalog,
+    DataFile,
+    END_HEIGHT_PROPERTY,
+    FORMAT_VERSION,
+    ManifestFile,
+    MetadataLogEntry,
+    PartitionField,
+    PartitionSpec,
+    Snapshot,
+    SnapshotLogEntry,
+    SnapshotRef,
+    SortOrder,
+    TableMetadata,
+    TableSchema,
+    random_uuid,
+    read_manifest_list,
+    write_manifest,
+    write_manifest_list,
+};
+use crate::{
+    DuneError,
+    DuneResult,
+    s3::KeyLayout,
+    schemas::AvroRow,
+};
+
+/// Number of times a commit is retried when another writer created the
+/// next metadata version first
+pub const MAX_COMMIT_ATTEMPTS: usize = 5;
+
+/// Width of the daily partition transform, in seconds
+const DAY_SECONDS: i64 = 86_400;
+
+/// Field id of the partition field, partition ids start at 1000
+const PARTITION_FIELD_ID: i32 = 1000;
+
+/// Key of the file pointing at the latest metadata version of a table
+pub fn version_hint_key(table_prefix: &str) -> String {
+    format!("{table_prefix}metadata/version-hint.text")
+}
+
+/// Partition value of the files of a UTC day: the day start as Unix seconds,
+/// matching the `truncate[86400]` transform on the block time column.
+pub fn day_partition(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .expect("Midnight is a valid time")
+        .and_utc()
+        .timestamp()
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Iceberg table over the range files of one exported table.
+pub struct IcebergTable {
+    catalog: Arc<dyn Catalog>,
+    /// Table prefix with a trailing slash, metadata lives under `metadata/`
+    prefix: String,
+    schema: TableSchema,
+    last_column_id: i32,
+    spec: PartitionSpec,
+}
+
+impl IcebergTable {
+    /// Creates the table of row type `T` under `prefix`. Tables using the
+    /// date-partitioned layout are partitioned by the day of the block time.
+    pub fn new<T: AvroRow + AvroSchema>(
+        catalog: Arc<dyn Catalog>,
+        prefix: impl Into<String>,
+        layout: KeyLayout,
+    ) -> DuneResult<Self> {
+        let (schema, last_column_id) = TableSchema::from_avro(&T::get_schema())?;
+        let fields = match layout {
+            KeyLayout::Flat => vec![],
+            KeyLayout::DatePartitioned => {
+                let source = schema.field(T::TIME_COLUMN).ok_or_else(|| {
+                    DuneError::Other(anyhow::anyhow!(
+                        "Missing time column {}",
+                        T::TIME_COLUMN
+                    ))
+                })?;
+                vec![PartitionField {
+                    source_id: source.id,
+                    field_id: PARTITION_FIELD_ID,
+                    name: format!("{}_day", T::TIME_COLUMN),
+                    transform: format!("truncate[{DAY_SECONDS}]"),
+                }]
+            }
+        };
+
+        Ok(Self {
+            catalog,
+            prefix: prefix.into(),
+            schema,
+            last_column_id,
+            spec: PartitionSpec { spec_id: 0, fields },
+        })
+    }
+
+    fn metadata_key(&self, version: u32) -> String {
+        format!("{}metadata/v{version}.metadata.json", self.prefix)
+    }
+
+    /// Loads the latest metadata version. The version hint is only a
+    /// starting point, newer versions are probed until one is missing.
+    pub async fn load(&self) -> DuneResult<Option<(u32, TableMetadata)>> {
+        let mut version = match self.catalog.read(&version_hint_key(&self.prefix)).await?
+        {
+            Some(hint) => String::from_utf8_lossy(&hint).trim().parse().unwrap_or(0),
+            None => 0,
+        };
+        let mut current = match version {
+            0 => None,
+            _ => self.catalog.read(&self.metadata_key(version)).await?,
+        };
+        while let Some(data) = self.catalog.read(&self.metadata_key(version + 1)).await? {
+            version += 1;
+            current = Some(data);
+        }
+
+        let Some(data) = current else {
+            return Ok(None);
+        };
+        let metadata = serde_json::from_slice(&data).map_err(|e| {
+            DuneError::Other(anyhow::anyhow!("Invalid metadata v{version}: {e}"))
+        })?;
+        Ok(Some((version, metadata)))
+    }
+
+    fn create_metadata(&self) -> TableMetadata {
+        let location = self.catalog.location(self.prefix.trim_end_matches('/'));
+        let name_mapping = self.schema.name_mapping().to_string();
+        TableMetadata {
+            format_version: FORMAT_VERSION,
+            table_uuid: random_uuid(),
+            location,
+            last_sequence_number: 0,
+            last_updated_ms: now_ms(),
+            last_column_id: self.last_column_id,
+            schemas: vec![self.schema.clone()],
+            current_schema_id: self.schema.schema_id,
+            partition_specs: vec![self.spec.clone()],
+            default_spec_id: self.spec.spec_id,
+            last_partition_id: PARTITION_FIELD_ID - 1 + self.spec.fields.len() as i32,
+            properties: BTreeMap::from([(
+                "schema.name-mapping.default".to_string(),
+                name_mapping,
+            )]),
+            current_snapshot_id: None,
+            snapshots: vec![],
+            snapshot_log: vec![],
+            metadata_log: vec![],
+            sort_orders: vec![SortOrder::unsorted()],
+            default_sort_order_id: 0,
+            refs: BTreeMap::new(),
+        }
+    }
+
+    /// Commits a snapshot appending `files`, the range files of a batch ending
+    /// at `end_height`. The commit is skipped if the current snapshot already
+    /// covers that height, so a batch uploaded again after a restart is not
+    /// appended twice. Returns whether a snapshot was committed.
+    pub async fn commit_append(
+        &self,
+        files: &[DataFile],
+        end_height: BlockHeight,
+    ) -> DuneResult<bool> {
+        for attempt in 1..=MAX_COMMIT_ATTEMPTS {
+            let (version, metadata) = match self.load().await? {
+                Some((_, metadata))
+                    if metadata
+                        .committed_height()
+                        .is_some_and(|height| height >= *end_height) =>
+                {
+                    tracing::info!(
+                        "Iceberg table {} already covers height {end_height}",
+                        self.prefix
+                    );
+                    return Ok(false);
+                }
+                Some(current) => current,
+                None => (0, self.create_metadata()),
+            };
+
+            let metadata = self
+                .append_snapshot(metadata, version, files, end_height)
+                .await?;
+            let data = serde_json::to_vec_pretty(&metadata)
+                .map_err(|e| DuneError::Other(anyhow::anyhow!(e)))?;
+
+            // Creating the next version only succeeds for one writer
+            let next = version + 1;
+            if self
+                .catalog
+                .write_new(&self.metadata_key(next), data)
+                .await?
+            {
+                self.catalog
+                    .write(
+                        &version_hint_key(&self.prefix),
+                        next.to_string().into_bytes(),
+                    )
+                    .await?;
+                tracing::info!(
+                    "Committed snapshot {} of Iceberg table {} as v{next}",
+                    metadata.current_snapshot_id.unwrap_or_default(),
+                    self.prefix
+                );
+                return Ok(true);
+            }
+            tracing::warn!(
+                "Iceberg table {} v{next} was committed concurrently, retrying (attempt {attempt})",
+                self.prefix
+            );
+        }
+
+        Err(DuneError::Other(anyhow::anyhow!(
+            "Failed to commit Iceberg table {} after {MAX_COMMIT_ATTEMPTS} attempts",
+            self.prefix
+        )))
+    }
+
+    /// Writes the manifest and manifest list of a new snapshot and returns
+    /// the metadata with that snapshot as the current one.
+    async fn append_snapshot(
+        &self,
+        mut metadata: TableMetadata,
+        version: u32,
+        files: &[DataFile],
+        end_height: BlockHeight,
+    ) -> DuneResult<TableMetadata> {
+        let schema = metadata.current_schema().cloned().ok_or_else(|| {
+            DuneError::Other(anyhow::anyhow!("Metadata without current schema"))
+        })?;
+        let spec = metadata.default_spec().cloned().ok_or_else(|| {
+            DuneError::Other(anyhow::anyhow!("Metadata without default spec"))
+        })?;
+        let parent = metadata.current_snapshot().cloned();
+        let snapshot_id = rand::random::<i64>() & i64::MAX;
+        let sequence_number = metadata.last_sequence_number + 1;
+        let timestamp_ms = now_ms();
+
+        let manifest =
+            write_manifest(&schema, &spec, snapshot_id, sequence_number, files)?;
+        let manifest_key = format!("{}metadata/{}-m0.avro", self.prefix, random_uuid());
+        let added_rows: u64 = files.iter().map(|file| file.record_count).sum();
+        let mut manifests = vec![ManifestFile {
+            manifest_path: self.catalog.location(&manifest_key),
+            manifest_length: manifest.len() as i64,
+            partition_spec_id: spec.spec_id,
+            content: 0,
+            sequence_number,
+            min_sequence_number: sequence_number,
+            added_snapshot_id: snapshot_id,
+            added_files_count: files.len() as i32,
+            existing_files_count: 0,
+            deleted_files_count: 0,
+            added_rows_count: added_rows as i64,
+            existing_rows_count: 0,
+            deleted_rows_count: 0,
+        }];
+        self.catalog.write(&manifest_key, manifest).await?;
+
+        // Manifests of earlier snapshots are carried over unchanged
+        if let Some(parent) = &parent {
+            let key = self.catalog.key_of(&parent.manifest_list).ok_or_else(|| {
+                DuneError::Other(anyhow::anyhow!(
+                    "Manifest list {} is outside the catalog",
+                    parent.manifest_list
+                ))
+            })?;
+            let data = self.catalog.read(key).await?.ok_or_else(|| {
+                DuneError::Other(anyhow::anyhow!("Missing manifest list {key}"))
+            })?;
+            manifests.extend(read_manifest_list(&data)?);
+        }
+
+        let manifest_list = write_manifest_list(
+            snapshot_id,
+            parent.as_ref().map(|parent| parent.snapshot_id),
+            sequence_number,
+            &manifests,
+        )?;
+        let manifest_list_key = format!(
+            "{}metadata/snap-{snapshot_id}-1-{}.avro",
+            self.prefix,
+            random_uuid()
+        );
+        self.catalog
+            .write(&manifest_list_key, manifest_list)
+            .await?;
+
+        let total = |name: &str| -> u64 {
+            parent
+                .as_ref()
+                .and_then(|parent| parent.summary.get(name))
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default()
+        };
+        let added_size: u64 = files.iter().map(|file| file.file_size_in_bytes).sum();
+        let summary = BTreeMap::from([
+            ("operation".to_string(), "append".to_string()),
+            ("added-data-files".to_string(), files.len().to_string()),
+            ("added-records".to_string(), added_rows.to_string()),
+            ("added-files-size".to_string(), added_size.to_string()),
+            (
+                "total-data-files".to_string(),
+                (total("total-data-files") + files.len() as u64).to_string(),
+            ),
+            (
+                "total-records".to_string(),
+                (total("total-records") + added_rows).to_string(),
+            ),
+            (
+                "total-files-size".to_string(),
+                (total("total-files-size") + added_size).to_string(),
+            ),
+            (END_HEIGHT_PROPERTY.to_string(), end_height.to_string()),
+        ]);
+
+        if version > 0 {
+            metadata.metadata_log.push(MetadataLogEntry {
+                timestamp_ms: metadata.last_updated_ms,
+                metadata_file: self.catalog.location(&self.metadata_key(version)),
+            });
+        }
+        metadata.snapshots.push(Snapshot {
+            snapshot_id,
+            parent_snapshot_id: parent.map(|parent| parent.snapshot_id),
+            sequence_number,
+            timestamp_ms,
+            manifest_list: self.catalog.location(&manifest_list_key),
+            summary,
+            schema_id: schema.schema_id,
+        });
+        metadata.snapshot_log.push(SnapshotLogEntry {
+            timestamp_ms,
+            snapshot_id,
+        });
+        metadata.current_snapshot_id = Some(snapshot_id);
+        metadata
+            .refs
+            .insert("main".to_string(), SnapshotRef::branch(snapshot_id));
+        metadata.last_sequence_number = sequence_number;
+        metadata.last_updated_ms = timestamp_ms;
+
+        Ok(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        helpers::FileFormat,
+        iceberg::LocalCatalog,
+        schemas::AvroReceipt,
+    };
+
+    fn data_file(start: u32, end: u32, date: NaiveDate) -> DataFile {
+        DataFile {
+            file_path: format!(
+                "s3://bucket/v1/local/receipts/{start:010}-{end:010}.avro"
+            ),
+            file_format: FileFormat::Avro,
+            record_count: 10,
+            file_size_in_bytes: 512,
+            partition: Some(day_partition(date)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commit_append_snapshots() -> DuneResult<()> {
+        let dir = tempdir().unwrap();
+        let catalog: Arc<dyn Catalog> = Arc::new(LocalCatalog::new(dir.path()));
+        let prefix = "v1/local/receipts/";
+        let table = IcebergTable::new::<AvroReceipt>(
+            catalog.clone(),
+            prefix,
+            KeyLayout::DatePartitioned,
+        )?;
+        let date = NaiveDate::from_ymd_opt(2025, 4, 18).unwrap();
+        assert_eq!(day_partition(date), 1_744_934_400);
+
+        assert!(table.load().await?.is_none());
+        assert!(
+            table
+                .commit_append(&[data_file(1, 10, date)], 10.into())
+                .await?
+        );
+        assert!(
+            table
+                .commit_append(&[data_file(11, 20, date)], 20.into())
+                .await?
+        );
+        // A batch uploaded again is not appended twice
+        assert!(
+            !table
+                .commit_append(&[data_file(11, 20, date)], 20.into())
+                .await?
+        );
+
+        let (version, metadata) = table.load().await?.unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(metadata.snapshots.len(), 2);
+        assert_eq!(metadata.last_sequence_number, 2);
+        assert_eq!(metadata.committed_height(), Some(20));
+        assert_eq!(metadata.metadata_log.len(), 1);
+        assert_eq!(
+            metadata.default_spec().unwrap().fields[0].source_id,
+            metadata
+                .current_schema()
+                .unwrap()
+                .field("blockTime")
+                .unwrap()
+                .id
+        );
+        let snapshot = metadata.current_snapshot().unwrap();
+        assert_eq!(snapshot.summary["total-records"], "20");
+        assert_eq!(
+            snapshot.parent_snapshot_id,
+            Some(metadata.snapshots[0].snapshot_id)
+        );
+
+        let key = catalog.key_of(&snapshot.manifest_list).unwrap();
+        let manifests = read_manifest_list(&catalog.read(key).await?.unwrap())?;
+        assert_eq!(manifests.len(), 2);
+        assert_eq!(
+            manifests
+                .iter()
+                .map(|m| m.sequence_number)
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+
+        // A stale version hint still resolves the latest version
+        catalog
+            .write(&version_hint_key(prefix), b"1".to_vec())
+            .await?;
+        assert_eq!(table.load().await?.unwrap().0, 2);
+
+        Ok(())
+    }
+}
@@ -1,19 +1,28 @@
 #![deny(unused_crate_dependencies)]
 #![deny(warnings)]
 
-// Used in the `main.rs`
-use fuel_web_utils as _;
-
+pub mod abi;
 pub mod alloc_counter;
+pub mod assets;
 pub mod block_buffer;
 mod cli;
+pub mod codec_bench;
+pub mod compact;
+pub mod data_lake;
 mod error;
 pub mod helpers;
+pub mod iceberg;
+pub mod migrate_layout;
+pub mod postgres;
+pub mod predicates;
 pub mod processor;
+pub mod roots;
 pub mod s3;
+pub mod schema_registry;
 pub mod schemas;
 pub mod service;
 pub mod tracked;
+pub mod verify;
 
 pub use block_buffer::*;
 pub use cli::*;
@@ -1,7 +1,6 @@
 #![deny(warnings)]
 
 use anyhow::Result;
-use clap::Parser;
 use fuel_core_services::Service;
 use fuel_web_utils::{
     shutdown::ShutdownController,
@@ -10,28 +9,66 @@ use fuel_web_utils::{
 use std::sync::Arc;
 use sv_dune::{
     Cli,
+    Command,
+    RunArgs,
+    codec_bench,
+    compact,
+    migrate_layout,
+    postgres::PostgresConfig,
+    roots,
     service::{
         Config,
         new_service,
     },
+    verify,
 };
 
 #[tokio::main]
 async fn main() -> Result<()> {
     init_tracing()?;
 
-    let cli = Cli::parse();
+    match Cli::parse_or_run().command {
+        Command::Run(args) => run(args).await,
+        Command::CodecBench(args) => codec_bench::run(args).await,
+        Command::MigrateLayout(args) => migrate_layout::run(args).await,
+        Command::Compact(args) => compact::run(args).await,
+        Command::Verify(args) => verify::run(args).await,
+        Command::Validate(args) => roots::run(args).await,
+    }
+}
+
+async fn run(args: RunArgs) -> Result<()> {
     let shutdown = Arc::new(ShutdownController::new());
     shutdown.clone().spawn_signal_handler();
 
+    let postgres = match (args.output.postgres(), args.database_url) {
+        (true, Some(url)) => Some(PostgresConfig {
+            url,
+            batch_size: args.postgres_batch_size,
+        }),
+        (true, None) => {
+            anyhow::bail!("--database-url is required with the {} output", args.output)
+        }
+        (false, _) => None,
+    };
+
     let config = Config {
-        url: cli.url,
-        starting_height: cli.starting_block.into(),
-        storage_type: cli.storage_type,
-        batch_size: cli.batch_size,
-        blocks_request_batch_size: cli.blocks_request_batch_size,
-        blocks_request_concurrency: cli.blocks_request_concurrency,
-        pending_blocks: cli.pending_blocks,
+        url: args.url,
+        starting_height: args.starting_block.into(),
+        storage_type: args.storage_type,
+        batch_size: args.batch_size,
+        blocks_request_batch_size: args.blocks_request_batch_size,
+        blocks_request_concurrency: args.blocks_request_concurrency,
+        pending_blocks: args.pending_blocks,
+        codecs: args.avro_codecs,
+        formats: args.file_formats,
+        key_layout: args.key_layout,
+        iceberg: args.iceberg,
+        output: args.output,
+        postgres,
+        nats_url: args.nats_url,
+        validate_roots: args.validate_roots,
+        abi_dir: args.abi_dir,
     };
 
     let service = new_service(config)?;
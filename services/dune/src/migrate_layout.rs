@@ -0,0 +1,519 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use fuel_streams_types::BlockHeight;
+
+use crate::{
+    DuneError,
+    DuneResult,
+    MigrateLayoutArgs,
+    helpers::{
+        AvroParser,
+        read_codec_metadata,
+    },
+    iceberg::version_hint_key,
+    s3::{
+        FuelNetwork,
+        KeyLayout,
+        S3KeyBuilder,
+        S3Storage,
+        S3StorageOpts,
+        S3TableName,
+        Storage,
+        StorageConfig,
+        TableRow,
+        parse_range_key,
+        with_table_row,
+    },
+    schemas::{
+        AvroBlock,
+        AvroRow,
+    },
+};
+
+/// One output file of a flat range file split by UTC day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatePartition {
+    pub start_height: BlockHeight,
+    pub end_height: BlockHeight,
+    pub date: NaiveDate,
+    pub rows: usize,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub source_files: usize,
+    pub written_files: usize,
+    pub deleted_files: usize,
+    pub rows: usize,
+}
+
+/// Heights of a UTC day within a range file of the blocks table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayRange {
+    pub start: BlockHeight,
+    pub end: BlockHeight,
+    pub date: NaiveDate,
+}
+
+/// Day ranges of the blocks table, by start height.
+///
+/// Every table is split at these heights rather than at the times of its own
+/// rows, so the partitioned files of a range have the same `start-end` names
+/// in every table, including for days where a table has no rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockDays {
+    ranges: BTreeMap<BlockHeight, DayRange>,
+}
+
+impl BlockDays {
+    /// Loads the day ranges of the blocks table, from the keys of its
+    /// partitioned files and the rows of its flat ones
+    pub async fn load(storage: &S3Storage, network: FuelNetwork) -> DuneResult<Self> {
+        let prefix = S3KeyBuilder::new(network)
+            .with_table(S3TableName::Blocks)
+            .table_prefix();
+
+        let mut days = Self::default();
+        for key in storage.list_keys(&prefix).await? {
+            let Some((start, end)) = parse_range_key(&key) else {
+                continue;
+            };
+            match key[prefix.len()..].split_once('/') {
+                None => {
+                    let data = storage.retrieve(&key).await?.ok_or_else(|| {
+                        DuneError::Other(anyhow::anyhow!(
+                            "Object {key} disappeared during migration"
+                        ))
+                    })?;
+                    let codec = read_codec_metadata(&data)?.unwrap_or_default();
+                    let blocks = AvroParser::new(codec)
+                        .reader_with_schema::<AvroBlock>()?
+                        .deserialize(&data)?;
+                    days.insert_blocks(&blocks, start, end)?;
+                }
+                Some((directory, _)) => {
+                    let date = directory.strip_prefix("date=").and_then(|date| {
+                        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+                    });
+                    if let Some(date) = date {
+                        days.insert(DayRange { start, end, date });
+                    }
+                }
+            }
+        }
+        Ok(days)
+    }
+
+    pub fn insert(&mut self, range: DayRange) {
+        self.ranges.insert(range.start, range);
+    }
+
+    /// Adds the days of the `blocks` of a flat file covering `start..=end`.
+    /// The first and last days keep the outer bounds of the file so the
+    /// height coverage stays contiguous.
+    pub fn insert_blocks(
+        &mut self,
+        blocks: &[AvroBlock],
+        start: BlockHeight,
+        end: BlockHeight,
+    ) -> DuneResult<()> {
+        let mut days: Vec<DayRange> = Vec::new();
+        for block in blocks {
+            let (Some(height), Some(date)) = (block.block_height(), block.block_date())
+            else {
+                return Err(DuneError::Other(anyhow::anyhow!(
+                    "Block at height {:?} has no height or time",
+                    block.height
+                )));
+            };
+            let height = BlockHeight::from(height as u32);
+            match days.last_mut() {
+                Some(day) if day.date == date => day.end = height,
+                Some(day) => {
+                    day.end = (*height - 1).into();
+                    days.push(DayRange {
+                        start: height,
+                        end: height,
+                        date,
+                    });
+                }
+                None => days.push(DayRange {
+                    start,
+                    end: height,
+                    date,
+                }),
+            }
+        }
+        if let Some(last) = days.last_mut() {
+            last.end = end;
+        }
+        for day in days {
+            self.insert(day);
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The days of `start..=end`, clipped to it. Fails unless the blocks
+    /// table covers every height of the range.
+    pub fn split(
+        &self,
+        start: BlockHeight,
+        end: BlockHeight,
+    ) -> DuneResult<Vec<DayRange>> {
+        let first = self
+            .ranges
+            .range(..=start)
+            .next_back()
+            .map(|(height, _)| *height)
+            .unwrap_or(start);
+
+        let mut days = Vec::new();
+        let mut next = start;
+        for day in self.ranges.range(first..=end).map(|(_, day)| day) {
+            if day.end < next {
+                continue;
+            }
+            if day.start > next {
+                break;
+            }
+            days.push(DayRange {
+                start: next,
+                end: day.end.min(end),
+                date: day.date,
+            });
+            if day.end >= end {
+                return Ok(days);
+            }
+            next = (*day.end + 1).into();
+        }
+
+        Err(DuneError::Other(anyhow::anyhow!(
+            "Heights {next} to {end} are missing from the blocks table"
+        )))
+    }
+}
+
+/// Splits the rows of a flat range file covering `start..=end` into the
+/// `days` of the blocks table, writing a partition for every day even if it
+/// has no rows. Partitions are re-encoded with the codec recorded in the
+/// source file.
+pub fn partition_by_date<T: TableRow>(
+    data: &[u8],
+    start: BlockHeight,
+    end: BlockHeight,
+    days: &BlockDays,
+) -> DuneResult<Vec<DatePartition>> {
+    let codec = read_codec_metadata(data)?.unwrap_or_default();
+    let parser = AvroParser::new(codec);
+    let rows = parser.reader_with_schema::<T>()?.deserialize(data)?;
+    let days = days.split(start, end)?;
+
+    let mut groups: Vec<Vec<T>> = days.iter().map(|_| Vec::new()).collect();
+    for row in rows {
+        let height = row.block_height().ok_or_else(|| {
+            DuneError::Other(anyhow::anyhow!("Row has no block height"))
+        })?;
+        let index = days.partition_point(|day| i64::from(*day.end) < height);
+        if index == days.len() || height < i64::from(*days[index].start) {
+            return Err(DuneError::Other(anyhow::anyhow!(
+                "Row at height {height} is outside of {start} to {end}"
+            )));
+        }
+        groups[index].push(row);
+    }
+
+    let mut partitions = Vec::with_capacity(days.len());
+    for (day, group) in days.into_iter().zip(groups) {
+        let mut writer = parser.writer_with_schema::<T>()?;
+        for row in &group {
+            writer.append(row)?;
+        }
+        partitions.push(DatePartition {
+            start_height: day.start,
+            end_height: day.end,
+            date: day.date,
+            rows: group.len(),
+            data: writer.into_inner()?,
+        });
+    }
+
+    Ok(partitions)
+}
+
+/// Rewrites every flat range file of `table` into the date-partitioned layout,
+/// split at the `days` of the blocks table. New objects are written before any
+/// source is deleted, and rewriting a file produces the same keys, so an
+/// interrupted run can simply be restarted.
+pub async fn migrate_table<T: TableRow>(
+    storage: &S3Storage,
+    network: FuelNetwork,
+    table: S3TableName,
+    days: &BlockDays,
+    delete_source: bool,
+    dry_run: bool,
+) -> DuneResult<MigrationReport> {
+    let flat = S3KeyBuilder::new(network).with_table(table);
+    let partitioned = S3KeyBuilder::new(network)
+        .with_table(table)
+        .with_layout(KeyLayout::DatePartitioned);
+    let prefix = flat.table_prefix();
+
+    // Deleting the sources would break the files referenced by Iceberg snapshots
+    if delete_source
+        && storage
+            .retrieve(&version_hint_key(&prefix))
+            .await?
+            .is_some()
+    {
+        return Err(DuneError::Other(anyhow::anyhow!(
+            "Table {table} has Iceberg metadata, migrate it without --delete-source"
+        )));
+    }
+
+    // Only direct children of the table prefix belong to the flat layout
+    let mut sources: Vec<_> = storage
+        .list_keys(&prefix)
+        .await?
+        .into_iter()
+        .filter(|key| !key[prefix.len()..].contains('/'))
+        .filter_map(|key| parse_range_key(&key).map(|range| (range, key)))
+        .collect();
+    sources.sort_by_key(|((start, _), _)| *start);
+
+    let mut report = MigrationReport::default();
+    for ((start, end), key) in sources {
+        let data = storage.retrieve(&key).await?.ok_or_else(|| {
+            DuneError::Other(anyhow::anyhow!("Object {key} disappeared during migration"))
+        })?;
+        let partitions = partition_by_date::<T>(&data, start, end, days)?;
+        report.source_files += 1;
+
+        for partition in partitions {
+            let new_key = partitioned.build_range_key(
+                partition.start_height,
+                partition.end_height,
+                partition.date,
+            );
+            tracing::info!("{key} -> {new_key} ({} rows)", partition.rows);
+            if !dry_run {
+                storage.store(&new_key, partition.data).await?;
+            }
+            report.written_files += 1;
+            report.rows += partition.rows;
+        }
+
+        if delete_source && !dry_run {
+            storage.delete(&key).await?;
+            report.deleted_files += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Entry point of the `migrate-layout` command
+pub async fn run(args: MigrateLayoutArgs) -> anyhow::Result<()> {
+    let storage = S3Storage::new(S3StorageOpts::admin_opts()).await?;
+    let network = FuelNetwork::load_from_env();
+
+    // Loaded before any table is migrated, as migrating the blocks table may
+    // delete its flat files
+    let days = BlockDays::load(&storage, network).await?;
+    tracing::info!("Found {} days in the blocks table", days.len());
+
+    for table in args.tables {
+        let report = with_table_row!(table, |Row| {
+            migrate_table::<Row>(
+                &storage,
+                network,
+                table,
+                &days,
+                args.delete_source,
+                args.dry_run,
+            )
+            .await?
+        })
+        .ok_or_else(|| anyhow::anyhow!("The metadata table has no range files"))?;
+        tracing::info!(
+            "Migrated {table}: {} source files, {} written, {} deleted, {} rows",
+            report.source_files,
+            report.written_files,
+            report.deleted_files,
+            report.rows
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{
+        TimeZone,
+        Utc,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        helpers::AvroCodec,
+        schemas::AvroTransaction,
+    };
+
+    fn block(height: i64, time: i64) -> AvroBlock {
+        AvroBlock {
+            height: Some(height),
+            time: Some(time),
+            ..Default::default()
+        }
+    }
+
+    fn transaction(height: i64) -> AvroTransaction {
+        AvroTransaction {
+            block_height: Some(height),
+            ..Default::default()
+        }
+    }
+
+    fn encode<T: TableRow>(codec: AvroCodec, rows: &[T]) -> DuneResult<Vec<u8>> {
+        let mut writer = AvroParser::new(codec).writer_with_schema::<T>()?;
+        for row in rows {
+            writer.append(row)?;
+        }
+        Ok(writer.into_inner()?)
+    }
+
+    fn midnight() -> i64 {
+        Utc.with_ymd_and_hms(2025, 4, 19, 0, 0, 0)
+            .unwrap()
+            .timestamp()
+    }
+
+    /// Blocks 10 to 13, the last two on the second day
+    fn blocks() -> Vec<AvroBlock> {
+        let midnight = midnight();
+        vec![
+            block(10, midnight - 2),
+            block(11, midnight - 1),
+            block(12, midnight),
+            block(13, midnight + 1),
+        ]
+    }
+
+    fn ranges(partitions: &[DatePartition]) -> Vec<(u32, u32, usize)> {
+        partitions
+            .iter()
+            .map(|p| (*p.start_height, *p.end_height, p.rows))
+            .collect()
+    }
+
+    #[test]
+    fn test_partition_by_date_splits_at_utc_midnight() -> DuneResult<()> {
+        let rows = blocks();
+        let mut days = BlockDays::default();
+        days.insert_blocks(&rows, 10.into(), 13.into())?;
+
+        let data = encode(AvroCodec::Snappy, &rows)?;
+        let partitions =
+            partition_by_date::<AvroBlock>(&data, 10.into(), 13.into(), &days)?;
+        assert_eq!(partitions.len(), 2);
+
+        let first = &partitions[0];
+        assert_eq!(first.date, NaiveDate::from_ymd_opt(2025, 4, 18).unwrap());
+        assert_eq!(
+            (first.start_height, first.end_height),
+            (10.into(), 11.into())
+        );
+        assert_eq!(first.rows, 2);
+
+        let second = &partitions[1];
+        assert_eq!(second.date, NaiveDate::from_ymd_opt(2025, 4, 19).unwrap());
+        assert_eq!(
+            (second.start_height, second.end_height),
+            (12.into(), 13.into())
+        );
+        assert_eq!(read_codec_metadata(&second.data)?, Some(AvroCodec::Snappy));
+
+        let decoded = AvroParser::default()
+            .reader_with_schema::<AvroBlock>()?
+            .deserialize(&second.data)?;
+        assert_eq!(decoded, rows[2..].to_vec());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_partition_by_date_uses_the_days_of_the_blocks() -> DuneResult<()> {
+        let mut days = BlockDays::default();
+        days.insert_blocks(&blocks(), 10.into(), 13.into())?;
+
+        // Transactions in the middle of each day still get the bounds of the
+        // blocks files
+        let data = encode(AvroCodec::Null, &[transaction(11), transaction(13)])?;
+        let partitions =
+            partition_by_date::<AvroTransaction>(&data, 10.into(), 13.into(), &days)?;
+        assert_eq!(ranges(&partitions), vec![(10, 11, 1), (12, 13, 1)]);
+
+        // A day without rows gets an empty file
+        let data = encode(AvroCodec::Null, &[transaction(10)])?;
+        let partitions =
+            partition_by_date::<AvroTransaction>(&data, 10.into(), 13.into(), &days)?;
+        assert_eq!(ranges(&partitions), vec![(10, 11, 1), (12, 13, 0)]);
+
+        let data = encode::<AvroTransaction>(AvroCodec::Null, &[])?;
+        let partitions =
+            partition_by_date::<AvroTransaction>(&data, 10.into(), 13.into(), &days)?;
+        assert_eq!(ranges(&partitions), vec![(10, 11, 0), (12, 13, 0)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_days_split() -> DuneResult<()> {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 4, day).unwrap();
+        let mut days = BlockDays::default();
+        days.insert_blocks(&blocks(), 10.into(), 13.into())?;
+        // Already migrated blocks of the next range
+        days.insert(DayRange {
+            start: 14.into(),
+            end: 20.into(),
+            date: date(19),
+        });
+
+        let split = days.split(11.into(), 15.into())?;
+        assert_eq!(
+            split,
+            vec![
+                DayRange {
+                    start: 11.into(),
+                    end: 11.into(),
+                    date: date(18),
+                },
+                DayRange {
+                    start: 12.into(),
+                    end: 13.into(),
+                    date: date(19),
+                },
+                DayRange {
+                    start: 14.into(),
+                    end: 15.into(),
+                    date: date(19),
+                },
+            ]
+        );
+
+        assert!(days.split(5.into(), 12.into()).is_err());
+        assert!(days.split(15.into(), 21.into()).is_err());
+        Ok(())
+    }
+}
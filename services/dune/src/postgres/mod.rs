@@ -0,0 +1,80 @@
+//! PostgreSQL output for blocks, transactions, inputs, outputs and receipts.
+//!
+//! Each batch is written to the `dune` schema with `INSERT ... ON CONFLICT DO
+//! UPDATE`, binding the values with their sqlx encoding, in the same
+//! transaction that advances the exporter checkpoint. Writing a batch again
+//! after a restart therefore updates the existing rows instead of duplicating
+//! them.
+
+mod rows;
+mod sink;
+
+use std::fmt::Display;
+
+pub use rows::*;
+pub use sink::*;
+use url::Url;
+
+/// Postgres sink error types.
+#[derive(Debug, thiserror::Error)]
+pub enum PostgresError {
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+    #[error(transparent)]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+    #[error("Invalid checkpoint {name}: block height {height} is out of range")]
+    InvalidCheckpoint { name: String, height: i64 },
+}
+
+/// Where the service writes the exported blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Range files uploaded to the configured storage
+    #[default]
+    Files,
+    /// Rows upserted into PostgreSQL
+    Postgres,
+    /// Both range files and PostgreSQL rows
+    Both,
+}
+
+impl OutputMode {
+    pub fn files(&self) -> bool {
+        matches!(self, OutputMode::Files | OutputMode::Both)
+    }
+
+    pub fn postgres(&self) -> bool {
+        matches!(self, OutputMode::Postgres | OutputMode::Both)
+    }
+}
+
+impl Display for OutputMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputMode::Files => write!(f, "files"),
+            OutputMode::Postgres => write!(f, "postgres"),
+            OutputMode::Both => write!(f, "both"),
+        }
+    }
+}
+
+impl std::str::FromStr for OutputMode {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "files" => Ok(OutputMode::Files),
+            "postgres" => Ok(OutputMode::Postgres),
+            "both" => Ok(OutputMode::Both),
+            _ => Err(anyhow::anyhow!("Unknown output mode {input}")),
+        }
+    }
+}
+
+/// Connection settings of the Postgres output.
+#[derive(Debug, Clone)]
+pub struct PostgresConfig {
+    pub url: Url,
+    /// Number of blocks written per database transaction
+    pub batch_size: usize,
+}
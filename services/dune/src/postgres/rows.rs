@@ -0,0 +1,726 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+use fuel_streams_domains::{
+    blocks::Block,
+    inputs::Input,
+    outputs::Output,
+    transactions::Transaction,
+};
+use sqlx::{
+    Postgres,
+    QueryBuilder,
+    query_builder::Separated,
+};
+
+use crate::{
+    helpers::AvroBytes,
+    schemas::{
+        AvroBlock,
+        AvroReceipt,
+        AvroTransaction,
+        InputCoin,
+        InputContract,
+        InputMessage,
+        OutputChange,
+        OutputCoin,
+        OutputContract,
+        OutputContractCreated,
+        OutputVariable,
+    },
+};
+
+/// Schema holding the tables written by the sink
+pub const SCHEMA: &str = "dune";
+
+/// A table written by the sink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgTable {
+    pub name: &'static str,
+    /// Columns in the order of the values of a [`RowBuffer`] row
+    pub columns: &'static [&'static str],
+    /// Primary key, used as the conflict target of the upsert
+    pub key: &'static [&'static str],
+}
+
+pub const BLOCKS: PgTable = PgTable {
+    name: "blocks",
+    columns: &[
+        "block_height",
+        "block_time",
+        "id",
+        "version",
+        "producer",
+        "transactions_count",
+        "transactions_root",
+        "application_hash",
+        "consensus_parameters_version",
+        "da_height",
+        "event_inbox_root",
+        "message_outbox_root",
+        "message_receipt_count",
+        "prev_root",
+        "state_transition_bytecode_version",
+        "consensus_type",
+        "poa_signature",
+    ],
+    key: &["block_height"],
+};
+
+pub const TRANSACTIONS: PgTable = PgTable {
+    name: "transactions",
+    columns: &[
+        "block_height",
+        "tx_index",
+        "block_time",
+        "id",
+        "type",
+        "status",
+        "script_gas_limit",
+        "maturity",
+        "max_fee",
+        "tip",
+        "witness_limit",
+        "mint_amount",
+        "mint_asset_id",
+        "mint_gas_price",
+        "bytecode_root",
+        "bytecode_witness_index",
+        "blob_id",
+        "salt",
+        "receipts_root",
+        "subsection_index",
+        "subsections_number",
+        "script",
+        "script_data",
+        "inputs_count",
+        "outputs_count",
+        "witnesses_count",
+        "storage_slots_count",
+        "raw_payload",
+    ],
+    key: &["block_height", "tx_index"],
+};
+
+pub const INPUTS: PgTable = PgTable {
+    name: "inputs",
+    columns: &[
+        "block_height",
+        "tx_index",
+        "input_index",
+        "tx_id",
+        "input_type",
+        "utxo_id",
+        "owner",
+        "asset_id",
+        "amount",
+        "contract_id",
+        "balance_root",
+        "state_root",
+        "sender",
+        "recipient",
+        "nonce",
+        "data",
+        "predicate",
+        "predicate_data",
+        "predicate_gas_used",
+        "witness_index",
+    ],
+    key: &["block_height", "tx_index", "input_index"],
+};
+
+pub const OUTPUTS: PgTable = PgTable {
+    name: "outputs",
+    columns: &[
+        "block_height",
+        "tx_index",
+        "output_index",
+        "tx_id",
+        "output_type",
+        "recipient",
+        "asset_id",
+        "amount",
+        "contract_id",
+        "input_index",
+        "balance_root",
+        "state_root",
+    ],
+    key: &["block_height", "tx_index", "output_index"],
+};
+
+pub const RECEIPTS: PgTable = PgTable {
+    name: "receipts",
+    columns: &[
+        "block_height",
+        "tx_index",
+        "receipt_index",
+        "tx_id",
+        "receipt_type",
+        "contract_id",
+        "to_contract",
+        "to_address",
+        "amount",
+        "asset_id",
+        "gas",
+        "gas_used",
+        "param1",
+        "param2",
+        "instruction_start",
+        "ptr",
+        "len",
+        "val",
+        "ra",
+        "rb",
+        "rc",
+        "rd",
+        "digest",
+        "data",
+        "result",
+        "panic_reason",
+        "panic_instruction",
+        "sub_id",
+        "sender",
+        "recipient",
+        "nonce",
+    ],
+    key: &["block_height", "tx_index", "receipt_index"],
+};
+
+/// Most parameters Postgres accepts in one statement
+const MAX_BINDS: usize = u16::MAX as usize;
+
+fn quoted(columns: &[&str]) -> String {
+    columns
+        .iter()
+        .map(|column| format!("\"{column}\""))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl PgTable {
+    /// Rows written by a single statement, within the parameter limit
+    pub fn rows_per_statement(&self) -> usize {
+        MAX_BINDS / self.columns.len()
+    }
+
+    /// Inserts `rows`, replacing the rows with the same key
+    pub fn upsert_query<'a>(
+        &self,
+        rows: &'a [Vec<PgValue>],
+    ) -> QueryBuilder<'a, Postgres> {
+        let mut query = QueryBuilder::new(format!(
+            "INSERT INTO {SCHEMA}.{} ({}) ",
+            self.name,
+            quoted(self.columns)
+        ));
+        query.push_values(rows, |mut values, row| {
+            for value in row {
+                value.bind(&mut values);
+            }
+        });
+
+        let updates = self
+            .columns
+            .iter()
+            .filter(|column| !self.key.contains(column))
+            .map(|column| format!("\"{column}\" = EXCLUDED.\"{column}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        query.push(format!(
+            " ON CONFLICT ({}) DO UPDATE SET {updates}",
+            quoted(self.key)
+        ));
+        query
+    }
+}
+
+/// A column value, bound with the sqlx encoding of its Postgres type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgValue {
+    Int(Option<i64>),
+    /// A 64-bit word the Avro rows carry as `i64`, for the `NUMERIC(20, 0)`
+    /// columns
+    Word(Option<i64>),
+    Text(Option<String>),
+    Bytes(Option<Vec<u8>>),
+    Time(Option<DateTime<Utc>>),
+}
+
+impl PgValue {
+    fn bind<'a>(&'a self, values: &mut Separated<'_, 'a, Postgres, &'static str>) {
+        match self {
+            PgValue::Int(value) => {
+                values.push_bind(*value);
+            }
+            // sqlx has no `NUMERIC` encoding without an extra decimal crate,
+            // so the cast back to `u64` is sent as text. It restores values
+            // above `i64::MAX`.
+            PgValue::Word(value) => {
+                values
+                    .push_bind(value.map(|value| (value as u64).to_string()))
+                    .push_unseparated("::NUMERIC");
+            }
+            PgValue::Text(value) => {
+                values.push_bind(value.as_deref());
+            }
+            PgValue::Bytes(value) => {
+                values.push_bind(value.as_deref());
+            }
+            PgValue::Time(value) => {
+                values.push_bind(*value);
+            }
+        }
+    }
+}
+
+/// Rows of a table, one [`PgValue`] per column.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RowBuffer {
+    rows: Vec<Vec<PgValue>>,
+    row: Vec<PgValue>,
+}
+
+impl RowBuffer {
+    pub fn as_rows(&self) -> &[Vec<PgValue>] {
+        &self.rows
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn end_row(&mut self) {
+        self.rows.push(std::mem::take(&mut self.row));
+    }
+
+    fn text(&mut self, value: Option<&str>) {
+        self.row.push(PgValue::Text(value.map(str::to_string)));
+    }
+
+    fn int(&mut self, value: Option<i64>) {
+        self.row.push(PgValue::Int(value));
+    }
+
+    fn word(&mut self, value: Option<i64>) {
+        self.row.push(PgValue::Word(value));
+    }
+
+    fn bytes(&mut self, value: Option<&AvroBytes>) {
+        self.row
+            .push(PgValue::Bytes(value.map(|value| value.0.clone())));
+    }
+
+    /// Unix timestamp in seconds, as a `TIMESTAMP WITH TIME ZONE`
+    fn time(&mut self, value: Option<i64>) {
+        let time = value.and_then(|secs| DateTime::from_timestamp(secs, 0));
+        self.row.push(PgValue::Time(time));
+    }
+}
+
+/// One row of the `inputs` table.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct InputRow {
+    input_type: &'static str,
+    utxo_id: Option<String>,
+    owner: Option<AvroBytes>,
+    asset_id: Option<AvroBytes>,
+    amount: Option<i64>,
+    contract_id: Option<AvroBytes>,
+    balance_root: Option<AvroBytes>,
+    state_root: Option<AvroBytes>,
+    sender: Option<AvroBytes>,
+    recipient: Option<AvroBytes>,
+    nonce: Option<AvroBytes>,
+    data: Option<AvroBytes>,
+    predicate: Option<AvroBytes>,
+    predicate_data: Option<AvroBytes>,
+    predicate_gas_used: Option<i64>,
+    witness_index: Option<i64>,
+}
+
+impl InputRow {
+    fn new(input: &Input) -> Self {
+        match input {
+            Input::Contract(contract) => {
+                let contract = InputContract::new(contract);
+                Self {
+                    input_type: "contract",
+                    utxo_id: contract.utxo_id,
+                    contract_id: contract.contract_id,
+                    balance_root: contract.balance_root,
+                    state_root: contract.state_root,
+                    ..Default::default()
+                }
+            }
+            Input::Coin(coin) => {
+                let coin = InputCoin::new(coin);
+                Self {
+                    input_type: "coin",
+                    utxo_id: coin.utxo_id,
+                    owner: coin.owner,
+                    asset_id: coin.asset_id,
+                    amount: coin.amount,
+                    predicate: coin.predicate,
+                    predicate_data: coin.predicate_data,
+                    predicate_gas_used: coin.predicate_gas_used,
+                    witness_index: coin.witness_index,
+                    ..Default::default()
+                }
+            }
+            Input::Message(message) => {
+                let message = InputMessage::new(message);
+                Self {
+                    input_type: "message",
+                    amount: message.amount,
+                    sender: message.sender,
+                    recipient: message.recipient,
+                    nonce: message.nonce,
+                    data: message.data,
+                    predicate: message.predicate,
+                    predicate_data: message.predicate_data,
+                    predicate_gas_used: message.predicate_gas_used,
+                    witness_index: message.witness_index,
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    fn write(&self, buf: &mut RowBuffer) {
+        buf.text(Some(self.input_type));
+        buf.text(self.utxo_id.as_deref());
+        buf.bytes(self.owner.as_ref());
+        buf.bytes(self.asset_id.as_ref());
+        buf.word(self.amount);
+        buf.bytes(self.contract_id.as_ref());
+        buf.bytes(self.balance_root.as_ref());
+        buf.bytes(self.state_root.as_ref());
+        buf.bytes(self.sender.as_ref());
+        buf.bytes(self.recipient.as_ref());
+        buf.bytes(self.nonce.as_ref());
+        buf.bytes(self.data.as_ref());
+        buf.bytes(self.predicate.as_ref());
+        buf.bytes(self.predicate_data.as_ref());
+        buf.word(self.predicate_gas_used);
+        buf.int(self.witness_index);
+    }
+}
+
+/// One row of the `outputs` table.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct OutputRow {
+    output_type: &'static str,
+    recipient: Option<AvroBytes>,
+    asset_id: Option<AvroBytes>,
+    amount: Option<i64>,
+    contract_id: Option<AvroBytes>,
+    input_index: Option<i64>,
+    balance_root: Option<AvroBytes>,
+    state_root: Option<AvroBytes>,
+}
+
+impl OutputRow {
+    fn new(output: &Output) -> Self {
+        match output {
+            Output::Coin(coin) => {
+                let coin = OutputCoin::new(coin);
+                Self {
+                    output_type: "coin",
+                    recipient: coin.to,
+                    asset_id: coin.asset_id,
+                    amount: coin.amount,
+                    ..Default::default()
+                }
+            }
+            Output::Contract(contract) => {
+                let contract = OutputContract::new(contract);
+                Self {
+                    output_type: "contract",
+                    input_index: contract.input_index,
+                    balance_root: contract.balance_root,
+                    state_root: contract.state_root,
+                    ..Default::default()
+                }
+            }
+            Output::Change(change) => {
+                let change = OutputChange::new(change);
+                Self {
+                    output_type: "change",
+                    recipient: change.to,
+                    asset_id: change.asset_id,
+                    amount: change.amount,
+                    ..Default::default()
+                }
+            }
+            Output::Variable(variable) => {
+                let variable = OutputVariable::new(variable);
+                Self {
+                    output_type: "variable",
+                    recipient: variable.to,
+                    asset_id: variable.asset_id,
+                    amount: variable.amount,
+                    ..Default::default()
+                }
+            }
+            Output::ContractCreated(created) => {
+                let created = OutputContractCreated::new(created);
+                Self {
+                    output_type: "contract_created",
+                    contract_id: created.contract_id,
+                    state_root: created.state_root,
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    fn write(&self, buf: &mut RowBuffer) {
+        buf.text(Some(self.output_type));
+        buf.bytes(self.recipient.as_ref());
+        buf.bytes(self.asset_id.as_ref());
+        buf.word(self.amount);
+        buf.bytes(self.contract_id.as_ref());
+        buf.int(self.input_index);
+        buf.bytes(self.balance_root.as_ref());
+        buf.bytes(self.state_root.as_ref());
+    }
+}
+
+/// The rows of every table for a batch of blocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchRows {
+    pub blocks: RowBuffer,
+    pub transactions: RowBuffer,
+    pub inputs: RowBuffer,
+    pub outputs: RowBuffer,
+    pub receipts: RowBuffer,
+}
+
+impl BatchRows {
+    pub fn new(blocks_and_txs: &[(Block, Vec<Transaction>)]) -> Self {
+        let mut rows = Self::default();
+        for (block, transactions) in blocks_and_txs {
+            rows.push_block(block);
+            for (tx_index, tx) in transactions.iter().enumerate() {
+                rows.push_transaction(block, tx_index as i64, tx);
+            }
+        }
+        rows
+    }
+
+    /// Tables in the order they are written
+    pub fn tables(&self) -> [(PgTable, &RowBuffer); 5] {
+        [
+            (BLOCKS, &self.blocks),
+            (TRANSACTIONS, &self.transactions),
+            (INPUTS, &self.inputs),
+            (OUTPUTS, &self.outputs),
+            (RECEIPTS, &self.receipts),
+        ]
+    }
+
+    fn push_block(&mut self, block: &Block) {
+        let row = AvroBlock::new(block);
+        let buf = &mut self.blocks;
+        buf.int(row.height);
+        buf.time(row.time);
+        buf.bytes(row.id.as_ref());
+        buf.text(row.version.as_deref());
+        buf.bytes(row.producer.as_ref());
+        buf.int(row.transactions_count);
+        buf.bytes(row.transactions_root.as_ref());
+        buf.bytes(row.application_hash.as_ref());
+        buf.int(row.consensus_parameters_version);
+        buf.word(row.da_height);
+        buf.bytes(row.event_inbox_root.as_ref());
+        buf.bytes(row.message_outbox_root.as_ref());
+        buf.int(row.message_receipt_count);
+        buf.bytes(row.prev_root.as_ref());
+        buf.int(row.state_transition_bytecode_version);
+        buf.text(row.consensus_type.as_deref());
+        buf.bytes(row.poa_consensus_data_signature.as_ref());
+        buf.end_row();
+    }
+
+    fn push_transaction(&mut self, block: &Block, tx_index: i64, tx: &Transaction) {
+        let row = AvroTransaction::from((block, tx));
+        let policies = row.policies.clone().unwrap_or_default();
+        let buf = &mut self.transactions;
+        buf.int(row.block_height);
+        buf.int(Some(tx_index));
+        buf.time(row.block_time);
+        buf.bytes(row.id.as_ref());
+        buf.text(row.r#type.as_deref());
+        buf.text(row.status.as_deref());
+        buf.word(row.script_gas_limit);
+        buf.int(row.maturity);
+        buf.word(policies.max_fee);
+        buf.word(policies.tip);
+        buf.word(policies.witness_limit);
+        buf.word(row.mint_amount);
+        buf.bytes(row.mint_asset_id.as_ref());
+        buf.word(row.mint_gas_price);
+        buf.bytes(row.bytecode_root.as_ref());
+        buf.int(row.bytecode_witness_index);
+        buf.bytes(row.blob_id.as_ref());
+        buf.bytes(row.salt.as_ref());
+        buf.bytes(row.receipts_root.as_ref());
+        buf.int(row.subsection_index);
+        buf.int(row.subsections_number);
+        buf.bytes(row.script.as_ref());
+        buf.bytes(row.script_data.as_ref());
+        buf.int(row.inputs_count);
+        buf.int(row.outputs_count);
+        buf.int(row.witnesses_count);
+        buf.int(row.storage_slots_count);
+        buf.bytes(row.raw_payload.as_ref());
+        buf.end_row();
+
+        let key = |buf: &mut RowBuffer, index: usize| {
+            buf.int(row.block_height);
+            buf.int(Some(tx_index));
+            buf.int(Some(index as i64));
+            buf.bytes(row.id.as_ref());
+        };
+
+        for (index, input) in tx.inputs.iter().enumerate() {
+            key(&mut self.inputs, index);
+            InputRow::new(input).write(&mut self.inputs);
+            self.inputs.end_row();
+        }
+
+        for (index, output) in tx.outputs.iter().enumerate() {
+            key(&mut self.outputs, index);
+            OutputRow::new(output).write(&mut self.outputs);
+            self.outputs.end_row();
+        }
+
+        for (index, receipt) in tx.receipts.iter().enumerate() {
+            let receipt = AvroReceipt::from((block, tx, receipt));
+            let buf = &mut self.receipts;
+            key(buf, index);
+            buf.text(receipt.receipt_type.as_deref());
+            buf.bytes(receipt.contract_id.as_ref());
+            buf.bytes(receipt.to.as_ref());
+            buf.text(receipt.to_address.as_deref());
+            buf.word(receipt.amount);
+            buf.bytes(receipt.asset_id.as_ref());
+            buf.word(receipt.gas);
+            buf.word(receipt.gas_used);
+            buf.word(receipt.param1);
+            buf.word(receipt.param2);
+            buf.word(receipt.is);
+            buf.word(receipt.ptr);
+            buf.word(receipt.len);
+            buf.word(receipt.val);
+            buf.word(receipt.ra);
+            buf.word(receipt.rb);
+            buf.word(receipt.rc);
+            buf.word(receipt.rd);
+            buf.bytes(receipt.digest.as_ref());
+            buf.text(receipt.data.as_deref());
+            buf.word(receipt.result);
+            buf.int(receipt.reason_reason.map(i64::from));
+            buf.int(receipt.reason_instruction);
+            buf.bytes(receipt.sub_id.as_ref());
+            buf.bytes(receipt.sender.as_ref());
+            buf.bytes(receipt.recipient.as_ref());
+            buf.bytes(receipt.nonce.as_ref());
+            buf.end_row();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_streams_domains::{
+        inputs::types::MockInput,
+        mocks::MockBlock,
+        outputs::types::MockOutput,
+        receipts::types::MockReceipt,
+        transactions::MockTransaction,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_row_buffer_values() {
+        let mut buf = RowBuffer::default();
+        buf.int(Some(-1));
+        buf.word(Some(-1));
+        buf.text(Some(""));
+        buf.text(None);
+        buf.bytes(Some(&AvroBytes(vec![0x00, 0xab])));
+        buf.time(Some(1_744_934_400));
+        buf.end_row();
+
+        assert_eq!(buf.rows(), 1);
+        assert_eq!(
+            buf.as_rows()[0],
+            vec![
+                PgValue::Int(Some(-1)),
+                PgValue::Word(Some(-1)),
+                PgValue::Text(Some(String::new())),
+                PgValue::Text(None),
+                PgValue::Bytes(Some(vec![0x00, 0xab])),
+                PgValue::Time(DateTime::from_timestamp(1_744_934_400, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upsert_query() {
+        let row = vec![PgValue::Int(Some(1)), PgValue::Word(None)];
+        let table = PgTable {
+            name: "outputs",
+            columns: &["block_height", "amount"],
+            key: &["block_height"],
+        };
+        assert_eq!(
+            table.upsert_query(&[row.clone(), row]).sql(),
+            "INSERT INTO dune.outputs (\"block_height\", \"amount\") \
+             VALUES ($1, $2::NUMERIC), ($3, $4::NUMERIC) \
+             ON CONFLICT (\"block_height\") DO UPDATE SET \"amount\" = EXCLUDED.\"amount\""
+        );
+        assert_eq!(RECEIPTS.rows_per_statement(), 2114);
+    }
+
+    #[test]
+    fn test_batch_rows_match_columns() {
+        let tx = MockTransaction::script(
+            MockInput::all(),
+            MockOutput::all(),
+            MockReceipt::all(),
+        );
+        let batch = vec![(MockBlock::random(), vec![tx.clone(), tx.clone()])];
+        let rows = BatchRows::new(&batch);
+
+        assert_eq!(rows.blocks.rows(), 1);
+        assert_eq!(rows.transactions.rows(), 2);
+        assert_eq!(rows.inputs.rows(), 2 * tx.inputs.len());
+        assert_eq!(rows.outputs.rows(), 2 * tx.outputs.len());
+        assert_eq!(rows.receipts.rows(), 2 * tx.receipts.len());
+
+        // Every row has one value per column
+        for (table, buf) in rows.tables() {
+            for row in buf.as_rows() {
+                assert_eq!(row.len(), table.columns.len(), "{}", table.name);
+            }
+        }
+        let height = batch[0].0.height.0 as i64;
+        assert_eq!(
+            rows.inputs.as_rows()[0][..3],
+            [
+                PgValue::Int(Some(height)),
+                PgValue::Int(Some(0)),
+                PgValue::Int(Some(0)),
+            ]
+        );
+    }
+}
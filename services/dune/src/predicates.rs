@@ -0,0 +1,106 @@
+//! Predicate bytecodes recently exported.
+//!
+//! The `predicate_bytecodes` dimension has one row per bytecode, keyed by its
+//! hash. The exporter remembers the hashes it wrote most recently, so a
+//! bytecode used by many blocks is written once rather than in every batch.
+//! The set is bounded and kept in memory only: a bytecode is written again
+//! once it falls out of it or after a restart, and readers deduplicate on the
+//! hash.
+
+use std::collections::{
+    BTreeMap,
+    HashMap,
+};
+
+use fuel_streams_types::Bytes32;
+
+/// Default number of hashes remembered
+pub const DEFAULT_CAPACITY: usize = 100_000;
+
+/// Hashes of the predicate bytecodes written most recently, evicting the
+/// least recently used beyond its capacity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredicateHashes {
+    capacity: usize,
+    /// Last use of each hash
+    hashes: HashMap<Bytes32, u64>,
+    /// Hashes by last use
+    uses: BTreeMap<u64, Bytes32>,
+    next_use: u64,
+}
+
+impl Default for PredicateHashes {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl PredicateHashes {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            hashes: HashMap::new(),
+            uses: BTreeMap::new(),
+            next_use: 0,
+        }
+    }
+
+    /// Records a use of `hash`, returning false if its bytecode was written
+    /// recently
+    pub fn insert(&mut self, hash: Bytes32) -> bool {
+        let used = self.next_use;
+        self.next_use += 1;
+        if let Some(last_use) = self.hashes.insert(hash.clone(), used) {
+            self.uses.remove(&last_use);
+            self.uses.insert(used, hash);
+            return false;
+        }
+
+        self.uses.insert(used, hash);
+        if self.hashes.len() > self.capacity
+            && let Some((_, evicted)) = self.uses.pop_first()
+        {
+            self.hashes.remove(&evicted);
+        }
+        true
+    }
+
+    pub fn contains(&self, hash: &Bytes32) -> bool {
+        self.hashes.contains_key(hash)
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_predicate_hashes_evict_least_recently_used() {
+        let mut hashes = PredicateHashes::with_capacity(2);
+        let [a, b, c] = [Bytes32::random(), Bytes32::random(), Bytes32::random()];
+        assert!(hashes.insert(a.clone()));
+        assert!(hashes.insert(b.clone()));
+        // Using `a` again makes `b` the least recently used
+        assert!(!hashes.insert(a.clone()));
+        assert!(hashes.insert(c.clone()));
+
+        assert_eq!(hashes.len(), 2);
+        assert!(hashes.contains(&a));
+        assert!(!hashes.contains(&b));
+        assert!(hashes.contains(&c));
+
+        // An evicted bytecode is written again
+        assert!(hashes.insert(b.clone()));
+        assert!(!hashes.contains(&a));
+    }
+}
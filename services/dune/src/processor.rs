@@ -1,12 +1,23 @@
 use crate::{
     DuneError,
     DuneResult,
+    assets::AssetSupplies,
     helpers::{
         AvroParser,
         AvroWriter,
+        TableFormats,
+    },
+    iceberg::{
+        Catalog,
+        DataFile,
+        IcebergTable,
+        LocalCatalog,
+        S3Catalog,
+        day_partition,
     },
     s3::{
         FuelNetwork,
+        KeyLayout,
         S3KeyBuilder,
         S3Storage,
         S3StorageOpts,
@@ -14,9 +25,11 @@ use crate::{
         Storage,
         StorageConfig,
     },
+    schema_registry::SchemaRegistry,
     schemas::{
         AvroBlock,
         AvroReceipt,
+        AvroRow,
         AvroTransaction,
     },
 };
@@ -24,6 +37,7 @@ use apache_avro::{
     AvroSchema,
     schema::derive::AvroSchemaComponent,
 };
+use chrono::NaiveDate;
 use fuel_streams_domains::{
     blocks::Block,
     transactions::Transaction,
@@ -34,7 +48,10 @@ use std::{
     fs::File,
     io::Write,
     ops::Deref,
-    path::Path,
+    path::{
+        Path,
+        PathBuf,
+    },
     sync::Arc,
 };
 
@@ -55,6 +72,9 @@ pub enum StorageType {
 #[derive(Debug, Clone)]
 pub struct Processor {
     storage_type: StorageType,
+    layout: KeyLayout,
+    formats: TableFormats,
+    iceberg: bool,
     pub max_file_size: usize,
 }
 
@@ -86,6 +106,12 @@ impl std::str::FromStr for StorageTypeConfig {
 }
 
 const LATEST_BLOCK_HEIGHT_KEY: &str = "latest_block_height.txt";
+const ASSET_SUPPLIES_KEY: &str = "asset_supplies.json";
+
+/// Directory receiving the output of the `File` storage type
+fn output_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("output")
+}
 
 impl Processor {
     const DEFAULT_MAX_FILE_SIZE: usize = 100 * 1024 * 1024; // 100MB
@@ -102,6 +128,9 @@ impl Processor {
         };
         Ok(Self {
             storage_type,
+            layout: KeyLayout::default(),
+            formats: TableFormats::default(),
+            iceberg: false,
             max_file_size: Self::get_size(
                 Self::DEFAULT_MAX_FILE_SIZE,
                 SizeUnit::Megabytes,
@@ -119,6 +148,96 @@ impl Processor {
         Ok(processor)
     }
 
+    /// Sets the key layout used for range files uploaded from the disk buffer
+    pub fn with_layout(mut self, layout: KeyLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn layout(&self) -> KeyLayout {
+        self.layout
+    }
+
+    /// Sets the file format of each table, which selects the key extension
+    /// of range files uploaded from the disk buffer
+    pub fn with_formats(mut self, formats: TableFormats) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    pub fn formats(&self) -> &TableFormats {
+        &self.formats
+    }
+
+    /// Enables Iceberg snapshots for the range files uploaded from the disk
+    /// buffer
+    pub fn with_iceberg(mut self, iceberg: bool) -> Self {
+        self.iceberg = iceberg;
+        self
+    }
+
+    pub fn iceberg(&self) -> bool {
+        self.iceberg
+    }
+
+    /// Catalog holding the Iceberg metadata: the bucket itself for S3, or an
+    /// `iceberg` directory next to the local output files
+    pub fn iceberg_catalog(&self) -> Arc<dyn Catalog> {
+        match &self.storage_type {
+            StorageType::S3(s3_storage) => Arc::new(S3Catalog::new(s3_storage.clone())),
+            StorageType::File => {
+                Arc::new(LocalCatalog::new(output_dir().join("iceberg")))
+            }
+        }
+    }
+
+    /// Describes a range file returned by [`Processor::process_data_from_file`]
+    /// for an Iceberg commit. `local_path` is the file that was uploaded.
+    pub fn data_file(
+        &self,
+        stored: &str,
+        local_path: impl AsRef<Path>,
+        table: S3TableName,
+        record_count: u64,
+        date: NaiveDate,
+    ) -> DuneResult<DataFile> {
+        let file_path = match &self.storage_type {
+            StorageType::S3(s3_storage) => {
+                format!("s3://{}/{stored}", s3_storage.bucket())
+            }
+            StorageType::File => format!("file://{stored}"),
+        };
+        let partition = match self.layout {
+            KeyLayout::Flat => None,
+            KeyLayout::DatePartitioned => Some(day_partition(date)),
+        };
+        Ok(DataFile {
+            file_path,
+            file_format: self.formats.for_table(table),
+            record_count,
+            file_size_in_bytes: std::fs::metadata(local_path)?.len(),
+            partition,
+        })
+    }
+
+    /// Commits an Iceberg snapshot appending `file` to `table`, if Iceberg
+    /// snapshots are enabled. Returns whether a snapshot was committed.
+    pub async fn commit_iceberg<T: AvroRow + AvroSchema>(
+        &self,
+        table: S3TableName,
+        file: DataFile,
+        end_height: BlockHeight,
+    ) -> DuneResult<bool> {
+        if !self.iceberg {
+            return Ok(false);
+        }
+        let network = FuelNetwork::load_from_env();
+        let prefix = S3KeyBuilder::new(network).with_table(table).table_prefix();
+        IcebergTable::new::<T>(self.iceberg_catalog(), prefix, self.layout)?
+            .commit_append(&[file], end_height)
+            .await
+    }
+
     fn get_size(size: usize, unit: SizeUnit) -> usize {
         match unit {
             SizeUnit::Bytes => size,
@@ -131,9 +250,8 @@ impl Processor {
     async fn create_output(&self, data: Vec<u8>, key: &str) -> DuneResult<String> {
         let created = match &self.storage_type {
             StorageType::File => {
-                let manifest_dir = env!("CARGO_MANIFEST_DIR");
                 let flat_key = key.replace('/', "_").replace("-", "_");
-                let output_dir = Path::new(manifest_dir).join("output");
+                let output_dir = output_dir();
                 std::fs::create_dir_all(&output_dir)?;
                 let file_path = output_dir.join(&flat_key).to_path_buf();
                 tracing::info!("Writing file: {:?}", file_path);
@@ -149,6 +267,19 @@ impl Processor {
         Ok(created)
     }
 
+    /// Publishes the table schemas under the `Metadata` table, rejecting
+    /// schema changes that old files could not be read with. Skipped for
+    /// file storage, which keeps no published versions to check against.
+    pub async fn publish_schemas(&self) -> DuneResult<()> {
+        match &self.storage_type {
+            StorageType::S3(s3_storage) => {
+                let network = FuelNetwork::load_from_env();
+                SchemaRegistry::new(s3_storage, network).publish_all().await
+            }
+            StorageType::File => Ok(()),
+        }
+    }
+
     pub async fn save_latest_height(
         &self,
         height: fuel_core_types::fuel_types::BlockHeight,
@@ -187,6 +318,41 @@ impl Processor {
         Ok(Some(height.into()))
     }
 
+    /// Saves the asset supplies as of the last uploaded batch
+    pub async fn save_asset_supplies(
+        &self,
+        supplies: &AssetSupplies,
+    ) -> DuneResult<String> {
+        let network = FuelNetwork::load_from_env();
+        let key_builder = S3KeyBuilder::new(network).with_table(S3TableName::Metadata);
+        let key = key_builder.build_key(ASSET_SUPPLIES_KEY);
+        let data = supplies.to_json().map_err(|e| {
+            anyhow::anyhow!("Unable to serialize the asset supplies: {}", e)
+        })?;
+        let file_path = self.create_output(data, &key).await?;
+        Ok(file_path)
+    }
+
+    /// Loads the asset supplies saved with [`Self::save_asset_supplies`],
+    /// empty before the first batch with a mint or burn is uploaded
+    pub async fn load_asset_supplies(&self) -> DuneResult<AssetSupplies> {
+        let s3_storage_opts = S3StorageOpts::admin_opts();
+        let s3_storage = S3Storage::new(s3_storage_opts).await?;
+
+        let network = FuelNetwork::load_from_env();
+        let key_builder = S3KeyBuilder::new(network).with_table(S3TableName::Metadata);
+        let key = key_builder.build_key(ASSET_SUPPLIES_KEY);
+        let Some(data) = s3_storage.retrieve(&key).await? else {
+            return Ok(AssetSupplies::default());
+        };
+
+        let supplies = AssetSupplies::from_json(&data)
+            .map_err(|e| anyhow::anyhow!("Unable to parse the asset supplies: {}", e))?;
+        Ok(supplies)
+    }
+
+    /// Uploads in-memory batches. Keys always use the flat layout since the
+    /// batches carry no block timestamps.
     pub async fn process_range(
         &self,
         batches: Vec<(BlockHeight, BlockHeight, Vec<u8>)>,
@@ -224,16 +390,21 @@ impl Processor {
 
     /// Process data from a file path, streaming directly to S3.
     /// This avoids loading the entire file into memory - ideal for large batches.
+    /// `date` is the UTC day of the batch, used by the date-partitioned layout.
     pub async fn process_data_from_file(
         &self,
         start_height: BlockHeight,
         end_height: BlockHeight,
+        date: NaiveDate,
         file_path: impl AsRef<std::path::Path>,
         table: S3TableName,
     ) -> DuneResult<String> {
         let network = FuelNetwork::load_from_env();
-        let key_builder = S3KeyBuilder::new(network).with_table(table);
-        let key = key_builder.build_key_from_heights(start_height, end_height);
+        let key_builder = S3KeyBuilder::new(network)
+            .with_table(table)
+            .with_layout(self.layout)
+            .with_format(self.formats.for_table(table));
+        let key = key_builder.build_range_key(start_height, end_height, date);
 
         match &self.storage_type {
             StorageType::File => {
@@ -396,10 +567,13 @@ impl Processor {
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
-    use fuel_streams_domains::mocks::{
-        MockBlock,
-        MockReceipt,
-        MockTransaction,
+    use fuel_streams_domains::{
+        assets::AssetEvent,
+        mocks::{
+            MockBlock,
+            MockReceipt,
+            MockTransaction,
+        },
     };
     use pretty_assertions::assert_eq;
 
@@ -625,6 +799,23 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_load_save_asset_supplies_s3() -> Result<()> {
+        let processor =
+            Processor::new_with_unit(StorageTypeConfig::S3, 1, SizeUnit::Megabytes)
+                .await?;
+
+        let tx = MockTransaction::script(vec![], vec![], vec![MockReceipt::mint()]);
+        let mut supplies = AssetSupplies::default();
+        supplies.apply(7u32.into(), &AssetEvent::from_transaction(0, &tx));
+        processor.save_asset_supplies(&supplies).await?;
+
+        let result = processor.load_asset_supplies().await?;
+        assert_eq!(result, supplies, "Supplies should match");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_load_save_block_height_s3() -> Result<()> {
         let processor =
@@ -0,0 +1,667 @@
+//! Recomputes the Merkle roots committed by blocks and script transactions
+//! from exported rows.
+//!
+//! A block header's `transactions_root` is the binary Merkle root of the
+//! canonical bytes of its transactions, which are exported as `raw_payload`.
+//! A script transaction's `receipts_root` is the root of the canonical bytes
+//! of its receipts, rebuilt here from the receipts table. Receipt rows written
+//! before receipts schema v2 have no program counter, so their root cannot be
+//! recomputed and is counted as skipped. The message outbox and event inbox
+//! roots are not checked: relayer events are not exported.
+
+use std::{
+    collections::HashMap,
+    fmt::{
+        self,
+        Display,
+    },
+    sync::Arc,
+};
+
+use fuel_core_types::{
+    fuel_merkle::binary::root_calculator::MerkleRootCalculator,
+    fuel_tx::SubAssetId,
+    fuel_types::canonical::Serialize as _,
+};
+use fuel_streams_domains::{
+    blocks::Block,
+    receipts::Receipt,
+    transactions::Transaction,
+};
+use fuel_streams_types::{
+    FuelCorePanicInstruction,
+    FuelCoreReceipt,
+    FuelCoreScriptExecutionResult,
+    PanicInstruction,
+    ScriptExecutionResult,
+};
+use futures::TryStreamExt;
+
+use crate::{
+    DuneError,
+    DuneResult,
+    ValidateArgs,
+    compact::RangeObject,
+    data_lake::DataLakeReader,
+    helpers::AvroBytes,
+    s3::{
+        FuelNetwork,
+        S3Storage,
+        S3StorageOpts,
+        S3TableName,
+        Storage,
+        StorageConfig,
+    },
+    schemas::{
+        AvroBlock,
+        AvroReceipt,
+        AvroTransaction,
+        RowDecodeError,
+    },
+    verify::hex,
+};
+
+/// Default number of blocks read and validated at once
+pub const DEFAULT_CHUNK_SIZE: u32 = 1000;
+
+/// Receipt types without a program counter
+const RECEIPTS_WITHOUT_PC: &[&str] = &["script_result", "message_out"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootKind {
+    /// `transactions_root` of a block header
+    Transactions,
+    /// `receipts_root` of a script transaction
+    Receipts,
+}
+
+impl Display for RootKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transactions => write!(f, "transactions root"),
+            Self::Receipts => write!(f, "receipts root"),
+        }
+    }
+}
+
+/// A stored root that differs from the one recomputed from the rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootMismatch {
+    pub kind: RootKind,
+    pub height: i64,
+    pub tx_id: Option<String>,
+    pub stored: String,
+    pub computed: String,
+}
+
+impl Display for RootMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "height {}", self.height)?;
+        if let Some(tx_id) = &self.tx_id {
+            write!(f, " tx {tx_id}")?;
+        }
+        write!(
+            f,
+            ": {} is {}, recomputed {}",
+            self.kind, self.stored, self.computed
+        )
+    }
+}
+
+/// Outcome of validating the roots of one or more blocks.
+#[derive(Debug, Clone, Default)]
+pub struct RootsReport {
+    pub blocks: usize,
+    /// Receipts roots compared
+    pub receipts_roots: usize,
+    /// Receipts roots that could not be recomputed from rows without a
+    /// program counter
+    pub skipped: usize,
+    pub mismatches: Vec<RootMismatch>,
+}
+
+impl RootsReport {
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn merge(&mut self, other: RootsReport) {
+        self.blocks += other.blocks;
+        self.receipts_roots += other.receipts_roots;
+        self.skipped += other.skipped;
+        self.mismatches.extend(other.mismatches);
+    }
+
+    fn compare(
+        &mut self,
+        kind: RootKind,
+        height: i64,
+        tx_id: Option<&AvroBytes>,
+        stored: &AvroBytes,
+        computed: [u8; 32],
+    ) {
+        if stored.0 != computed {
+            self.mismatches.push(RootMismatch {
+                kind,
+                height,
+                tx_id: tx_id.map(|id| hex(&id.0)),
+                stored: hex(&stored.0),
+                computed: hex(&computed),
+            });
+        }
+    }
+}
+
+impl Display for RootsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Validated {} blocks and {} receipts roots ({} skipped): {} mismatches",
+            self.blocks,
+            self.receipts_roots,
+            self.skipped,
+            self.mismatches.len()
+        )
+    }
+}
+
+/// Binary Merkle root of the canonical transaction bytes, in block order
+pub fn transactions_root<'a>(payloads: impl IntoIterator<Item = &'a [u8]>) -> [u8; 32] {
+    let mut calculator = MerkleRootCalculator::new();
+    for payload in payloads {
+        calculator.push(payload);
+    }
+    calculator.root()
+}
+
+/// Binary Merkle root of the canonical receipt bytes, in execution order
+pub fn receipts_root(receipts: &[FuelCoreReceipt]) -> [u8; 32] {
+    let mut calculator = MerkleRootCalculator::new();
+    for receipt in receipts {
+        calculator.push(&receipt.to_bytes());
+    }
+    calculator.root()
+}
+
+/// Validates the roots of one block from its exported rows, with the
+/// transactions and receipts in the order they were written.
+pub fn validate_block(
+    block: &AvroBlock,
+    transactions: &[AvroTransaction],
+    receipts: &[AvroReceipt],
+) -> Result<RootsReport, RowDecodeError> {
+    let height = block
+        .height
+        .ok_or(RowDecodeError::Missing("AvroBlock.height"))?;
+    let mut report = RootsReport {
+        blocks: 1,
+        ..Default::default()
+    };
+
+    let stored = block
+        .transactions_root
+        .as_ref()
+        .ok_or(RowDecodeError::Missing("AvroBlock.transactionsRoot"))?;
+    let payloads = transactions
+        .iter()
+        .map(|tx| {
+            tx.raw_payload
+                .as_ref()
+                .map(|payload| payload.0.as_slice())
+                .ok_or(RowDecodeError::Missing("AvroTransaction.rawPayload"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    report.compare(
+        RootKind::Transactions,
+        height,
+        None,
+        stored,
+        transactions_root(payloads),
+    );
+
+    let mut by_tx = HashMap::<&[u8], Vec<&AvroReceipt>>::new();
+    for receipt in receipts {
+        let tx_id = receipt
+            .transaction_id
+            .as_ref()
+            .ok_or(RowDecodeError::Missing("AvroReceipt.transactionId"))?;
+        by_tx.entry(tx_id.0.as_slice()).or_default().push(receipt);
+    }
+
+    // Only script transactions commit to their receipts
+    for tx in transactions {
+        let Some(stored) = &tx.receipts_root else {
+            continue;
+        };
+        let tx_id = tx
+            .id
+            .as_ref()
+            .ok_or(RowDecodeError::Missing("AvroTransaction.id"))?;
+        let rows = by_tx.remove(tx_id.0.as_slice()).unwrap_or_default();
+
+        let without_pc = rows.iter().any(|row| {
+            row.pc.is_none()
+                && row
+                    .receipt_type
+                    .as_deref()
+                    .is_some_and(|kind| !RECEIPTS_WITHOUT_PC.contains(&kind))
+        });
+        if without_pc {
+            report.skipped += 1;
+            continue;
+        }
+
+        let receipts = rows
+            .into_iter()
+            .map(|row| core_receipt(Receipt::try_from(row.clone())?))
+            .collect::<Result<Vec<_>, _>>()?;
+        report.receipts_roots += 1;
+        report.compare(
+            RootKind::Receipts,
+            height,
+            Some(tx_id),
+            stored,
+            receipts_root(&receipts),
+        );
+    }
+
+    Ok(report)
+}
+
+/// Validates the roots of a block from the rows the exporter writes for it
+pub fn validate_export(
+    block: &Block,
+    transactions: &[Transaction],
+) -> Result<RootsReport, RowDecodeError> {
+    let transaction_rows: Vec<_> = transactions
+        .iter()
+        .map(|tx| AvroTransaction::from((block, tx)))
+        .collect();
+    let receipt_rows: Vec<_> = transactions
+        .iter()
+        .flat_map(|tx| {
+            tx.receipts
+                .iter()
+                .map(move |receipt| AvroReceipt::from((block, tx, receipt)))
+        })
+        .collect();
+    validate_block(&AvroBlock::new(block), &transaction_rows, &receipt_rows)
+}
+
+/// Converts a receipt back to the fuel-core type whose canonical bytes are
+/// committed by `receipts_root`
+fn core_receipt(receipt: Receipt) -> Result<FuelCoreReceipt, RowDecodeError> {
+    Ok(match receipt {
+        Receipt::Call(r) => FuelCoreReceipt::Call {
+            id: r.id.into_inner(),
+            to: r.to.into_inner(),
+            amount: r.amount.0,
+            asset_id: r.asset_id.into_inner(),
+            gas: r.gas.0,
+            param1: r.param1.0,
+            param2: r.param2.0,
+            pc: r.pc.0,
+            is: r.is.0,
+        },
+        Receipt::Return(r) => FuelCoreReceipt::Return {
+            id: r.id.into_inner(),
+            val: r.val.0,
+            pc: r.pc.0,
+            is: r.is.0,
+        },
+        Receipt::ReturnData(r) => FuelCoreReceipt::ReturnData {
+            id: r.id.into_inner(),
+            ptr: r.ptr.0,
+            len: r.len.0,
+            digest: r.digest.into_inner(),
+            pc: r.pc.0,
+            is: r.is.0,
+            data: r.data.map(|data| data.0.0.into()),
+        },
+        Receipt::Panic(r) => FuelCoreReceipt::Panic {
+            id: r.id.into_inner(),
+            reason: panic_instruction(&r.reason),
+            pc: r.pc.0,
+            is: r.is.0,
+            contract_id: r.contract_id.map(|id| id.into_inner()),
+        },
+        Receipt::Revert(r) => FuelCoreReceipt::Revert {
+            id: r.id.into_inner(),
+            ra: r.ra.0,
+            pc: r.pc.0,
+            is: r.is.0,
+        },
+        Receipt::Log(r) => FuelCoreReceipt::Log {
+            id: r.id.into_inner(),
+            ra: r.ra.0,
+            rb: r.rb.0,
+            rc: r.rc.0,
+            rd: r.rd.0,
+            pc: r.pc.0,
+            is: r.is.0,
+        },
+        Receipt::LogData(r) => FuelCoreReceipt::LogData {
+            id: r.id.into_inner(),
+            ra: r.ra.0,
+            rb: r.rb.0,
+            ptr: r.ptr.0,
+            len: r.len.0,
+            digest: r.digest.into_inner(),
+            pc: r.pc.0,
+            is: r.is.0,
+            data: r.data.map(|data| data.0.0.into()),
+        },
+        Receipt::Transfer(r) => FuelCoreReceipt::Transfer {
+            id: r.id.into_inner(),
+            to: r.to.into_inner(),
+            amount: r.amount.0,
+            asset_id: r.asset_id.into_inner(),
+            pc: r.pc.0,
+            is: r.is.0,
+        },
+        Receipt::TransferOut(r) => FuelCoreReceipt::TransferOut {
+            id: r.id.into_inner(),
+            to: r.to.into_inner(),
+            amount: r.amount.0,
+            asset_id: r.asset_id.into_inner(),
+            pc: r.pc.0,
+            is: r.is.0,
+        },
+        Receipt::ScriptResult(r) => FuelCoreReceipt::ScriptResult {
+            result: match r.result {
+                ScriptExecutionResult::Success => FuelCoreScriptExecutionResult::Success,
+                ScriptExecutionResult::Revert => FuelCoreScriptExecutionResult::Revert,
+                ScriptExecutionResult::Panic => FuelCoreScriptExecutionResult::Panic,
+                ScriptExecutionResult::GenericFailure(code) => {
+                    FuelCoreScriptExecutionResult::GenericFailure(code)
+                }
+                ScriptExecutionResult::Unknown => {
+                    return Err(RowDecodeError::invalid(
+                        "AvroReceipt.result",
+                        "unknown script result",
+                    ));
+                }
+            },
+            gas_used: r.gas_used.0,
+        },
+        Receipt::MessageOut(r) => FuelCoreReceipt::MessageOut {
+            sender: r.sender.into_inner(),
+            recipient: r.recipient.into_inner(),
+            amount: r.amount.0,
+            nonce: r.nonce.into_inner(),
+            len: r.len.0,
+            digest: r.digest.into_inner(),
+            data: r.data.map(|data| data.0.0.into()),
+        },
+        Receipt::Mint(r) => FuelCoreReceipt::Mint {
+            sub_id: SubAssetId::from(*r.sub_id.into_inner()),
+            contract_id: r.contract_id.into_inner(),
+            val: r.val.0,
+            pc: r.pc.0,
+            is: r.is.0,
+        },
+        Receipt::Burn(r) => FuelCoreReceipt::Burn {
+            sub_id: SubAssetId::from(*r.sub_id.into_inner()),
+            contract_id: r.contract_id.into_inner(),
+            val: r.val.0,
+            pc: r.pc.0,
+            is: r.is.0,
+        },
+    })
+}
+
+/// Packs the reason into the top byte and the instruction into the next four,
+/// the word layout fuel-asm reads a panic instruction from
+fn panic_instruction(reason: &PanicInstruction) -> FuelCorePanicInstruction {
+    let word = (u64::from(reason.reason) << 56) | (u64::from(reason.instruction) << 24);
+    FuelCorePanicInstruction::from(word)
+}
+
+/// Validates the roots of the stored blocks in `from..=to`, reading
+/// `chunk_size` blocks of every table at a time.
+pub async fn validate_range<S: Storage>(
+    reader: &DataLakeReader<S>,
+    from: u32,
+    to: u32,
+    chunk_size: u32,
+) -> DuneResult<RootsReport> {
+    if from > to {
+        return Err(DuneError::InvalidBlockRange {
+            start: from,
+            end: to,
+        });
+    }
+    let chunk_size = chunk_size.max(1);
+
+    // List each table once and pick the files of every chunk from it
+    let blocks = reader
+        .discover(S3TableName::Blocks, from.into(), to.into())
+        .await?;
+    let transactions = reader
+        .discover(S3TableName::Transactions, from.into(), to.into())
+        .await?;
+    let receipts = reader
+        .discover(S3TableName::Receipts, from.into(), to.into())
+        .await?;
+    let overlapping = |objects: &[RangeObject], start: u32, end: u32| {
+        objects
+            .iter()
+            .filter(|object| *object.start <= end && *object.end >= start)
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let mut report = RootsReport::default();
+    let mut start = from;
+    loop {
+        let end = start.saturating_add(chunk_size - 1).min(to);
+
+        let blocks: Vec<AvroBlock> = reader
+            .read_rows(overlapping(&blocks, start, end), start.into(), end.into())
+            .try_collect()
+            .await?;
+        let mut transactions = group_by_height(
+            reader
+                .read_rows::<AvroTransaction>(
+                    overlapping(&transactions, start, end),
+                    start.into(),
+                    end.into(),
+                )
+                .try_collect()
+                .await?,
+            |tx| tx.block_height,
+        );
+        let mut receipts = group_by_height(
+            reader
+                .read_rows::<AvroReceipt>(
+                    overlapping(&receipts, start, end),
+                    start.into(),
+                    end.into(),
+                )
+                .try_collect()
+                .await?,
+            |receipt| receipt.block_height,
+        );
+
+        for block in &blocks {
+            let height = block.height.unwrap_or_default();
+            let transactions = transactions.remove(&height).unwrap_or_default();
+            let receipts = receipts.remove(&height).unwrap_or_default();
+            report.merge(validate_block(block, &transactions, &receipts)?);
+        }
+
+        if end == to {
+            break;
+        }
+        start = end + 1;
+    }
+
+    Ok(report)
+}
+
+fn group_by_height<T>(
+    rows: Vec<T>,
+    height: impl Fn(&T) -> Option<i64>,
+) -> HashMap<i64, Vec<T>> {
+    let mut groups = HashMap::<i64, Vec<T>>::new();
+    for row in rows {
+        groups
+            .entry(height(&row).unwrap_or_default())
+            .or_default()
+            .push(row);
+    }
+    groups
+}
+
+/// Entry point of the `validate` command
+pub async fn run(args: ValidateArgs) -> anyhow::Result<()> {
+    let storage = Arc::new(S3Storage::new(S3StorageOpts::admin_opts()).await?);
+    let reader = DataLakeReader::new(storage, FuelNetwork::load_from_env());
+
+    tracing::info!(
+        "Validating the roots of blocks {} to {}",
+        args.from,
+        args.to
+    );
+    let report = validate_range(&reader, args.from, args.to, args.chunk_size).await?;
+
+    for mismatch in &report.mismatches {
+        println!("{mismatch}");
+    }
+    println!("{report}");
+
+    if !report.is_clean() {
+        anyhow::bail!("Found {} root mismatches", report.mismatches.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_core_types::fuel_crypto::Hasher;
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockReceipt,
+        MockTransaction,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Rows of a block whose stored roots match its transactions and receipts
+    fn consistent_rows() -> (AvroBlock, Vec<AvroTransaction>, Vec<AvroReceipt>) {
+        let block = MockBlock::build(7.into());
+        let txs = [
+            MockTransaction::script(vec![], vec![], MockReceipt::all()),
+            MockTransaction::script(vec![], vec![], MockReceipt::all()),
+        ];
+
+        let mut block_row = AvroBlock::new(&block);
+        let mut tx_rows: Vec<_> = txs
+            .iter()
+            .map(|tx| AvroTransaction::from((&block, tx)))
+            .collect();
+        let receipt_rows: Vec<_> = txs
+            .iter()
+            .flat_map(|tx| {
+                tx.receipts
+                    .iter()
+                    .map(|receipt| AvroReceipt::from((&block, tx, receipt)))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        for (tx, row) in txs.iter().zip(&mut tx_rows) {
+            let receipts: Vec<_> = tx
+                .receipts
+                .iter()
+                .map(|receipt| core_receipt(receipt.clone()).unwrap())
+                .collect();
+            row.receipts_root = Some(receipts_root(&receipts).to_vec().into());
+        }
+        let payloads = tx_rows
+            .iter()
+            .map(|tx| tx.raw_payload.as_ref().unwrap().0.as_slice());
+        block_row.transactions_root = Some(transactions_root(payloads).to_vec().into());
+
+        (block_row, tx_rows, receipt_rows)
+    }
+
+    #[test]
+    fn test_roots_follow_the_binary_merkle_tree() {
+        // Empty tree and single leaf of the Fuel binary Merkle tree
+        assert_eq!(transactions_root(std::iter::empty()), *Hasher::hash([]));
+        let payload = [1u8, 2, 3];
+        let leaf = Hasher::default().chain([0u8]).chain(payload).finalize();
+        assert_eq!(transactions_root([payload.as_slice()]), *leaf);
+    }
+
+    #[test]
+    fn test_core_receipts_round_trip() {
+        for receipt in MockReceipt::all() {
+            let core = core_receipt(receipt.clone()).unwrap();
+            assert_eq!(Receipt::from(core), receipt);
+        }
+    }
+
+    #[test]
+    fn test_consistent_block() {
+        let (block, transactions, receipts) = consistent_rows();
+        let report = validate_block(&block, &transactions, &receipts).unwrap();
+        assert!(report.is_clean(), "{:?}", report.mismatches);
+        assert_eq!(report.blocks, 1);
+        assert_eq!(report.receipts_roots, 2);
+        assert_eq!(report.skipped, 0);
+    }
+
+    #[test]
+    fn test_tampered_rows_are_flagged() {
+        let (block, mut transactions, mut receipts) = consistent_rows();
+        transactions.swap(0, 1);
+        let last = receipts
+            .iter()
+            .rposition(|receipt| receipt.receipt_type.as_deref() == Some("call"))
+            .unwrap();
+        let tampered = &mut receipts[last];
+        tampered.pc = tampered.pc.map(|pc| pc + 4);
+        tampered.pc_u64 = tampered.pc.map(|pc| pc.to_string());
+
+        let report = validate_block(&block, &transactions, &receipts).unwrap();
+        let kinds: Vec<_> = report
+            .mismatches
+            .iter()
+            .map(|mismatch| (mismatch.kind, mismatch.tx_id.clone()))
+            .collect();
+        let tampered_tx = receipts[last].transaction_id.as_ref().map(|id| hex(&id.0));
+        assert_eq!(
+            kinds,
+            vec![
+                (RootKind::Transactions, None),
+                (RootKind::Receipts, tampered_tx)
+            ]
+        );
+        assert!(
+            report
+                .mismatches
+                .iter()
+                .all(|mismatch| mismatch.height == 7)
+        );
+    }
+
+    #[test]
+    fn test_receipts_without_pc_are_skipped() {
+        let (block, transactions, mut receipts) = consistent_rows();
+        let tx_id = transactions[0].id.clone();
+        for receipt in &mut receipts {
+            if receipt.transaction_id == tx_id {
+                receipt.pc = None;
+                receipt.pc_u64 = None;
+            }
+        }
+
+        let report = validate_block(&block, &transactions, &receipts).unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.receipts_roots, 1);
+        assert_eq!(report.skipped, 1);
+    }
+}
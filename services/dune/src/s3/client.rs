@@ -21,7 +21,17 @@ use aws_sdk_s3::{
     operation::get_object::GetObjectError,
     primitives::ByteStream,
 };
-use std::path::Path;
+use std::{
+    io::Write,
+    path::Path,
+};
+
+/// Key and size of a listed object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredObject {
+    pub key: String,
+    pub size: u64,
+}
 
 #[derive(Debug, Clone)]
 pub struct S3Storage {
@@ -158,6 +168,95 @@ impl Storage for S3Storage {
         })
         .await
     }
+
+    /// Follows continuation tokens until every object is listed
+    async fn list_objects(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<StoredObject>, StorageError> {
+        let mut objects = Vec::new();
+        let mut continuation_token = None;
+
+        loop {
+            let mut list_request = self
+                .client
+                .list_objects_v2()
+                .bucket(self.config.bucket())
+                .prefix(prefix);
+
+            if let Some(token) = continuation_token {
+                list_request = list_request.continuation_token(token);
+            }
+
+            let response = list_request
+                .send()
+                .await
+                .map_err(|e| StorageError::ListError(e.to_string()))?;
+
+            objects.extend(response.contents().iter().filter_map(|object| {
+                object.key().map(|key| StoredObject {
+                    key: key.to_string(),
+                    size: object.size().unwrap_or_default().max(0) as u64,
+                })
+            }));
+
+            if response.is_truncated().unwrap_or_default() {
+                continuation_token = response.next_continuation_token().map(String::from);
+            } else {
+                break;
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Streams the body to disk chunk by chunk, so large range files are
+    /// never held in memory
+    async fn retrieve_to_file(
+        &self,
+        key: &str,
+        path: &Path,
+    ) -> Result<bool, StorageError> {
+        with_retry(&self.retry_config, "retrieve_to_file", || async {
+            let result = self
+                .client
+                .get_object()
+                .bucket(self.config.bucket())
+                .key(key)
+                .send()
+                .await;
+
+            if let Err(SdkError::ServiceError(err)) = &result
+                && matches!(err.err(), GetObjectError::NoSuchKey(_))
+            {
+                return Ok(false)
+            }
+
+            let mut body = result
+                .map_err(|e| StorageError::RetrieveError(e.to_string()))?
+                .body;
+            let written = async {
+                let mut file = std::fs::File::create(path)
+                    .map_err(|e| StorageError::RetrieveError(e.to_string()))?;
+                while let Some(chunk) = body
+                    .try_next()
+                    .await
+                    .map_err(|e| StorageError::RetrieveError(e.to_string()))?
+                {
+                    file.write_all(&chunk)
+                        .map_err(|e| StorageError::RetrieveError(e.to_string()))?;
+                }
+                Ok(())
+            }
+            .await;
+
+            if written.is_err() {
+                let _ = std::fs::remove_file(path);
+            }
+            written.map(|()| true)
+        })
+        .await
+    }
 }
 
 impl S3Storage {
@@ -327,6 +426,66 @@ impl S3Storage {
         Ok(())
     }
 
+    /// Lists every object key under `prefix`.
+    pub async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        Ok(self
+            .list_objects(prefix)
+            .await?
+            .into_iter()
+            .map(|object| object.key)
+            .collect())
+    }
+
+    /// Server-side copy of an object within the bucket.
+    pub async fn copy(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        with_retry(&self.retry_config, "copy", || async {
+            self.client
+                .copy_object()
+                .bucket(self.config.bucket())
+                .copy_source(format!("{}/{}", self.config.bucket(), from))
+                .key(to)
+                .send()
+                .await
+                .map_err(|e| StorageError::StoreError(e.to_string()))?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub fn bucket(&self) -> String {
+        self.config.bucket()
+    }
+
+    /// Stores an object only if nothing exists under `key` yet, returning
+    /// `false` when the key is already taken. Not retried, since a retry
+    /// after a lost response would report our own write as taken.
+    pub async fn store_if_absent(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+    ) -> Result<bool, StorageError> {
+        let result = self
+            .client
+            .put_object()
+            .bucket(self.config.bucket())
+            .key(key)
+            .if_none_match("*")
+            .body(data.into())
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            // 412 when the key exists, 409 on a concurrent conditional write
+            Err(SdkError::ServiceError(err))
+                if matches!(err.raw().status().as_u16(), 409 | 412) =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(StorageError::StoreError(err.to_string())),
+        }
+    }
+
     pub async fn delete_all_objects(&self) -> Result<(), StorageError> {
         let mut continuation_token = None;
 
@@ -681,6 +840,53 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_list_objects_and_copy() {
+        let storage = S3Storage::new_for_testing().await.unwrap();
+
+        let content = b"Hello, Storage!".to_vec();
+        storage.store("list/a", content.clone()).await.unwrap();
+        storage.copy("list/a", "list/b").await.unwrap();
+
+        let mut objects = storage.list_objects("list/").await.unwrap();
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(
+            objects,
+            vec![
+                StoredObject {
+                    key: "list/a".to_string(),
+                    size: content.len() as u64,
+                },
+                StoredObject {
+                    key: "list/b".to_string(),
+                    size: content.len() as u64,
+                },
+            ]
+        );
+        let copied = storage.retrieve("list/b").await.unwrap().unwrap();
+        assert_eq!(copied, content);
+    }
+
+    #[tokio::test]
+    async fn test_store_if_absent() {
+        let storage = S3Storage::new_for_testing().await.unwrap();
+
+        assert!(
+            storage
+                .store_if_absent("once", b"first".to_vec())
+                .await
+                .unwrap()
+        );
+        assert!(
+            !storage
+                .store_if_absent("once", b"second".to_vec())
+                .await
+                .unwrap()
+        );
+        let stored = storage.retrieve("once").await.unwrap().unwrap();
+        assert_eq!(stored, b"first".to_vec());
+    }
+
     #[tokio::test]
     #[traced_test]
     async fn test_non_existing_file() {
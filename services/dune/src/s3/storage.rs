@@ -1,8 +1,13 @@
+use std::path::Path;
+
 use async_trait::async_trait;
 use displaydoc::Display as DisplayDoc;
 use thiserror::Error;
 
-use super::StorageConfig;
+use super::{
+    StorageConfig,
+    StoredObject,
+};
 
 #[derive(Error, Debug, DisplayDoc)]
 pub enum StorageError {
@@ -45,4 +50,23 @@ pub trait Storage: std::fmt::Debug + Send + Sync {
     async fn retrieve(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError>;
 
     async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Lists every object under `prefix`.
+    async fn list_objects(&self, prefix: &str)
+    -> Result<Vec<StoredObject>, StorageError>;
+
+    /// Writes the object to `path`, returning `false` if it does not exist.
+    /// The default implementation holds the whole object in memory.
+    async fn retrieve_to_file(
+        &self,
+        key: &str,
+        path: &Path,
+    ) -> Result<bool, StorageError> {
+        let Some(data) = self.retrieve(key).await? else {
+            return Ok(false);
+        };
+        std::fs::write(path, data)
+            .map_err(|e| StorageError::RetrieveError(e.to_string()))?;
+        Ok(true)
+    }
 }
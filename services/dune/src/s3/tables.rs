@@ -3,7 +3,21 @@ use std::{
     sync::LazyLock,
 };
 
+use apache_avro::{
+    AvroSchema,
+    schema::derive::AvroSchemaComponent,
+};
+use chrono::NaiveDate;
 use fuel_streams_types::BlockHeight;
+use serde::{
+    Serialize,
+    de::DeserializeOwned,
+};
+
+use crate::{
+    helpers::FileFormat,
+    schemas::AvroRow,
+};
 
 pub static BUCKET_PREFIX: LazyLock<String> = LazyLock::new(|| {
     dotenvy::var("BUCKET_PREFIX")
@@ -38,24 +52,158 @@ impl FuelNetwork {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
-pub enum S3TableName {
+/// Every table written range by range, with the row type of its files.
+///
+/// This is the only list of tables: [`S3TableName`] with its names,
+/// [`S3TableName::ROW_TABLES`], the [`TableRow`] impls and every dispatch done
+/// with [`with_table_row!`] are generated from it, so adding a table is a
+/// single entry here.
+macro_rules! row_tables {
+    ([$($callback:tt)*] $($args:tt)*) => {
+        $($callback)*! {
+            ($($args)*)
+            Blocks => "blocks": $crate::schemas::AvroBlock,
+            Transactions => "transactions": $crate::schemas::AvroTransaction,
+            Receipts => "receipts": $crate::schemas::AvroReceipt,
+            Inputs => "inputs": $crate::schemas::AvroInput,
+            Outputs => "outputs": $crate::schemas::AvroOutput,
+            Utxos => "utxos": $crate::schemas::AvroUtxo,
+            Messages => "messages": $crate::schemas::AvroMessage,
+            Predicates => "predicates": $crate::schemas::AvroPredicate,
+            PredicateBytecodes => "predicate_bytecodes": $crate::schemas::AvroPredicateBytecode,
+            Contracts => "contracts": $crate::schemas::AvroContract,
+            Assets => "assets": $crate::schemas::AvroAssetEvent,
+            AssetSupplies => "asset_supplies": $crate::schemas::AvroAssetSupply,
+            Transfers => "transfers": $crate::schemas::AvroTransfer,
+            DecodedLogs => "decoded_logs": $crate::schemas::AvroDecodedLog,
+            DecodedCalls => "decoded_calls": $crate::schemas::AvroDecodedCall,
+            BlockStats => "block_stats": $crate::schemas::AvroBlockStats,
+        }
+    };
+}
+pub(crate) use row_tables;
+
+/// Row type of the files of a table
+pub trait TableRow:
+    AvroRow
+    + AvroSchema
+    + AvroSchemaComponent
+    + Serialize
+    + DeserializeOwned
+    + Send
+    + Sync
+    + 'static
+{
+    const TABLE: S3TableName;
+}
+
+macro_rules! table_names {
+    (() $($variant:ident => $name:literal: $row:ty,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum S3TableName {
+            $($variant,)*
+            Metadata,
+        }
+
+        impl S3TableName {
+            /// Every table holding block rows, i.e. all but `Metadata`
+            pub const ROW_TABLES: &[S3TableName] = &[$(S3TableName::$variant,)*];
+        }
+
+        impl Display for S3TableName {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $(S3TableName::$variant => write!(f, $name),)*
+                    S3TableName::Metadata => write!(f, "metadata"),
+                }
+            }
+        }
+
+        impl std::str::FromStr for S3TableName {
+            type Err = anyhow::Error;
+
+            fn from_str(input: &str) -> Result<Self, Self::Err> {
+                match input {
+                    $($name => Ok(S3TableName::$variant),)*
+                    "metadata" => Ok(S3TableName::Metadata),
+                    _ => Err(anyhow::anyhow!("Unknown table name {input}")),
+                }
+            }
+        }
+
+        $(
+            impl TableRow for $row {
+                const TABLE: S3TableName = S3TableName::$variant;
+            }
+        )*
+    };
+}
+
+row_tables!([table_names]);
+
+impl Default for S3TableName {
+    /// The first table, `blocks`
+    fn default() -> Self {
+        S3TableName::ROW_TABLES[0]
+    }
+}
+
+macro_rules! table_row_match {
+    (($table:expr, $row:ident, $body:expr) $($variant:ident => $name:literal: $ty:ty,)*) => {
+        match $table {
+            $($crate::s3::S3TableName::$variant => Some({
+                type $row = $ty;
+                $body
+            }),)*
+            $crate::s3::S3TableName::Metadata => None,
+        }
+    };
+}
+pub(crate) use table_row_match;
+
+/// Evaluates `body` with `Row` aliased to the row type of `table`, returning
+/// `None` for the metadata table, which has no rows.
+///
+/// ```ignore
+/// let rows = with_table_row!(table, |Row| read::<Row>(table)?);
+/// ```
+macro_rules! with_table_row {
+    ($table:expr, |$row:ident| $body:expr) => {
+        $crate::s3::tables::row_tables!(
+            [$crate::s3::tables::table_row_match] $table, $row, $body
+        )
+    };
+}
+pub(crate) use with_table_row;
+
+/// How range files are laid out under a table prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyLayout {
+    /// `table/start-end.avro`
     #[default]
-    Blocks,
-    Transactions,
-    Receipts,
-    Metadata,
+    Flat,
+    /// Hive-style `table/date=YYYY-MM-DD/start-end.avro`, partitioned by the
+    /// UTC day of the block timestamps.
+    DatePartitioned,
 }
 
-impl Display for S3TableName {
+impl Display for KeyLayout {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            S3TableName::Blocks => write!(f, "blocks"),
-            S3TableName::Transactions => write!(f, "transactions"),
-            S3TableName::Receipts => write!(f, "receipts"),
-            S3TableName::Metadata => {
-                write!(f, "metadata")
-            }
+            KeyLayout::Flat => write!(f, "flat"),
+            KeyLayout::DatePartitioned => write!(f, "date"),
+        }
+    }
+}
+
+impl std::str::FromStr for KeyLayout {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "flat" => Ok(KeyLayout::Flat),
+            "date" => Ok(KeyLayout::DatePartitioned),
+            _ => Err(anyhow::anyhow!("Unknown key layout {input}")),
         }
     }
 }
@@ -63,6 +211,8 @@ impl Display for S3TableName {
 pub struct S3KeyBuilder {
     chain: FuelNetwork,
     table: S3TableName,
+    layout: KeyLayout,
+    format: FileFormat,
 }
 
 impl S3KeyBuilder {
@@ -70,6 +220,8 @@ impl S3KeyBuilder {
         Self {
             chain,
             table: S3TableName::default(),
+            layout: KeyLayout::default(),
+            format: FileFormat::default(),
         }
     }
 
@@ -83,6 +235,21 @@ impl S3KeyBuilder {
         self
     }
 
+    pub fn with_layout(mut self, layout: KeyLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn with_format(mut self, format: FileFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Prefix shared by every object of the table, with a trailing slash
+    pub fn table_prefix(&self) -> String {
+        format!("{}/{}/{}/", *BUCKET_PREFIX, self.chain, self.table)
+    }
+
     pub fn build_key(&self, filename: &str) -> String {
         format!(
             "{}/{}/{}/{}",
@@ -95,10 +262,126 @@ impl S3KeyBuilder {
         start_block: BlockHeight,
         end_block: BlockHeight,
     ) -> String {
-        let filename = format!("{:010}-{:010}.avro", start_block, end_block);
+        let filename = range_filename(start_block, end_block, self.format);
         format!(
             "{}/{}/{}/{}",
             *BUCKET_PREFIX, self.chain, self.table, filename
         )
     }
+
+    /// Builds the key of a range file according to the configured layout.
+    /// `date` is the UTC day of the blocks in the range and is only used by
+    /// the date-partitioned layout.
+    pub fn build_range_key(
+        &self,
+        start_block: BlockHeight,
+        end_block: BlockHeight,
+        date: NaiveDate,
+    ) -> String {
+        match self.layout {
+            KeyLayout::Flat => self.build_key_from_heights(start_block, end_block),
+            KeyLayout::DatePartitioned => {
+                let filename = range_filename(start_block, end_block, self.format);
+                format!(
+                    "{}/{}/{}/date={}/{}",
+                    *BUCKET_PREFIX,
+                    self.chain,
+                    self.table,
+                    date.format("%Y-%m-%d"),
+                    filename
+                )
+            }
+        }
+    }
+}
+
+/// File name of a range file covering `start_block..=end_block`
+pub fn range_filename(
+    start_block: BlockHeight,
+    end_block: BlockHeight,
+    format: FileFormat,
+) -> String {
+    format!(
+        "{:010}-{:010}.{}",
+        start_block,
+        end_block,
+        format.extension()
+    )
+}
+
+/// Extracts the height range from an Avro range file key of either layout.
+/// Parquet files are not matched, so the maintenance commands that rewrite
+/// Avro files leave them alone.
+pub fn parse_range_key(key: &str) -> Option<(BlockHeight, BlockHeight)> {
+    let filename = key.rsplit('/').next()?;
+    let (start, end) = filename.strip_suffix(".avro")?.split_once('-')?;
+    let start: u32 = start.parse().ok()?;
+    let end: u32 = end.parse().ok()?;
+    Some((start.into(), end.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_build_range_key_layouts() {
+        let date = NaiveDate::from_ymd_opt(2025, 4, 18).unwrap();
+        let builder =
+            S3KeyBuilder::new(FuelNetwork::Mainnet).with_table(S3TableName::Transactions);
+
+        assert_eq!(
+            builder.build_range_key(1.into(), 3600.into(), date),
+            format!("{}/mainnet/transactions/1-3600.avro", *BUCKET_PREFIX)
+        );
+
+        let builder = builder.with_layout(KeyLayout::DatePartitioned);
+        let key = builder.build_range_key(1.into(), 3600.into(), date);
+        assert_eq!(
+            key,
+            format!(
+                "{}/mainnet/transactions/date=2025-04-18/1-3600.avro",
+                *BUCKET_PREFIX
+            )
+        );
+        assert_eq!(parse_range_key(&key), Some((1.into(), 3600.into())));
+
+        let builder = builder.with_format(FileFormat::Parquet);
+        let key = builder.build_range_key(1.into(), 3600.into(), date);
+        assert_eq!(
+            key,
+            format!(
+                "{}/mainnet/transactions/date=2025-04-18/1-3600.parquet",
+                *BUCKET_PREFIX
+            )
+        );
+        assert_eq!(parse_range_key(&key), None);
+        assert_eq!(
+            parse_range_key("v1/mainnet/metadata/latest_block_height.txt"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_row_tables_registry() {
+        assert_eq!(S3TableName::ROW_TABLES.len(), 16);
+        assert_eq!(S3TableName::default(), S3TableName::Blocks);
+        for table in S3TableName::ROW_TABLES {
+            assert_eq!(table.to_string().parse::<S3TableName>().unwrap(), *table);
+            let row_table = with_table_row!(*table, |Row| Row::TABLE);
+            assert_eq!(row_table, Some(*table));
+        }
+        assert_eq!(
+            with_table_row!(S3TableName::Metadata, |Row| Row::TABLE),
+            None
+        );
+        assert_eq!(
+            "predicate_bytecodes".parse::<S3TableName>().unwrap(),
+            S3TableName::PredicateBytecodes
+        );
+        assert!("metadata".parse::<S3TableName>().is_ok());
+        assert!("unknown".parse::<S3TableName>().is_err());
+    }
 }
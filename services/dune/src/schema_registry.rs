@@ -0,0 +1,368 @@
+use apache_avro::{
+    Schema,
+    rabin::Rabin,
+    schema_compatibility::SchemaCompatibility,
+};
+
+use crate::{
+    DuneError,
+    DuneResult,
+    s3::{
+        FuelNetwork,
+        S3KeyBuilder,
+        S3Storage,
+        S3TableName,
+        Storage,
+        TableRow,
+        with_table_row,
+    },
+};
+
+/// Directory of the published schemas inside the `Metadata` table
+const SCHEMAS_DIR: &str = "schemas";
+
+/// Schema registry error types.
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaRegistryError {
+    #[error(
+        "{table} schema v{version} was already published with fingerprint {published}, \
+         but the current schema has fingerprint {candidate}; bump the schema version"
+    )]
+    FingerprintMismatch {
+        table: S3TableName,
+        version: u32,
+        published: String,
+        candidate: String,
+    },
+    #[error("{table} schema is incompatible with published v{version}: {reason}")]
+    Incompatible {
+        table: S3TableName,
+        version: u32,
+        reason: String,
+    },
+    #[error("Invalid published schema {key}: {reason}")]
+    InvalidPublished { key: String, reason: String },
+}
+
+/// Which readers must keep working when a schema changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompatibilityMode {
+    /// The candidate can read files written with every published version
+    #[default]
+    Backward,
+    /// Readers using a published version can read files written with the
+    /// candidate
+    Forward,
+    /// Both backward and forward
+    Full,
+}
+
+/// A schema version stored under the `Metadata` table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishedSchema {
+    pub version: u32,
+    pub schema: Schema,
+    pub fingerprint: String,
+}
+
+/// Result of publishing the schema of a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishOutcome {
+    /// The version was new and passed the compatibility checks
+    Published(u32),
+    /// The version was already published with the same fingerprint
+    Unchanged(u32),
+}
+
+/// CRC-64-AVRO (Rabin) fingerprint of the Parsing Canonical Form, in hex
+pub fn canonical_fingerprint(schema: &Schema) -> String {
+    schema.fingerprint::<Rabin>().to_string()
+}
+
+/// Checks `candidate` against every published version using the Avro schema
+/// resolution rules.
+pub fn check_compatibility(
+    table: S3TableName,
+    candidate: &Schema,
+    published: &[PublishedSchema],
+    mode: CompatibilityMode,
+) -> Result<(), SchemaRegistryError> {
+    for existing in published {
+        let incompatible = |reason: String| SchemaRegistryError::Incompatible {
+            table,
+            version: existing.version,
+            reason,
+        };
+        if matches!(mode, CompatibilityMode::Backward | CompatibilityMode::Full) {
+            SchemaCompatibility::can_read(&existing.schema, candidate)
+                .map_err(|e| incompatible(format!("cannot read old files: {e}")))?;
+        }
+        if matches!(mode, CompatibilityMode::Forward | CompatibilityMode::Full) {
+            SchemaCompatibility::can_read(candidate, &existing.schema)
+                .map_err(|e| incompatible(format!("old readers cannot read: {e}")))?;
+        }
+    }
+    Ok(())
+}
+
+/// Publishes the table schemas as `.avsc` files with their fingerprints,
+/// under `metadata/schemas/{table}/v{version}.avsc`.
+pub struct SchemaRegistry<'a> {
+    storage: &'a S3Storage,
+    keys: S3KeyBuilder,
+    mode: CompatibilityMode,
+}
+
+impl<'a> SchemaRegistry<'a> {
+    pub fn new(storage: &'a S3Storage, network: FuelNetwork) -> Self {
+        Self {
+            storage,
+            keys: S3KeyBuilder::new(network).with_table(S3TableName::Metadata),
+            mode: CompatibilityMode::default(),
+        }
+    }
+
+    pub fn with_mode(mut self, mode: CompatibilityMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn table_dir(&self, table: S3TableName) -> String {
+        self.keys.build_key(&format!("{SCHEMAS_DIR}/{table}/"))
+    }
+
+    fn schema_key(&self, table: S3TableName, version: u32) -> String {
+        format!("{}v{version}.avsc", self.table_dir(table))
+    }
+
+    fn fingerprint_key(&self, table: S3TableName, version: u32) -> String {
+        format!("{}v{version}.fingerprint", self.table_dir(table))
+    }
+
+    /// Lists the published versions of a table, oldest first
+    pub async fn published(
+        &self,
+        table: S3TableName,
+    ) -> DuneResult<Vec<PublishedSchema>> {
+        let dir = self.table_dir(table);
+        let mut versions: Vec<u32> = self
+            .storage
+            .list_keys(&dir)
+            .await?
+            .iter()
+            .filter_map(|key| key[dir.len()..].strip_prefix('v'))
+            .filter_map(|name| name.strip_suffix(".avsc"))
+            .filter_map(|version| version.parse::<u32>().ok())
+            .collect();
+        versions.sort_unstable();
+
+        let mut published = Vec::with_capacity(versions.len());
+        for version in versions {
+            let key = self.schema_key(table, version);
+            let invalid = |reason: String| SchemaRegistryError::InvalidPublished {
+                key: key.clone(),
+                reason,
+            };
+            let data = self
+                .storage
+                .retrieve(&key)
+                .await?
+                .ok_or_else(|| invalid("disappeared while listing".to_string()))?;
+            let json = String::from_utf8(data).map_err(|e| invalid(e.to_string()))?;
+            let schema = Schema::parse_str(&json).map_err(|e| invalid(e.to_string()))?;
+            published.push(PublishedSchema {
+                version,
+                fingerprint: canonical_fingerprint(&schema),
+                schema,
+            });
+        }
+        Ok(published)
+    }
+
+    /// Publishes `schema` as `version` of `table`. A version that is already
+    /// published must have the same fingerprint, and a new version must be
+    /// compatible with every published one.
+    pub async fn publish_schema(
+        &self,
+        table: S3TableName,
+        version: u32,
+        schema: &Schema,
+    ) -> DuneResult<PublishOutcome> {
+        let fingerprint = canonical_fingerprint(schema);
+        let published = self.published(table).await?;
+
+        if let Some(existing) = published.iter().find(|p| p.version == version) {
+            if existing.fingerprint != fingerprint {
+                return Err(SchemaRegistryError::FingerprintMismatch {
+                    table,
+                    version,
+                    published: existing.fingerprint.clone(),
+                    candidate: fingerprint,
+                }
+                .into());
+            }
+            return Ok(PublishOutcome::Unchanged(version));
+        }
+
+        check_compatibility(table, schema, &published, self.mode)?;
+
+        let avsc = serde_json::to_vec_pretty(schema)
+            .map_err(|e| DuneError::Other(anyhow::anyhow!(e)))?;
+        self.storage
+            .store(&self.schema_key(table, version), avsc)
+            .await?;
+        self.storage
+            .store(
+                &self.fingerprint_key(table, version),
+                fingerprint.clone().into_bytes(),
+            )
+            .await?;
+        tracing::info!("Published {table} schema v{version} ({fingerprint})");
+
+        Ok(PublishOutcome::Published(version))
+    }
+
+    /// Publishes the schema of row type `T` at its `SCHEMA_VERSION`
+    pub async fn publish<T: TableRow>(&self) -> DuneResult<PublishOutcome> {
+        self.publish_schema(T::TABLE, T::SCHEMA_VERSION, &T::get_schema())
+            .await
+    }
+
+    /// Publishes the schemas of every exported table
+    pub async fn publish_all(&self) -> DuneResult<()> {
+        for &table in S3TableName::ROW_TABLES {
+            with_table_row!(table, |Row| self.publish::<Row>().await?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use pretty_assertions::{
+        assert_eq,
+        assert_ne,
+    };
+
+    use super::*;
+    use crate::schemas::{
+        AvroBlock,
+        AvroReceipt,
+    };
+
+    fn record(fields: &str) -> Schema {
+        Schema::parse_str(&format!(
+            r#"{{"type": "record", "name": "Row", "fields": [{fields}]}}"#
+        ))
+        .unwrap()
+    }
+
+    fn published(schema: Schema) -> Vec<PublishedSchema> {
+        vec![PublishedSchema {
+            version: 1,
+            fingerprint: canonical_fingerprint(&schema),
+            schema,
+        }]
+    }
+
+    #[test]
+    fn test_canonical_fingerprint() {
+        let schema = AvroBlock::get_schema();
+        let canonical = Schema::parse_str(&schema.canonical_form()).unwrap();
+        assert_eq!(
+            canonical_fingerprint(&schema),
+            canonical_fingerprint(&canonical)
+        );
+        assert_eq!(canonical_fingerprint(&schema).len(), 16);
+        assert_ne!(
+            canonical_fingerprint(&schema),
+            canonical_fingerprint(&AvroReceipt::get_schema())
+        );
+    }
+
+    #[test]
+    fn test_check_compatibility() {
+        let table = S3TableName::Blocks;
+        let v1 = published(record(r#"{"name": "height", "type": ["null", "long"]}"#));
+
+        // New optional field with a default: old files resolve to the default
+        let added = record(
+            r#"{"name": "height", "type": ["null", "long"]},
+               {"name": "size", "type": ["null", "long"], "default": null}"#,
+        );
+        assert!(check_compatibility(table, &added, &v1, CompatibilityMode::Full).is_ok());
+
+        // New field without a default cannot be read from old files
+        let required = record(
+            r#"{"name": "height", "type": ["null", "long"]},
+               {"name": "size", "type": "long"}"#,
+        );
+        assert!(
+            check_compatibility(table, &required, &v1, CompatibilityMode::Backward)
+                .is_err()
+        );
+        assert!(
+            check_compatibility(table, &required, &v1, CompatibilityMode::Forward)
+                .is_ok()
+        );
+
+        // Changing a type breaks both directions
+        let retyped = record(r#"{"name": "height", "type": ["null", "string"]}"#);
+        let err = check_compatibility(table, &retyped, &v1, CompatibilityMode::Backward)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SchemaRegistryError::Incompatible { version: 1, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_publish_schema_versions() -> DuneResult<()> {
+        let storage = S3Storage::new_for_testing().await?;
+        let registry = SchemaRegistry::new(&storage, FuelNetwork::Local);
+        let table = S3TableName::Receipts;
+        let v1 = record(r#"{"name": "height", "type": ["null", "long"]}"#);
+
+        assert_eq!(
+            registry.publish_schema(table, 1, &v1).await?,
+            PublishOutcome::Published(1)
+        );
+        assert_eq!(
+            registry.publish_schema(table, 1, &v1).await?,
+            PublishOutcome::Unchanged(1)
+        );
+
+        // Changing a published version requires a version bump
+        let v2 = record(
+            r#"{"name": "height", "type": ["null", "long"]},
+               {"name": "size", "type": ["null", "long"], "default": null}"#,
+        );
+        assert!(registry.publish_schema(table, 1, &v2).await.is_err());
+        assert_eq!(
+            registry.publish_schema(table, 2, &v2).await?,
+            PublishOutcome::Published(2)
+        );
+
+        let incompatible = record(r#"{"name": "height", "type": "string"}"#);
+        assert!(
+            registry
+                .publish_schema(table, 3, &incompatible)
+                .await
+                .is_err()
+        );
+
+        let published = registry.published(table).await?;
+        assert_eq!(
+            published.iter().map(|p| p.version).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let fingerprint = storage
+            .retrieve(&registry.fingerprint_key(table, 2))
+            .await?
+            .unwrap();
+        assert_eq!(fingerprint, canonical_fingerprint(&v2).into_bytes());
+
+        Ok(())
+    }
+}
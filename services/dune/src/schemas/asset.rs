@@ -0,0 +1,195 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    assets::AssetEvent,
+    blocks::Block,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    assets::AssetSupply,
+    helpers::AvroBytes,
+};
+
+/// One row of the `assets` ledger: a mint or burn of a native asset
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroAssetEvent {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    /// Position of the `Mint` or `Burn` receipt in its transaction
+    #[avro(rename = "receiptIndex")]
+    pub receipt_index: Option<i64>,
+    /// `mint` or `burn`
+    #[avro(rename = "eventType")]
+    pub event_type: Option<String>,
+    /// SHA-256 of `contractId` followed by `subId`
+    #[avro(rename = "assetId")]
+    pub asset_id: Option<AvroBytes>,
+    #[avro(rename = "contractId")]
+    pub contract_id: Option<AvroBytes>,
+    #[avro(rename = "subId")]
+    pub sub_id: Option<AvroBytes>,
+    /// Change of the supply, negative for burns. Wraps above `i64::MAX`.
+    pub amount: Option<i64>,
+    /// Exact decimal value of `amount`
+    #[avro(rename = "amountDecimal")]
+    pub amount_decimal: Option<String>,
+}
+
+impl AvroAssetEvent {
+    /// Rows of the mints and burns of the transactions of `block`
+    pub fn rows(block: &Block, events: &[AssetEvent]) -> Vec<Self> {
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        events
+            .iter()
+            .map(|event| Self {
+                block_height: Some(block.height.0 as i64),
+                block_time: Some(block_time),
+                ..Self::new(event)
+            })
+            .collect()
+    }
+
+    /// Row of a single event, without its block coordinates
+    pub fn new(event: &AssetEvent) -> Self {
+        Self {
+            block_height: None,
+            block_time: None,
+            transaction_id: Some(event.tx_id.clone().into()),
+            tx_index: Some(event.tx_index as i64),
+            receipt_index: Some(event.receipt_index as i64),
+            event_type: Some(event.r#type.to_string()),
+            asset_id: Some(event.asset_id.clone().into()),
+            contract_id: Some(event.contract_id.clone().into()),
+            sub_id: Some(event.sub_id.clone().into()),
+            amount: Some(event.signed_amount() as i64),
+            amount_decimal: Some(event.signed_amount().to_string()),
+        }
+    }
+}
+
+/// One row of the `asset_supplies` dimension: the cumulative supply of an
+/// asset as of the last block of a batch that minted or burned it
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroAssetSupply {
+    /// Last block of the batch
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "assetId")]
+    pub asset_id: Option<AvroBytes>,
+    #[avro(rename = "contractId")]
+    pub contract_id: Option<AvroBytes>,
+    #[avro(rename = "subId")]
+    pub sub_id: Option<AvroBytes>,
+    /// Null when the exporter has only seen burns of the asset
+    #[avro(rename = "firstMintHeight")]
+    pub first_mint_height: Option<i64>,
+    /// Minted minus burned amount. Wraps outside the `i64` range.
+    pub supply: Option<i64>,
+    /// Exact decimal value of `supply`
+    #[avro(rename = "supplyDecimal")]
+    pub supply_decimal: Option<String>,
+}
+
+impl AvroAssetSupply {
+    /// Row of `supply` as of the block at `block_height` and `block_time`
+    pub fn new(block_height: i64, block_time: i64, supply: &AssetSupply) -> Self {
+        Self {
+            block_height: Some(block_height),
+            block_time: Some(block_time),
+            asset_id: Some(supply.asset_id.clone().into()),
+            contract_id: Some(supply.contract_id.clone().into()),
+            sub_id: Some(supply.sub_id.clone().into()),
+            first_mint_height: supply.first_mint_height.map(i64::from),
+            supply: Some(supply.supply as i64),
+            supply_decimal: Some(supply.supply.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockReceipt,
+        MockTransaction,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        assets::AssetSupplies,
+        helpers::{
+            AvroParser,
+            write_schema_files,
+        },
+    };
+
+    #[test]
+    fn test_avro_asset_event_rows() {
+        let block = MockBlock::random();
+        let receipts = vec![MockReceipt::mint(), MockReceipt::burn()];
+        let tx = MockTransaction::script(vec![], vec![], receipts);
+        let events = AssetEvent::from_transaction(1, &tx);
+        let rows = AvroAssetEvent::rows(&block, &events);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].event_type.as_deref(), Some("mint"));
+        assert_eq!(rows[0].amount_decimal.as_deref(), Some("100"));
+        assert_eq!(rows[1].event_type.as_deref(), Some("burn"));
+        assert_eq!(rows[1].amount, Some(-100));
+        assert_eq!(rows[1].amount_decimal.as_deref(), Some("-100"));
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroAssetEvent>().unwrap();
+        for row in &rows {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            assert_eq!(row.transaction_id, Some(tx.id.clone().into()));
+            writer.append(row).unwrap();
+        }
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroAssetEvent>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+    }
+
+    #[test]
+    fn test_avro_asset_supply() {
+        let tx = MockTransaction::script(vec![], vec![], vec![MockReceipt::mint()]);
+        let events = AssetEvent::from_transaction(0, &tx);
+        let mut supplies = AssetSupplies::default();
+        supplies.apply(5u32.into(), &events);
+
+        let supply = supplies.get(&events[0].asset_id).unwrap();
+        let row = AvroAssetSupply::new(9, 1_700_000_000, supply);
+        assert_eq!(row.block_height, Some(9));
+        assert_eq!(row.first_mint_height, Some(5));
+        assert_eq!(row.supply_decimal.as_deref(), Some("100"));
+    }
+
+    #[tokio::test]
+    async fn write_asset_schemas() {
+        let schemas = [
+            ("asset_event.json", AvroAssetEvent::get_schema()),
+            ("asset_supply.json", AvroAssetSupply::get_schema()),
+        ];
+
+        write_schema_files(&schemas).await;
+    }
+}
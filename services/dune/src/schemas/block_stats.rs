@@ -0,0 +1,187 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    blocks::{
+        Block,
+        BlockStats,
+    },
+    transactions::Transaction,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// One row of the `block_stats` table: aggregates of the transactions and
+/// receipts of a block
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroBlockStats {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "createTransactions")]
+    pub create_transactions: Option<i64>,
+    #[avro(rename = "mintTransactions")]
+    pub mint_transactions: Option<i64>,
+    #[avro(rename = "scriptTransactions")]
+    pub script_transactions: Option<i64>,
+    #[avro(rename = "upgradeTransactions")]
+    pub upgrade_transactions: Option<i64>,
+    #[avro(rename = "uploadTransactions")]
+    pub upload_transactions: Option<i64>,
+    #[avro(rename = "blobTransactions")]
+    pub blob_transactions: Option<i64>,
+    #[avro(rename = "successfulTransactions")]
+    pub successful_transactions: Option<i64>,
+    #[avro(rename = "failedTransactions")]
+    pub failed_transactions: Option<i64>,
+    /// Sum of the `totalGas` of the transactions
+    #[avro(rename = "totalGas")]
+    pub total_gas: Option<i64>,
+    #[avro(rename = "totalGasU64")]
+    pub total_gas_u64: Option<String>,
+    /// Sum of the `gasUsed` of the transactions
+    #[avro(rename = "gasUsed")]
+    pub gas_used: Option<i64>,
+    #[avro(rename = "gasUsedU64")]
+    pub gas_used_u64: Option<String>,
+    #[avro(rename = "totalFee")]
+    pub total_fee: Option<i64>,
+    #[avro(rename = "totalFeeU64")]
+    pub total_fee_u64: Option<String>,
+    #[avro(rename = "totalTip")]
+    pub total_tip: Option<i64>,
+    #[avro(rename = "totalTipU64")]
+    pub total_tip_u64: Option<String>,
+    /// Distinct owners of the coin and message inputs
+    #[avro(rename = "uniqueSenders")]
+    pub unique_senders: Option<i64>,
+    #[avro(rename = "callReceipts")]
+    pub call_receipts: Option<i64>,
+    #[avro(rename = "returnReceipts")]
+    pub return_receipts: Option<i64>,
+    #[avro(rename = "returnDataReceipts")]
+    pub return_data_receipts: Option<i64>,
+    #[avro(rename = "panicReceipts")]
+    pub panic_receipts: Option<i64>,
+    #[avro(rename = "revertReceipts")]
+    pub revert_receipts: Option<i64>,
+    #[avro(rename = "logReceipts")]
+    pub log_receipts: Option<i64>,
+    #[avro(rename = "logDataReceipts")]
+    pub log_data_receipts: Option<i64>,
+    #[avro(rename = "transferReceipts")]
+    pub transfer_receipts: Option<i64>,
+    #[avro(rename = "transferOutReceipts")]
+    pub transfer_out_receipts: Option<i64>,
+    #[avro(rename = "scriptResultReceipts")]
+    pub script_result_receipts: Option<i64>,
+    #[avro(rename = "messageOutReceipts")]
+    pub message_out_receipts: Option<i64>,
+    #[avro(rename = "mintReceipts")]
+    pub mint_receipts: Option<i64>,
+    #[avro(rename = "burnReceipts")]
+    pub burn_receipts: Option<i64>,
+}
+
+impl AvroBlockStats {
+    /// Row of `block`, whose transactions are `transactions`
+    pub fn new(block: &Block, transactions: &[Transaction]) -> Self {
+        let stats = BlockStats::from_transactions(transactions);
+        let count = |count: u64| Some(count as i64);
+        let txs = &stats.transactions;
+        let receipts = &stats.receipts;
+        Self {
+            block_height: Some(block.height.0 as i64),
+            block_time: Some(block.header.get_timestamp_utc().timestamp()),
+            create_transactions: count(txs.create),
+            mint_transactions: count(txs.mint),
+            script_transactions: count(txs.script),
+            upgrade_transactions: count(txs.upgrade),
+            upload_transactions: count(txs.upload),
+            blob_transactions: count(txs.blob),
+            successful_transactions: count(stats.successful_transactions),
+            failed_transactions: count(stats.failed_transactions),
+            total_gas: Some(stats.total_gas as i64),
+            total_gas_u64: Some(stats.total_gas.to_string()),
+            gas_used: Some(stats.gas_used as i64),
+            gas_used_u64: Some(stats.gas_used.to_string()),
+            total_fee: Some(stats.total_fee as i64),
+            total_fee_u64: Some(stats.total_fee.to_string()),
+            total_tip: Some(stats.total_tip as i64),
+            total_tip_u64: Some(stats.total_tip.to_string()),
+            unique_senders: count(stats.unique_senders),
+            call_receipts: count(receipts.call),
+            return_receipts: count(receipts.r#return),
+            return_data_receipts: count(receipts.return_data),
+            panic_receipts: count(receipts.panic),
+            revert_receipts: count(receipts.revert),
+            log_receipts: count(receipts.log),
+            log_data_receipts: count(receipts.log_data),
+            transfer_receipts: count(receipts.transfer),
+            transfer_out_receipts: count(receipts.transfer_out),
+            script_result_receipts: count(receipts.script_result),
+            message_out_receipts: count(receipts.message_out),
+            mint_receipts: count(receipts.mint),
+            burn_receipts: count(receipts.burn),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockTransaction,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::helpers::{
+        AvroParser,
+        write_schema_files,
+    };
+
+    #[test]
+    fn test_avro_block_stats() {
+        let block = MockBlock::random();
+        let transactions = MockTransaction::all();
+        let row = AvroBlockStats::new(&block, &transactions);
+        assert_eq!(row.block_height, Some(block.height.0 as i64));
+        assert_eq!(row.script_transactions, Some(1));
+        assert_eq!(row.blob_transactions, Some(1));
+        assert_eq!(row.successful_transactions, Some(6));
+        assert_eq!(row.failed_transactions, Some(0));
+        assert_eq!(row.call_receipts, Some(6));
+        assert_eq!(
+            row.total_fee_u64,
+            row.total_fee.map(|fee| (fee as u64).to_string())
+        );
+
+        let empty = AvroBlockStats::new(&block, &[]);
+        assert_eq!(empty.script_transactions, Some(0));
+        assert_eq!(empty.unique_senders, Some(0));
+        assert_eq!(empty.total_gas_u64.as_deref(), Some("0"));
+        assert_eq!(empty.gas_used_u64.as_deref(), Some("0"));
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroBlockStats>().unwrap();
+        writer.append(&row).unwrap();
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroBlockStats>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, vec![row]);
+    }
+
+    #[tokio::test]
+    async fn write_block_stats_schemas() {
+        let schemas = [("block_stats.json", AvroBlockStats::get_schema())];
+
+        write_schema_files(&schemas).await;
+    }
+}
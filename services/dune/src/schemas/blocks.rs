@@ -1,13 +1,30 @@
 use apache_avro::AvroSchema;
 use fuel_streams_domains::blocks::{
+    self,
     Block,
     Consensus,
 };
+use fuel_streams_types::{
+    BlockHeader,
+    BlockHeight,
+    BlockId,
+    BlockTime,
+    BlockVersion,
+    WrappedU32,
+};
 use serde::{
     Deserialize,
     Serialize,
 };
 
+use super::decode::{
+    RowDecodeError,
+    bytes,
+    int,
+    parse,
+    required,
+    word,
+};
 use crate::helpers::AvroBytes;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, AvroSchema)]
@@ -63,6 +80,10 @@ pub struct AvroBlock {
     #[avro(rename = "poaConsensusDataSignature")]
     pub poa_consensus_data_signature: Option<AvroBytes>,
     pub producer: Option<AvroBytes>,
+    /// Lossless decimal text of `daHeight`, added in schema v2 and absent from
+    /// older files
+    #[avro(rename = "daHeightU64", default = "null")]
+    pub da_height_u64: Option<String>,
 }
 
 impl AvroBlock {
@@ -112,10 +133,91 @@ impl AvroBlock {
                 .as_ref()
                 .and_then(|p| p.signature.clone()),
             producer: Some(block.producer.clone().into()),
+            da_height_u64: Some(block.header.da_height.0.to_string()),
         }
     }
 }
 
+/// Decodes a block row. Genesis roots are not exported, so a genesis block
+/// decodes with zeroed roots.
+impl TryFrom<AvroBlock> for Block {
+    type Error = RowDecodeError;
+
+    fn try_from(row: AvroBlock) -> Result<Self, Self::Error> {
+        let wrapped_u32 =
+            |value: Option<i64>, field| int::<_, u32>(value, field).map(WrappedU32::from);
+
+        let consensus_type = required(row.consensus_type, "AvroBlock.consensusType")?;
+        let consensus = match consensus_type.as_str() {
+            "Genesis" => Consensus::Genesis(blocks::Genesis::default()),
+            "PoAConsensus" => Consensus::PoAConsensus(blocks::PoAConsensus::new(bytes(
+                row.poa_consensus_data_signature,
+                "AvroBlock.poaConsensusDataSignature",
+            )?)),
+            other => {
+                return Err(RowDecodeError::invalid(
+                    "AvroBlock.consensusType",
+                    format!("unknown consensus {other}"),
+                ));
+            }
+        };
+
+        let height = BlockHeight::from(int::<_, u32>(row.height, "AvroBlock.height")?);
+        let id: BlockId = bytes(row.id, "AvroBlock.id")?;
+        let transaction_count =
+            required(row.transactions_count, "AvroBlock.transactionsCount")?;
+        let version: BlockVersion = parse(
+            &required(row.version, "AvroBlock.version")?,
+            "AvroBlock.version",
+        )?;
+
+        let header = BlockHeader {
+            application_hash: bytes(row.application_hash, "AvroBlock.applicationHash")?,
+            consensus_parameters_version: wrapped_u32(
+                row.consensus_parameters_version,
+                "AvroBlock.consensusParametersVersion",
+            )?,
+            da_height: word(row.da_height_u64, row.da_height, "AvroBlock.daHeight")?,
+            event_inbox_root: bytes(row.event_inbox_root, "AvroBlock.eventInboxRoot")?,
+            id: id.clone(),
+            height,
+            message_outbox_root: bytes(
+                row.message_outbox_root,
+                "AvroBlock.messageOutboxRoot",
+            )?,
+            message_receipt_count: wrapped_u32(
+                row.message_receipt_count,
+                "AvroBlock.messageReceiptCount",
+            )?,
+            prev_root: bytes(row.prev_root, "AvroBlock.prevRoot")?,
+            state_transition_bytecode_version: wrapped_u32(
+                row.state_transition_bytecode_version,
+                "AvroBlock.stateTransitionBytecodeVersion",
+            )?,
+            time: BlockTime::from_unix(required(row.time, "AvroBlock.time")?),
+            transactions_count: int(
+                Some(transaction_count),
+                "AvroBlock.transactionsCount",
+            )?,
+            transactions_root: bytes(
+                row.transactions_root,
+                "AvroBlock.transactionsRoot",
+            )?,
+            version: version.clone(),
+        };
+
+        Ok(Self {
+            consensus,
+            header,
+            height,
+            id,
+            transaction_count,
+            version,
+            producer: bytes(row.producer, "AvroBlock.producer")?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use fuel_streams_domains::{
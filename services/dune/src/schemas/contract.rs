@@ -0,0 +1,133 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    blocks::Block,
+    contracts::Contract,
+    transactions::Transaction,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::helpers::AvroBytes;
+
+/// One row of the `contracts` table: a contract deployed by a `Create`
+/// transaction
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroContract {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "contractId")]
+    pub contract_id: Option<AvroBytes>,
+    /// Deploying transaction
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    pub salt: Option<AvroBytes>,
+    /// SHA-256 of the bytecode witness
+    #[avro(rename = "bytecodeHash")]
+    pub bytecode_hash: Option<AvroBytes>,
+    #[avro(rename = "bytecodeLength")]
+    pub bytecode_length: Option<i64>,
+    #[avro(rename = "stateRoot")]
+    pub state_root: Option<AvroBytes>,
+    #[avro(rename = "storageSlotsCount")]
+    pub storage_slots_count: Option<i64>,
+    /// Owner of the inputs paying for the deployment, null when they have
+    /// several owners
+    pub deployer: Option<AvroBytes>,
+}
+
+impl AvroContract {
+    /// Rows of the contracts deployed by `tx`, the `tx_index`-th transaction
+    /// of `block`
+    pub fn rows(block: &Block, tx_index: usize, tx: &Transaction) -> Vec<Self> {
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        Contract::from_transaction(tx_index as i32, tx)
+            .iter()
+            .map(|contract| Self {
+                block_height: Some(block.height.0 as i64),
+                block_time: Some(block_time),
+                ..Self::new(contract)
+            })
+            .collect()
+    }
+
+    /// Row of a single contract, without its block coordinates
+    pub fn new(contract: &Contract) -> Self {
+        Self {
+            block_height: None,
+            block_time: None,
+            contract_id: Some(contract.contract_id.clone().into()),
+            transaction_id: Some(contract.tx_id.clone().into()),
+            tx_index: Some(contract.tx_index as i64),
+            salt: Some(contract.salt.clone().into()),
+            bytecode_hash: Some(contract.bytecode_hash.clone().into()),
+            bytecode_length: Some(contract.bytecode_length as i64),
+            state_root: Some(contract.state_root.clone().into()),
+            storage_slots_count: Some(contract.storage_slots_count as i64),
+            deployer: contract.deployer.clone().map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockInput,
+        MockOutput,
+        MockTransaction,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::helpers::{
+        AvroParser,
+        write_schema_files,
+    };
+
+    #[test]
+    fn test_avro_contract_rows() {
+        let block = MockBlock::random();
+        let outputs = vec![MockOutput::contract_created(), MockOutput::change(10)];
+        let tx =
+            MockTransaction::create(vec![MockInput::coin_signed(None)], outputs, vec![]);
+        let rows = AvroContract::rows(&block, 2, &tx);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].storage_slots_count, Some(1));
+        assert!(rows[0].deployer.is_some());
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroContract>().unwrap();
+        for row in &rows {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            assert_eq!(row.transaction_id, Some(tx.id.clone().into()));
+            assert_eq!(row.tx_index, Some(2));
+            writer.append(row).unwrap();
+        }
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroContract>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+
+        let script = MockTransaction::script(vec![], MockOutput::all(), vec![]);
+        assert!(AvroContract::rows(&block, 0, &script).is_empty());
+    }
+
+    #[tokio::test]
+    async fn write_contract_schemas() {
+        let schemas = [("contract.json", AvroContract::get_schema())];
+
+        write_schema_files(&schemas).await;
+    }
+}
@@ -0,0 +1,701 @@
+//! Conversion of exported rows back into the domain types.
+//!
+//! `TryFrom<AvroBlock> for Block`, `TryFrom<AvroTransaction> for Transaction`
+//! and `TryFrom<AvroReceipt> for Receipt` live next to their row types. The
+//! fields listed in [`LOSSY_FIELDS`] do not survive the round trip, every other
+//! field decodes to the value it was exported from.
+//!
+//! Unsigned 64-bit values are stored as the `long` with the same bits, so
+//! values above `i64::MAX` read as negative in other engines. Since schema v2
+//! of transactions and blocks and v3 of receipts, each of them also has a
+//! `...U64` string column holding the exact decimal value. Readers prefer the
+//! string column and fall back to the `long` bits for older files.
+
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+use fuel_streams_types::{
+    Address,
+    AssetId,
+    BlobId,
+    BlockId,
+    Bytes32,
+    ContractId,
+    FuelCoreBytes32,
+    Nonce,
+    Salt,
+    Signature,
+    TxId,
+};
+
+use crate::helpers::AvroBytes;
+
+/// Domain fields that do not survive domain → Avro → domain, with the value
+/// they decode to
+pub const LOSSY_FIELDS: &[(&str, &str)] = &[
+    (
+        "Block.consensus",
+        "Genesis roots are not exported, a genesis block decodes with zeroed roots",
+    ),
+    (
+        "Block.header.transactions_count",
+        "not exported, restored from Block.transaction_count",
+    ),
+    (
+        "Transaction.receipts",
+        "exported to the receipts table, decodes as empty",
+    ),
+    (
+        "Transaction.input_contracts",
+        "restored from the contract inputs, sorted and deduplicated, None for mints",
+    ),
+    (
+        "Transaction.total_gas, total_fee, gas_used and tip",
+        "exported since transactions schema v3, decode as None from older files",
+    ),
+    (
+        "Receipt.pc",
+        "exported since receipts schema v2, decodes as 0 from older files",
+    ),
+    ("PanicReceipt.contract_id", "not exported, decodes as None"),
+    (
+        "ScriptResultReceipt.result",
+        "GenericFailure(0..=2) shares its code with Success, Revert and Panic",
+    ),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum RowDecodeError {
+    #[error("{0} is missing")]
+    Missing(&'static str),
+    #[error("{field} is invalid: {reason}")]
+    Invalid { field: &'static str, reason: String },
+}
+
+impl RowDecodeError {
+    pub(crate) fn invalid(field: &'static str, reason: impl Display) -> Self {
+        Self::Invalid {
+            field,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Fixed-size byte types exported as Avro `bytes`
+pub(crate) trait FromAvroBytes: Sized {
+    fn from_avro_bytes(
+        bytes: AvroBytes,
+        field: &'static str,
+    ) -> Result<Self, RowDecodeError>;
+}
+
+macro_rules! impl_from_avro_bytes {
+    ($($type:ty => $len:literal),* $(,)?) => {
+        $(
+            impl FromAvroBytes for $type {
+                fn from_avro_bytes(
+                    bytes: AvroBytes,
+                    field: &'static str,
+                ) -> Result<Self, RowDecodeError> {
+                    let len = bytes.0.len();
+                    let array = <[u8; $len]>::try_from(bytes.0).map_err(|_| {
+                        RowDecodeError::invalid(
+                            field,
+                            format!("expected {} bytes, got {len}", $len),
+                        )
+                    })?;
+                    Ok(Self::from(array))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_avro_bytes!(
+    Address => 32,
+    AssetId => 32,
+    BlobId => 32,
+    BlockId => 32,
+    Bytes32 => 32,
+    ContractId => 32,
+    FuelCoreBytes32 => 32,
+    Nonce => 32,
+    Salt => 32,
+    Signature => 64,
+    TxId => 32,
+);
+
+pub(crate) fn required<T>(
+    value: Option<T>,
+    field: &'static str,
+) -> Result<T, RowDecodeError> {
+    value.ok_or(RowDecodeError::Missing(field))
+}
+
+pub(crate) fn bytes<T: FromAvroBytes>(
+    value: Option<AvroBytes>,
+    field: &'static str,
+) -> Result<T, RowDecodeError> {
+    T::from_avro_bytes(required(value, field)?, field)
+}
+
+pub(crate) fn bytes_opt<T: FromAvroBytes>(
+    value: Option<AvroBytes>,
+    field: &'static str,
+) -> Result<Option<T>, RowDecodeError> {
+    value
+        .map(|value| T::from_avro_bytes(value, field))
+        .transpose()
+}
+
+pub(crate) fn bytes_vec<T: FromAvroBytes>(
+    values: Option<Vec<AvroBytes>>,
+    field: &'static str,
+) -> Result<Option<Vec<T>>, RowDecodeError> {
+    values
+        .map(|values| {
+            values
+                .into_iter()
+                .map(|value| T::from_avro_bytes(value, field))
+                .collect()
+        })
+        .transpose()
+}
+
+/// Narrows an integer column, failing on values out of the domain range
+pub(crate) fn int<V, T>(
+    value: Option<V>,
+    field: &'static str,
+) -> Result<T, RowDecodeError>
+where
+    V: Copy + Display,
+    T: TryFrom<V>,
+{
+    let value = required(value, field)?;
+    T::try_from(value)
+        .map_err(|_| RowDecodeError::invalid(field, format!("{value} is out of range")))
+}
+
+pub(crate) fn int_opt<V, T>(
+    value: Option<V>,
+    field: &'static str,
+) -> Result<Option<T>, RowDecodeError>
+where
+    V: Copy + Display,
+    T: TryFrom<V>,
+{
+    value.map(|value| int(Some(value), field)).transpose()
+}
+
+/// Decimal text of an unsigned 64-bit value stored in a `long` column, for
+/// its lossless `...U64` companion column
+pub(crate) fn unsigned(value: Option<i64>) -> Option<String> {
+    value.map(|value| (value as u64).to_string())
+}
+
+/// Sets a missing `...U64` column from the bits of its `long` column
+pub(crate) fn backfill(text: &mut Option<String>, value: Option<i64>) {
+    if text.is_none() {
+        *text = unsigned(value);
+    }
+}
+
+/// Reads an unsigned 64-bit value from its `...U64` decimal column, or from
+/// the bits of its `long` column in files written before the decimal columns
+pub(crate) fn word<T: From<u64>>(
+    text: Option<String>,
+    value: Option<i64>,
+    field: &'static str,
+) -> Result<T, RowDecodeError> {
+    match text {
+        Some(text) => parse::<u64>(&text, field).map(T::from),
+        None => required(value, field).map(|value| T::from(value as u64)),
+    }
+}
+
+pub(crate) fn word_opt<T: From<u64>>(
+    text: Option<String>,
+    value: Option<i64>,
+    field: &'static str,
+) -> Result<Option<T>, RowDecodeError> {
+    match (text, value) {
+        (None, None) => Ok(None),
+        (text, value) => word(text, value, field).map(Some),
+    }
+}
+
+pub(crate) fn parse<T>(value: &str, field: &'static str) -> Result<T, RowDecodeError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|e| RowDecodeError::invalid(field, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::{
+        AvroSchema,
+        schema::derive::AvroSchemaComponent,
+    };
+    use fuel_streams_domains::{
+        blocks::{
+            Block,
+            Consensus,
+            Genesis,
+            PoAConsensus,
+        },
+        inputs::{
+            Input,
+            InputContract,
+        },
+        mocks::{
+            MockBlock,
+            MockInput,
+            MockOutput,
+            MockReceipt,
+            MockTransaction,
+        },
+        outputs::Output,
+        receipts::*,
+        transactions::Transaction,
+    };
+    use fuel_streams_types::{
+        Amount,
+        FuelCoreUpgradePurpose,
+        GasAmount,
+        HexData,
+        PanicInstruction,
+        Policies,
+        ScriptExecutionResult,
+        StorageSlot,
+        TxPointer,
+        UtxoId,
+        Word,
+    };
+    use pretty_assertions::assert_eq;
+    use rand::{
+        Rng,
+        seq::SliceRandom,
+    };
+    use serde::{
+        Serialize,
+        de::DeserializeOwned,
+    };
+
+    use super::*;
+    use crate::{
+        helpers::AvroParser,
+        schemas::{
+            AvroBlock,
+            AvroReceipt,
+            AvroRow,
+            AvroTransaction,
+        },
+    };
+
+    const CASES: usize = 64;
+
+    /// Writes the row to an Avro file and reads it back
+    fn through_avro<T>(row: &T) -> T
+    where
+        T: AvroSchema
+            + AvroSchemaComponent
+            + Serialize
+            + DeserializeOwned
+            + Send
+            + Sync
+            + 'static,
+    {
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<T>().unwrap();
+        writer.append(row).unwrap();
+        let file = writer.into_inner().unwrap();
+        let mut rows = parser
+            .reader_with_schema::<T>()
+            .unwrap()
+            .deserialize(&file)
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        rows.remove(0)
+    }
+
+    fn random_data() -> HexData {
+        let len = rand::rng().random_range(0..64);
+        HexData(AvroBytes::random(len).0.into())
+    }
+
+    fn random_block() -> Block {
+        let mut block = MockBlock::random();
+        block.transaction_count = rand::rng().random_range(0..=u16::MAX as i64);
+        block.header.transactions_count = block.transaction_count as u16;
+        block.header.da_height = rand::rng().random::<u64>().into();
+        if rand::rng().random_bool(0.5) {
+            block.consensus =
+                Consensus::PoAConsensus(PoAConsensus::new(Signature::random()));
+        }
+        block
+    }
+
+    fn random_inputs() -> Vec<Input> {
+        let mut inputs = MockInput::all()
+            .into_iter()
+            .map(|input| match input {
+                Input::Coin(mut coin) => {
+                    coin.amount = Amount::random();
+                    coin.predicate_gas_used = GasAmount::random();
+                    coin.witness_index = rand::rng().random();
+                    Input::Coin(coin)
+                }
+                Input::Message(mut message) => {
+                    message.amount = Amount::random();
+                    message.data = random_data();
+                    Input::Message(message)
+                }
+                input => input,
+            })
+            .collect::<Vec<_>>();
+        inputs.extend([
+            MockInput::contract(),
+            MockInput::coin_signed(Some(UtxoId::random())),
+        ]);
+        inputs.shuffle(&mut rand::rng());
+        inputs
+    }
+
+    fn random_outputs() -> Vec<Output> {
+        let mut outputs = MockOutput::all();
+        outputs.extend([
+            MockOutput::coin(rand::rng().random()),
+            MockOutput::change(rand::rng().random()),
+            MockOutput::variable(rand::rng().random()),
+        ]);
+        outputs.shuffle(&mut rand::rng());
+        outputs
+    }
+
+    /// Transactions of every type, with the lossy fields set to the values
+    /// they decode to
+    fn random_transactions() -> Vec<Transaction> {
+        let build = [
+            MockTransaction::script,
+            MockTransaction::create,
+            MockTransaction::mint,
+            MockTransaction::upgrade,
+            MockTransaction::upload,
+            MockTransaction::blob,
+        ];
+        let mut transactions = build
+            .iter()
+            .map(|build| build(random_inputs(), random_outputs(), vec![]))
+            .collect::<Vec<_>>();
+
+        let mut upgrade =
+            MockTransaction::upgrade(random_inputs(), random_outputs(), vec![]);
+        upgrade.upgrade_purpose = Some(
+            FuelCoreUpgradePurpose::ConsensusParameters {
+                witness_index: rand::rng().random(),
+                checksum: FuelCoreBytes32::from(rand::rng().random::<[u8; 32]>()),
+            }
+            .into(),
+        );
+        transactions.push(upgrade);
+
+        for tx in &mut transactions {
+            tx.policies = Some(Policies::random());
+            tx.tx_pointer = Some(TxPointer::random());
+            tx.witnesses = Some(vec![random_data(), random_data()]);
+            tx.witnesses_count = 2;
+            tx.storage_slots = Some(vec![StorageSlot {
+                key: Bytes32::random(),
+                value: Bytes32::random(),
+            }]);
+            if tx.is_mint {
+                tx.mint_amount = Some(Amount::random());
+                tx.mint_gas_price = Some(Amount::random());
+                tx.input_contracts = None;
+            } else {
+                let mut ids = tx
+                    .inputs
+                    .iter()
+                    .filter_map(|input| match input {
+                        Input::Contract(InputContract { contract_id, .. }) => {
+                            Some(ContractId::from(contract_id.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                ids.sort_by(|a, b| a.0.cmp(&b.0));
+                ids.dedup();
+                tx.input_contracts = Some(ids);
+            }
+        }
+        transactions
+    }
+
+    /// Receipts of every type, with the lossy fields set to the values they
+    /// decode to
+    fn random_receipts() -> Vec<Receipt> {
+        let pc = Word::random();
+        vec![
+            Receipt::Call(CallReceipt {
+                id: ContractId::random(),
+                to: ContractId::random(),
+                amount: Word::random(),
+                asset_id: AssetId::random(),
+                gas: Word::random(),
+                param1: Word::random(),
+                param2: Word::random(),
+                pc,
+                is: Word::random(),
+            }),
+            Receipt::Return(ReturnReceipt {
+                id: ContractId::random(),
+                val: Word::random(),
+                pc,
+                is: Word::random(),
+            }),
+            Receipt::ReturnData(ReturnDataReceipt {
+                id: ContractId::random(),
+                ptr: Word::random(),
+                len: Word::random(),
+                digest: Bytes32::random(),
+                pc,
+                is: Word::random(),
+                data: Some(random_data()),
+            }),
+            Receipt::Panic(PanicReceipt {
+                id: ContractId::random(),
+                reason: PanicInstruction {
+                    reason: rand::rng().random(),
+                    instruction: rand::rng().random(),
+                },
+                pc,
+                is: Word::random(),
+                contract_id: None,
+            }),
+            Receipt::Revert(RevertReceipt {
+                id: ContractId::random(),
+                ra: Word::random(),
+                pc,
+                is: Word::random(),
+            }),
+            Receipt::Log(LogReceipt {
+                id: ContractId::random(),
+                ra: Word::random(),
+                rb: Word::random(),
+                rc: Word::random(),
+                rd: Word::random(),
+                pc,
+                is: Word::random(),
+            }),
+            Receipt::LogData(LogDataReceipt {
+                id: ContractId::random(),
+                ra: Word::random(),
+                rb: Word::random(),
+                ptr: Word::random(),
+                len: Word::random(),
+                digest: Bytes32::random(),
+                pc,
+                is: Word::random(),
+                data: None,
+            }),
+            Receipt::Transfer(TransferReceipt {
+                id: ContractId::random(),
+                to: ContractId::random(),
+                amount: Word::random(),
+                asset_id: AssetId::random(),
+                pc,
+                is: Word::random(),
+            }),
+            Receipt::TransferOut(TransferOutReceipt {
+                id: ContractId::random(),
+                to: Address::random(),
+                amount: Word::random(),
+                asset_id: AssetId::random(),
+                pc,
+                is: Word::random(),
+            }),
+            Receipt::ScriptResult(ScriptResultReceipt {
+                result: [
+                    ScriptExecutionResult::Success,
+                    ScriptExecutionResult::Revert,
+                    ScriptExecutionResult::Panic,
+                    ScriptExecutionResult::GenericFailure(
+                        rand::rng().random_range(3..=u64::MAX),
+                    ),
+                ][rand::rng().random_range(0..4)],
+                gas_used: Word::random(),
+            }),
+            Receipt::MessageOut(MessageOutReceipt {
+                sender: Address::random(),
+                recipient: Address::random(),
+                amount: Word::random(),
+                nonce: Nonce::random(),
+                len: Word::random(),
+                digest: Bytes32::random(),
+                data: Some(random_data()),
+            }),
+            Receipt::Mint(MintReceipt {
+                sub_id: Bytes32::random(),
+                contract_id: ContractId::random(),
+                val: Word::random(),
+                pc,
+                is: Word::random(),
+            }),
+            Receipt::Burn(BurnReceipt {
+                sub_id: Bytes32::random(),
+                contract_id: ContractId::random(),
+                val: Word::random(),
+                pc,
+                is: Word::random(),
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        for _ in 0..CASES {
+            let block = random_block();
+            let row = through_avro(&AvroBlock::new(&block));
+            assert_eq!(Block::try_from(row).unwrap(), block);
+        }
+    }
+
+    #[test]
+    fn test_transaction_round_trip() {
+        for _ in 0..CASES {
+            let block = random_block();
+            for tx in random_transactions() {
+                let row = through_avro(&AvroTransaction::from((&block, &tx)));
+                assert_eq!(Transaction::try_from(row).unwrap(), tx);
+            }
+        }
+    }
+
+    #[test]
+    fn test_receipt_round_trip() {
+        for _ in 0..CASES {
+            let block = random_block();
+            let tx = MockTransaction::script(vec![], vec![], vec![]);
+            for receipt in random_receipts() {
+                let row = through_avro(&AvroReceipt::from((&block, &tx, &receipt)));
+                assert_eq!(Receipt::try_from(row).unwrap(), receipt);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lossy_fields() {
+        let mut block = random_block();
+        block.consensus = Consensus::Genesis(Genesis {
+            coins_root: Bytes32::random(),
+            ..Default::default()
+        });
+        let decoded = Block::try_from(AvroBlock::new(&block)).unwrap();
+        assert_eq!(decoded.consensus, Consensus::Genesis(Genesis::default()));
+
+        let receipt = Receipt::Panic(PanicReceipt {
+            id: ContractId::random(),
+            reason: PanicInstruction::default(),
+            pc: 8.into(),
+            is: 4.into(),
+            contract_id: Some(ContractId::random()),
+        });
+        let tx = MockTransaction::script(vec![], vec![], vec![receipt.clone()]);
+        let mut row = AvroReceipt::from((&block, &tx, &receipt));
+        let Receipt::Panic(decoded) = Receipt::try_from(row.clone()).unwrap() else {
+            panic!("expected a panic receipt");
+        };
+        assert_eq!(decoded.pc, 8.into());
+        assert_eq!(decoded.is, 4.into());
+        assert_eq!(decoded.contract_id, None);
+
+        // Files written before receipts schema v2 have no program counters
+        row.pc = None;
+        row.pc_u64 = None;
+        let Receipt::Panic(decoded) = Receipt::try_from(row).unwrap() else {
+            panic!("expected a panic receipt");
+        };
+        assert_eq!(decoded.pc, 0.into());
+
+        let row = AvroTransaction::from((&block, &tx));
+        assert!(Transaction::try_from(row).unwrap().receipts.is_empty());
+    }
+
+    #[test]
+    fn test_unsigned_columns() {
+        let block = random_block();
+        let receipt = Receipt::Return(ReturnReceipt {
+            id: ContractId::random(),
+            val: u64::MAX.into(),
+            pc: 8.into(),
+            is: 4.into(),
+        });
+        let tx = MockTransaction::script(vec![], vec![], vec![receipt.clone()]);
+        let mut row = through_avro(&AvroReceipt::from((&block, &tx, &receipt)));
+        assert_eq!(row.val, Some(-1));
+        assert_eq!(row.val_u64.as_deref(), Some("18446744073709551615"));
+        assert_eq!(Receipt::try_from(row.clone()).unwrap(), receipt);
+
+        // Files written before the decimal columns decode from the bits
+        row.val_u64 = None;
+        assert_eq!(Receipt::try_from(row.clone()).unwrap(), receipt);
+        row.upgrade();
+        assert_eq!(row.val_u64.as_deref(), Some("18446744073709551615"));
+
+        row.val_u64 = Some("-1".to_string());
+        assert!(matches!(
+            Receipt::try_from(row),
+            Err(RowDecodeError::Invalid {
+                field: "AvroReceipt.val",
+                ..
+            })
+        ));
+
+        let mut tx = MockTransaction::mint(vec![], vec![], vec![]);
+        tx.mint_amount = Some(u64::MAX.into());
+        let mut row = AvroTransaction::from((&block, &tx));
+        row.mint_amount_u64 = None;
+        row.upgrade();
+        assert_eq!(row.mint_amount_u64.as_deref(), Some("18446744073709551615"));
+    }
+
+    #[test]
+    fn test_invalid_rows() {
+        let block = random_block();
+        let mut row = AvroBlock::new(&block);
+        row.producer = Some(AvroBytes::random(31));
+        assert!(matches!(
+            Block::try_from(row),
+            Err(RowDecodeError::Invalid {
+                field: "AvroBlock.producer",
+                ..
+            })
+        ));
+
+        let mut row = AvroBlock::new(&block);
+        row.height = Some(i64::from(u32::MAX) + 1);
+        assert!(matches!(
+            Block::try_from(row),
+            Err(RowDecodeError::Invalid {
+                field: "AvroBlock.height",
+                ..
+            })
+        ));
+
+        let tx = MockTransaction::script(MockInput::all(), vec![], vec![]);
+        let mut row = AvroTransaction::from((&block, &tx));
+        row.inputs.coin_inputs = Some(vec![]);
+        assert!(matches!(
+            Transaction::try_from(row),
+            Err(RowDecodeError::Missing("Inputs.coinInputs"))
+        ));
+
+        let mut row = AvroReceipt::from((&block, &tx, &MockReceipt::call()));
+        row.receipt_type = Some("unknown".to_string());
+        assert!(Receipt::try_from(row).is_err());
+    }
+}
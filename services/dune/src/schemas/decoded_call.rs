@@ -0,0 +1,180 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    blocks::Block,
+    transactions::Transaction,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    abi::AbiRegistry,
+    helpers::AvroBytes,
+};
+
+/// One row of the `decoded_calls` table: a `Call` receipt to a contract with
+/// a known ABI, with the called function and its arguments
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroDecodedCall {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    /// Position of the receipt in its transaction
+    #[avro(rename = "receiptIndex")]
+    pub receipt_index: Option<i64>,
+    /// The called contract
+    #[avro(rename = "contractId")]
+    pub contract_id: Option<AvroBytes>,
+    /// Name of the called function, null when its selector wasn't found
+    pub function: Option<String>,
+    /// The arguments as a JSON object, null when they could not be decoded
+    pub arguments: Option<String>,
+    /// Why the call could not be decoded, null when it was
+    #[avro(rename = "decodeError")]
+    pub decode_error: Option<String>,
+}
+
+impl AvroDecodedCall {
+    /// Rows of the `Call` receipts of `tx`, the `tx_index`-th transaction of
+    /// `block`, to the contracts of `abis`
+    pub fn rows(
+        block: &Block,
+        tx_index: usize,
+        tx: &Transaction,
+        abis: &AbiRegistry,
+    ) -> Vec<Self> {
+        if abis.is_empty() {
+            return vec![];
+        }
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        let script_data = tx.script_data.as_ref().map(|data| data.0.0.as_slice());
+        abis.decode_calls(script_data, &tx.receipts)
+            .into_iter()
+            .map(|(receipt_index, call, decoded)| {
+                let (arguments, decode_error) = match decoded.arguments {
+                    Ok(value) => (Some(value.to_string()), None),
+                    Err(e) => (None, Some(e.to_string())),
+                };
+                Self {
+                    block_height: Some(block.height.0 as i64),
+                    block_time: Some(block_time),
+                    transaction_id: Some(tx.id.clone().into()),
+                    tx_index: Some(tx_index as i64),
+                    receipt_index: Some(receipt_index as i64),
+                    contract_id: Some(call.to.clone().into()),
+                    function: decoded.function,
+                    arguments,
+                    decode_error,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::{
+        mocks::{
+            MockBlock,
+            MockReceipt,
+            MockTransaction,
+        },
+        receipts::{
+            CallReceipt,
+            Receipt,
+        },
+    };
+    use fuel_streams_types::ContractId;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        abi::ProgramAbi,
+        helpers::{
+            AvroParser,
+            write_schema_files,
+        },
+    };
+
+    #[test]
+    fn test_avro_decoded_call_rows() {
+        let block = MockBlock::random();
+        let Receipt::Call(mut call) = MockReceipt::call() else {
+            unreachable!()
+        };
+        // The selector of `pause`, with the script data at address 1000
+        let mut script_data = 5u64.to_be_bytes().to_vec();
+        script_data.extend(b"pause");
+        call.id = ContractId::zeroed();
+        call.param1 = 1000.into();
+        let receipts = vec![
+            MockReceipt::log_data(),
+            Receipt::Call(call.clone()),
+            // A call made by a contract
+            Receipt::Call(CallReceipt {
+                id: ContractId::random(),
+                ..call.clone()
+            }),
+        ];
+        let mut tx = MockTransaction::script(vec![], vec![], receipts);
+        tx.script_data = Some(script_data.into());
+
+        let abi = json!({
+            "encodingVersion": "1",
+            "concreteTypes": [{ "type": "()", "concreteTypeId": "aa" }],
+            "functions": [{ "name": "pause", "inputs": [], "output": "aa" }]
+        });
+        let mut abis = AbiRegistry::default();
+        assert!(AvroDecodedCall::rows(&block, 0, &tx, &abis).is_empty());
+        abis.insert(
+            call.to.clone(),
+            ProgramAbi::from_json(abi.to_string().as_bytes()).unwrap(),
+        );
+
+        let rows = AvroDecodedCall::rows(&block, 3, &tx, &abis);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].receipt_index, Some(1));
+        assert_eq!(rows[0].tx_index, Some(3));
+        assert_eq!(rows[0].function.as_deref(), Some("pause"));
+        assert_eq!(rows[0].arguments.as_deref(), Some("{}"));
+        assert_eq!(rows[0].decode_error, None);
+        assert_eq!(rows[1].function, None);
+        assert_eq!(rows[1].arguments, None);
+        assert_eq!(
+            rows[1].decode_error.as_deref(),
+            Some("function selector not found in the script data")
+        );
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroDecodedCall>().unwrap();
+        for row in &rows {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            assert_eq!(row.contract_id, Some(call.to.clone().into()));
+            writer.append(row).unwrap();
+        }
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroDecodedCall>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+    }
+
+    #[tokio::test]
+    async fn write_decoded_call_schemas() {
+        let schemas = [("decoded_call.json", AvroDecodedCall::get_schema())];
+
+        write_schema_files(&schemas).await;
+    }
+}
@@ -0,0 +1,187 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    blocks::Block,
+    receipts::Receipt,
+    transactions::Transaction,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    abi::AbiRegistry,
+    helpers::AvroBytes,
+};
+
+/// One row of the `decoded_logs` table: a `LogData` receipt of a contract
+/// with a known ABI, decoded into JSON
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroDecodedLog {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    /// Position of the receipt in its transaction
+    #[avro(rename = "receiptIndex")]
+    pub receipt_index: Option<i64>,
+    #[avro(rename = "contractId")]
+    pub contract_id: Option<AvroBytes>,
+    /// The `rb` register of the receipt
+    #[avro(rename = "logId")]
+    pub log_id: Option<i64>,
+    #[avro(rename = "logIdU64")]
+    pub log_id_u64: Option<String>,
+    /// Name of the logged type, null for a log id missing from the ABI
+    #[avro(rename = "eventType")]
+    pub event_type: Option<String>,
+    /// The decoded value as JSON, null when it could not be decoded
+    pub decoded: Option<String>,
+    /// Why the value could not be decoded, null when it was
+    #[avro(rename = "decodeError")]
+    pub decode_error: Option<String>,
+}
+
+impl AvroDecodedLog {
+    /// Rows of the `LogData` receipts of `tx`, the `tx_index`-th transaction
+    /// of `block`, emitted by the contracts of `abis`
+    pub fn rows(
+        block: &Block,
+        tx_index: usize,
+        tx: &Transaction,
+        abis: &AbiRegistry,
+    ) -> Vec<Self> {
+        if abis.is_empty() {
+            return vec![];
+        }
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        tx.receipts
+            .iter()
+            .enumerate()
+            .filter_map(|(receipt_index, receipt)| {
+                let Receipt::LogData(log) = receipt else {
+                    return None;
+                };
+                let abi = abis.get(&log.id)?;
+                let data = log.data.as_ref().map(|data| data.0.0.as_slice());
+                let decoded = abi.decode_log(log.rb.0, data);
+                let (decoded_json, decode_error) = match decoded.value {
+                    Ok(value) => (Some(value.to_string()), None),
+                    Err(e) => (None, Some(e.to_string())),
+                };
+                Some(Self {
+                    block_height: Some(block.height.0 as i64),
+                    block_time: Some(block_time),
+                    transaction_id: Some(tx.id.clone().into()),
+                    tx_index: Some(tx_index as i64),
+                    receipt_index: Some(receipt_index as i64),
+                    contract_id: Some(log.id.clone().into()),
+                    log_id: Some(log.rb.0 as i64),
+                    log_id_u64: Some(log.rb.0.to_string()),
+                    event_type: decoded.event_type,
+                    decoded: decoded_json,
+                    decode_error,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::{
+        mocks::{
+            MockBlock,
+            MockReceipt,
+            MockTransaction,
+        },
+        receipts::LogDataReceipt,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        abi::ProgramAbi,
+        helpers::{
+            AvroParser,
+            write_schema_files,
+        },
+    };
+
+    #[test]
+    fn test_avro_decoded_log_rows() {
+        let block = MockBlock::random();
+        let Receipt::LogData(mut log) = MockReceipt::log_data() else {
+            unreachable!()
+        };
+        log.rb = 7.into();
+        log.data = Some(vec![0, 0, 0, 0, 0, 0, 0, 42].into());
+        let unknown_id = LogDataReceipt {
+            rb: 8.into(),
+            ..log.clone()
+        };
+        let receipts = vec![
+            MockReceipt::call(),
+            Receipt::LogData(log.clone()),
+            Receipt::LogData(unknown_id),
+            // Logs of contracts without an ABI are left out
+            MockReceipt::log_data(),
+        ];
+        let tx = MockTransaction::script(vec![], vec![], receipts);
+
+        let abi = json!({
+            "encodingVersion": "1",
+            "concreteTypes": [{ "type": "u64", "concreteTypeId": "aa" }],
+            "metadataTypes": [],
+            "loggedTypes": [{ "logId": "7", "concreteTypeId": "aa" }]
+        });
+        let mut abis = AbiRegistry::default();
+        assert!(AvroDecodedLog::rows(&block, 0, &tx, &abis).is_empty());
+        abis.insert(
+            log.id.clone(),
+            ProgramAbi::from_json(abi.to_string().as_bytes()).unwrap(),
+        );
+
+        let rows = AvroDecodedLog::rows(&block, 2, &tx, &abis);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].receipt_index, Some(1));
+        assert_eq!(rows[0].tx_index, Some(2));
+        assert_eq!(rows[0].log_id_u64.as_deref(), Some("7"));
+        assert_eq!(rows[0].event_type.as_deref(), Some("u64"));
+        assert_eq!(rows[0].decoded.as_deref(), Some("42"));
+        assert_eq!(rows[0].decode_error, None);
+        assert_eq!(rows[1].event_type, None);
+        assert_eq!(rows[1].decoded, None);
+        assert_eq!(rows[1].decode_error.as_deref(), Some("unknown log id 8"));
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroDecodedLog>().unwrap();
+        for row in &rows {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            assert_eq!(row.contract_id, Some(log.id.clone().into()));
+            writer.append(row).unwrap();
+        }
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroDecodedLog>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+    }
+
+    #[tokio::test]
+    async fn write_decoded_log_schemas() {
+        let schemas = [("decoded_log.json", AvroDecodedLog::get_schema())];
+
+        write_schema_files(&schemas).await;
+    }
+}
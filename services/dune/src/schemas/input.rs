@@ -1,11 +1,26 @@
 use apache_avro::AvroSchema;
-use fuel_streams_domains::inputs;
+use fuel_streams_domains::{
+    blocks::Block,
+    inputs,
+    transactions::Transaction,
+};
+use fuel_streams_types::HexData;
 use serde::{
     Deserialize,
     Serialize,
 };
 
-use super::TxPointer;
+use super::{
+    TxPointer,
+    decode::{
+        RowDecodeError,
+        bytes,
+        int,
+        parse,
+        required,
+        word,
+    },
+};
 use crate::helpers::AvroBytes;
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
@@ -53,6 +68,12 @@ pub struct InputCoin {
     pub utxo_id: Option<String>,
     #[avro(rename = "witnessIndex")]
     pub witness_index: Option<i64>,
+    // Lossless decimal text of `amount` and `predicateGasUsed`, added in
+    // transactions schema v2 and absent from older files
+    #[avro(rename = "amountU64", default = "null")]
+    pub amount_u64: Option<String>,
+    #[avro(rename = "predicateGasUsedU64", default = "null")]
+    pub predicate_gas_used_u64: Option<String>,
 }
 
 impl InputCoin {
@@ -67,6 +88,8 @@ impl InputCoin {
             tx_pointer: Some((&input.tx_pointer).into()),
             utxo_id: Some(input.utxo_id.to_string()),
             witness_index: Some(input.witness_index as i64),
+            amount_u64: Some(input.amount.0.to_string()),
+            predicate_gas_used_u64: Some(input.predicate_gas_used.0.to_string()),
         }
     }
 }
@@ -90,6 +113,12 @@ pub struct InputMessage {
     pub sender: Option<AvroBytes>,
     #[avro(rename = "witnessIndex")]
     pub witness_index: Option<i64>,
+    // Lossless decimal text of `amount` and `predicateGasUsed`, added in
+    // transactions schema v2 and absent from older files
+    #[avro(rename = "amountU64", default = "null")]
+    pub amount_u64: Option<String>,
+    #[avro(rename = "predicateGasUsedU64", default = "null")]
+    pub predicate_gas_used_u64: Option<String>,
 }
 
 impl InputMessage {
@@ -106,6 +135,8 @@ impl InputMessage {
             recipient: Some(input.recipient.clone().into()),
             sender: Some(input.sender.clone().into()),
             witness_index: Some(input.witness_index as i64),
+            amount_u64: Some(input.amount.0.to_string()),
+            predicate_gas_used_u64: Some(input.predicate_gas_used.0.to_string()),
         }
     }
 }
@@ -156,10 +187,257 @@ impl Inputs {
     }
 }
 
+impl TryFrom<InputContract> for inputs::InputContract {
+    type Error = RowDecodeError;
+
+    fn try_from(input: InputContract) -> Result<Self, Self::Error> {
+        Ok(Self {
+            balance_root: bytes(input.balance_root, "InputContract.balanceRoot")?,
+            contract_id: bytes(input.contract_id, "InputContract.contractId")?,
+            state_root: bytes(input.state_root, "InputContract.stateRoot")?,
+            tx_pointer: required(input.tx_pointer, "InputContract.txPointer")?
+                .try_into()?,
+            utxo_id: parse(
+                &required(input.utxo_id, "InputContract.utxoId")?,
+                "InputContract.utxoId",
+            )?,
+        })
+    }
+}
+
+impl TryFrom<InputCoin> for inputs::InputCoin {
+    type Error = RowDecodeError;
+
+    fn try_from(input: InputCoin) -> Result<Self, Self::Error> {
+        Ok(Self {
+            amount: word(input.amount_u64, input.amount, "InputCoin.amount")?,
+            asset_id: bytes(input.asset_id, "InputCoin.assetId")?,
+            owner: bytes(input.owner, "InputCoin.owner")?,
+            predicate: required(input.predicate, "InputCoin.predicate")?.0.into(),
+            predicate_data: required(input.predicate_data, "InputCoin.predicateData")?
+                .0
+                .into(),
+            predicate_gas_used: word(
+                input.predicate_gas_used_u64,
+                input.predicate_gas_used,
+                "InputCoin.predicateGasUsed",
+            )?,
+            tx_pointer: required(input.tx_pointer, "InputCoin.txPointer")?.try_into()?,
+            utxo_id: parse(
+                &required(input.utxo_id, "InputCoin.utxoId")?,
+                "InputCoin.utxoId",
+            )?,
+            witness_index: int(input.witness_index, "InputCoin.witnessIndex")?,
+        })
+    }
+}
+
+impl TryFrom<InputMessage> for inputs::InputMessage {
+    type Error = RowDecodeError;
+
+    fn try_from(input: InputMessage) -> Result<Self, Self::Error> {
+        Ok(Self {
+            amount: word(input.amount_u64, input.amount, "InputMessage.amount")?,
+            data: required(input.data, "InputMessage.data")?.0.into(),
+            nonce: bytes(input.nonce, "InputMessage.nonce")?,
+            predicate: required(input.predicate, "InputMessage.predicate")?
+                .0
+                .into(),
+            predicate_length: int(
+                input.predicate_length,
+                "InputMessage.predicateLength",
+            )?,
+            predicate_data: required(input.predicate_data, "InputMessage.predicateData")?
+                .0
+                .into(),
+            predicate_gas_used: word(
+                input.predicate_gas_used_u64,
+                input.predicate_gas_used,
+                "InputMessage.predicateGasUsed",
+            )?,
+            predicate_data_length: int(
+                input.predicate_data_length,
+                "InputMessage.predicateDataLength",
+            )?,
+            recipient: bytes(input.recipient, "InputMessage.recipient")?,
+            sender: bytes(input.sender, "InputMessage.sender")?,
+            witness_index: int(input.witness_index, "InputMessage.witnessIndex")?,
+        })
+    }
+}
+
+/// Restores the inputs in transaction order, following `inputTypes`
+impl TryFrom<Inputs> for Vec<inputs::Input> {
+    type Error = RowDecodeError;
+
+    fn try_from(inputs: Inputs) -> Result<Self, Self::Error> {
+        let mut contracts = inputs.contract_inputs.unwrap_or_default().into_iter();
+        let mut coins = inputs.coin_inputs.unwrap_or_default().into_iter();
+        let mut messages = inputs.message_inputs.unwrap_or_default().into_iter();
+
+        let mut decoded = Vec::new();
+        for input_type in inputs.input_types.unwrap_or_default() {
+            let input = match input_type.as_str() {
+                "contract" => inputs::Input::Contract(
+                    required(contracts.next(), "Inputs.contractInputs")?.try_into()?,
+                ),
+                "coin" => inputs::Input::Coin(
+                    required(coins.next(), "Inputs.coinInputs")?.try_into()?,
+                ),
+                "message" => inputs::Input::Message(
+                    required(messages.next(), "Inputs.messageInputs")?.try_into()?,
+                ),
+                other => {
+                    return Err(RowDecodeError::invalid(
+                        "Inputs.inputTypes",
+                        format!("unknown input type {other}"),
+                    ));
+                }
+            };
+            decoded.push(input);
+        }
+
+        if contracts.next().is_some()
+            || coins.next().is_some()
+            || messages.next().is_some()
+        {
+            return Err(RowDecodeError::invalid(
+                "Inputs.inputTypes",
+                "fewer types than inputs",
+            ));
+        }
+        Ok(decoded)
+    }
+}
+
+/// One row of the `inputs` table, flattened out of a transaction
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroInput {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    /// Position of the input in its transaction
+    #[avro(rename = "inputIndex")]
+    pub input_index: Option<i64>,
+    #[avro(rename = "inputType")]
+    pub input_type: Option<String>,
+    /// Coin owner or message recipient
+    pub owner: Option<AvroBytes>,
+    pub sender: Option<AvroBytes>,
+    #[avro(rename = "contractId")]
+    pub contract_id: Option<AvroBytes>,
+    #[avro(rename = "assetId")]
+    pub asset_id: Option<AvroBytes>,
+    pub amount: Option<i64>,
+    #[avro(rename = "amountU64")]
+    pub amount_u64: Option<String>,
+    #[avro(rename = "utxoId")]
+    pub utxo_id: Option<String>,
+    pub nonce: Option<AvroBytes>,
+    /// Predicate bytecode, null for inputs signed by a witness
+    pub predicate: Option<AvroBytes>,
+    #[avro(rename = "predicateData")]
+    pub predicate_data: Option<AvroBytes>,
+    #[avro(rename = "predicateGasUsed")]
+    pub predicate_gas_used: Option<i64>,
+    #[avro(rename = "predicateGasUsedU64")]
+    pub predicate_gas_used_u64: Option<String>,
+    #[avro(rename = "witnessIndex")]
+    pub witness_index: Option<i64>,
+}
+
+impl AvroInput {
+    /// Rows of the inputs of `tx`, the `tx_index`-th transaction of `block`
+    pub fn rows(block: &Block, tx_index: usize, tx: &Transaction) -> Vec<Self> {
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        tx.inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| Self {
+                block_height: Some(block.height.0 as i64),
+                block_time: Some(block_time),
+                transaction_id: Some(tx.id.clone().into()),
+                tx_index: Some(tx_index as i64),
+                input_index: Some(index as i64),
+                ..Self::new(input)
+            })
+            .collect()
+    }
+
+    /// Row of a single input, without its block and transaction coordinates
+    pub fn new(input: &inputs::Input) -> Self {
+        let predicate = |predicate: &HexData, data: &HexData| {
+            if predicate.0.0.is_empty() {
+                (None, None)
+            } else {
+                (Some(predicate.clone().into()), Some(data.clone().into()))
+            }
+        };
+
+        match input {
+            inputs::Input::Contract(input) => Self {
+                input_type: Some("contract".to_string()),
+                contract_id: Some(input.contract_id.clone().into()),
+                utxo_id: Some(input.utxo_id.to_string()),
+                ..Default::default()
+            },
+            inputs::Input::Coin(input) => {
+                let (predicate, predicate_data) =
+                    predicate(&input.predicate, &input.predicate_data);
+                Self {
+                    input_type: Some("coin".to_string()),
+                    owner: Some(input.owner.clone().into()),
+                    asset_id: Some(input.asset_id.clone().into()),
+                    amount: Some(input.amount.0 as i64),
+                    amount_u64: Some(input.amount.0.to_string()),
+                    utxo_id: Some(input.utxo_id.to_string()),
+                    predicate,
+                    predicate_data,
+                    predicate_gas_used: Some(input.predicate_gas_used.0 as i64),
+                    predicate_gas_used_u64: Some(input.predicate_gas_used.0.to_string()),
+                    witness_index: Some(input.witness_index as i64),
+                    ..Default::default()
+                }
+            }
+            inputs::Input::Message(input) => {
+                let (predicate, predicate_data) =
+                    predicate(&input.predicate, &input.predicate_data);
+                Self {
+                    input_type: Some("message".to_string()),
+                    owner: Some(input.recipient.clone().into()),
+                    sender: Some(input.sender.clone().into()),
+                    amount: Some(input.amount.0 as i64),
+                    amount_u64: Some(input.amount.0.to_string()),
+                    nonce: Some(input.nonce.clone().into()),
+                    predicate,
+                    predicate_data,
+                    predicate_gas_used: Some(input.predicate_gas_used.0 as i64),
+                    predicate_gas_used_u64: Some(input.predicate_gas_used.0.to_string()),
+                    witness_index: Some(input.witness_index as i64),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use apache_avro::AvroSchema;
-    use fuel_streams_domains::inputs::types::MockInput;
+    use fuel_streams_domains::{
+        inputs::types::MockInput,
+        mocks::{
+            MockBlock,
+            MockTransaction,
+        },
+    };
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -250,6 +528,39 @@ mod tests {
         test_input_serialization(parser, avro_input);
     }
 
+    #[test]
+    fn test_avro_input_rows() {
+        let block = MockBlock::random();
+        let inputs = MockInput::all();
+        let tx = MockTransaction::script(inputs.clone(), vec![], vec![]);
+        let rows = AvroInput::rows(&block, 3, &tx);
+        assert_eq!(rows.len(), inputs.len());
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroInput>().unwrap();
+        for (index, (row, input)) in rows.iter().zip(&inputs).enumerate() {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            assert_eq!(row.transaction_id, Some(tx.id.clone().into()));
+            assert_eq!(row.tx_index, Some(3));
+            assert_eq!(row.input_index, Some(index as i64));
+            let has_predicate = match input {
+                inputs::Input::Coin(coin) => !coin.predicate.0.0.is_empty(),
+                inputs::Input::Message(message) => !message.predicate.0.0.is_empty(),
+                inputs::Input::Contract(_) => false,
+            };
+            assert_eq!(row.predicate.is_some(), has_predicate);
+            writer.append(row).unwrap();
+        }
+
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroInput>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+    }
+
     #[tokio::test]
     async fn write_input_schemas() {
         let schemas = [
@@ -257,6 +568,7 @@ mod tests {
             ("input_coin.json", InputCoin::get_schema()),
             ("input_message.json", InputMessage::get_schema()),
             ("inputs.json", Inputs::get_schema()),
+            ("input.json", AvroInput::get_schema()),
         ];
 
         write_schema_files(&schemas).await;
@@ -0,0 +1,128 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    blocks::Block,
+    messages::Message,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::helpers::AvroBytes;
+
+/// One row of the `messages` table: a bridge message imported from the DA
+/// layer or consumed by a transaction input
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroMessage {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    /// Position of the message among the message events of its block
+    #[avro(rename = "messageIndex")]
+    pub message_index: Option<i64>,
+    /// `imported` or `consumed`
+    #[avro(rename = "messageType")]
+    pub message_type: Option<String>,
+    pub sender: Option<AvroBytes>,
+    pub recipient: Option<AvroBytes>,
+    pub nonce: Option<AvroBytes>,
+    pub amount: Option<i64>,
+    #[avro(rename = "amountU64")]
+    pub amount_u64: Option<String>,
+    pub data: Option<AvroBytes>,
+    /// DA layer block the message originated from
+    #[avro(rename = "daHeight")]
+    pub da_height: Option<i64>,
+    #[avro(rename = "daHeightU64")]
+    pub da_height_u64: Option<String>,
+}
+
+impl AvroMessage {
+    /// Rows of the messages imported and consumed by `block`
+    pub fn rows(block: &Block, messages: &[Message]) -> Vec<Self> {
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        messages
+            .iter()
+            .map(|message| Self {
+                block_time: Some(block_time),
+                ..Self::new(message)
+            })
+            .collect()
+    }
+
+    /// Row of a single message, without its block time
+    pub fn new(message: &Message) -> Self {
+        Self {
+            block_height: Some(message.block_height.0 as i64),
+            block_time: None,
+            message_index: Some(message.message_index as i64),
+            message_type: Some(message.r#type.to_string()),
+            sender: Some(message.sender.clone().into()),
+            recipient: Some(message.recipient.clone().into()),
+            nonce: Some(message.nonce.clone().into()),
+            amount: Some(message.amount.0 as i64),
+            amount_u64: Some(message.amount.0.to_string()),
+            data: Some(message.data.clone().into()),
+            da_height: Some(message.da_height.0 as i64),
+            da_height_u64: Some(message.da_height.0.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockMessage,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::helpers::{
+        AvroParser,
+        write_schema_files,
+    };
+
+    #[test]
+    fn test_avro_message_rows() {
+        let block = MockBlock::random();
+        let mut messages = MockMessage::all();
+        for (index, message) in messages.iter_mut().enumerate() {
+            message.block_height = block.height;
+            message.message_index = index as u32;
+        }
+        messages[0].amount = u64::MAX.into();
+
+        let rows = AvroMessage::rows(&block, &messages);
+        assert_eq!(rows.len(), messages.len());
+        assert_eq!(rows[0].message_type.as_deref(), Some("imported"));
+        assert_eq!(rows[0].amount, Some(-1));
+        assert_eq!(rows[0].amount_u64.as_deref(), Some("18446744073709551615"));
+        assert_eq!(rows[1].message_type.as_deref(), Some("consumed"));
+        assert_eq!(rows[1].message_index, Some(1));
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroMessage>().unwrap();
+        for row in &rows {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            writer.append(row).unwrap();
+        }
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroMessage>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+    }
+
+    #[tokio::test]
+    async fn write_message_schemas() {
+        let schemas = [("message.json", AvroMessage::get_schema())];
+
+        write_schema_files(&schemas).await;
+    }
+}
@@ -1,13 +1,38 @@
+mod asset;
 mod block_header;
+mod block_stats;
 mod blocks;
+mod contract;
+mod decode;
+mod decoded_call;
+mod decoded_log;
 mod input;
+mod message;
 mod output;
+mod predicate;
 mod receipt;
+mod row;
 mod transaction;
+mod transfer;
+mod utxo;
 
+pub use asset::*;
 pub use block_header::*;
+pub use block_stats::*;
 pub use blocks::*;
+pub use contract::*;
+pub use decode::{
+    LOSSY_FIELDS,
+    RowDecodeError,
+};
+pub use decoded_call::*;
+pub use decoded_log::*;
 pub use input::*;
+pub use message::*;
 pub use output::*;
+pub use predicate::*;
 pub use receipt::*;
+pub use row::*;
 pub use transaction::*;
+pub use transfer::*;
+pub use utxo::*;
@@ -1,10 +1,28 @@
 use apache_avro::AvroSchema;
-use fuel_streams_domains::outputs;
+use fuel_streams_domains::{
+    blocks::Block,
+    outputs,
+    transactions::Transaction,
+};
+use fuel_streams_types::{
+    Address,
+    Amount,
+    AssetId,
+    Bytes32,
+    UtxoId,
+};
 use serde::{
     Deserialize,
     Serialize,
 };
 
+use super::decode::{
+    RowDecodeError,
+    bytes,
+    int,
+    required,
+    word,
+};
 use crate::helpers::AvroBytes;
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
@@ -14,6 +32,10 @@ pub struct OutputCoin {
     #[avro(rename = "assetId")]
     pub asset_id: Option<AvroBytes>,
     pub to: Option<AvroBytes>,
+    /// Lossless decimal text of `amount`, added in transactions schema v2
+    /// and absent from older files
+    #[avro(rename = "amountU64", default = "null")]
+    pub amount_u64: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
@@ -43,6 +65,10 @@ pub struct OutputChange {
     #[avro(rename = "assetId")]
     pub asset_id: Option<AvroBytes>,
     pub to: Option<AvroBytes>,
+    /// Lossless decimal text of `amount`, added in transactions schema v2
+    /// and absent from older files
+    #[avro(rename = "amountU64", default = "null")]
+    pub amount_u64: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
@@ -52,6 +78,10 @@ pub struct OutputVariable {
     #[avro(rename = "assetId")]
     pub asset_id: Option<AvroBytes>,
     pub to: Option<AvroBytes>,
+    /// Lossless decimal text of `amount`, added in transactions schema v2
+    /// and absent from older files
+    #[avro(rename = "amountU64", default = "null")]
+    pub amount_u64: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
@@ -77,6 +107,7 @@ impl OutputCoin {
             amount: Some(output.amount.0 as i64),
             asset_id: Some(output.asset_id.clone().into()),
             to: Some(output.to.clone().into()),
+            amount_u64: Some(output.amount.0.to_string()),
         }
     }
 }
@@ -106,6 +137,7 @@ impl OutputChange {
             amount: Some(output.amount.0 as i64),
             asset_id: Some(output.asset_id.clone().into()),
             to: Some(output.to.clone().into()),
+            amount_u64: Some(output.amount.0.to_string()),
         }
     }
 }
@@ -116,6 +148,7 @@ impl OutputVariable {
             amount: Some(output.amount.0 as i64),
             asset_id: Some(output.asset_id.clone().into()),
             to: Some(output.to.clone().into()),
+            amount_u64: Some(output.amount.0.to_string()),
         }
     }
 }
@@ -165,10 +198,233 @@ impl Outputs {
     }
 }
 
+impl TryFrom<OutputCoin> for outputs::OutputCoin {
+    type Error = RowDecodeError;
+
+    fn try_from(output: OutputCoin) -> Result<Self, Self::Error> {
+        Ok(Self {
+            amount: word(output.amount_u64, output.amount, "OutputCoin.amount")?,
+            asset_id: bytes(output.asset_id, "OutputCoin.assetId")?,
+            to: bytes(output.to, "OutputCoin.to")?,
+        })
+    }
+}
+
+impl TryFrom<OutputContract> for outputs::OutputContract {
+    type Error = RowDecodeError;
+
+    fn try_from(output: OutputContract) -> Result<Self, Self::Error> {
+        Ok(Self {
+            balance_root: bytes(output.balance_root, "OutputContract.balanceRoot")?,
+            input_index: int(output.input_index, "OutputContract.inputIndex")?,
+            state_root: bytes(output.state_root, "OutputContract.stateRoot")?,
+        })
+    }
+}
+
+impl TryFrom<OutputContractCreated> for outputs::OutputContractCreated {
+    type Error = RowDecodeError;
+
+    fn try_from(output: OutputContractCreated) -> Result<Self, Self::Error> {
+        Ok(Self {
+            contract_id: bytes(output.contract_id, "OutputContractCreated.contractId")?,
+            state_root: bytes(output.state_root, "OutputContractCreated.stateRoot")?,
+        })
+    }
+}
+
+impl TryFrom<OutputChange> for outputs::OutputChange {
+    type Error = RowDecodeError;
+
+    fn try_from(output: OutputChange) -> Result<Self, Self::Error> {
+        Ok(Self {
+            amount: word(output.amount_u64, output.amount, "OutputChange.amount")?,
+            asset_id: bytes(output.asset_id, "OutputChange.assetId")?,
+            to: bytes(output.to, "OutputChange.to")?,
+        })
+    }
+}
+
+impl TryFrom<OutputVariable> for outputs::OutputVariable {
+    type Error = RowDecodeError;
+
+    fn try_from(output: OutputVariable) -> Result<Self, Self::Error> {
+        Ok(Self {
+            amount: word(output.amount_u64, output.amount, "OutputVariable.amount")?,
+            asset_id: bytes(output.asset_id, "OutputVariable.assetId")?,
+            to: bytes(output.to, "OutputVariable.to")?,
+        })
+    }
+}
+
+/// Restores the outputs in transaction order, following `outputTypes`
+impl TryFrom<Outputs> for Vec<outputs::Output> {
+    type Error = RowDecodeError;
+
+    fn try_from(outputs: Outputs) -> Result<Self, Self::Error> {
+        let mut coins = outputs.coin_outputs.unwrap_or_default().into_iter();
+        let mut contracts = outputs.contract_outputs.unwrap_or_default().into_iter();
+        let mut changes = outputs.change_outputs.unwrap_or_default().into_iter();
+        let mut variables = outputs.variable_outputs.unwrap_or_default().into_iter();
+        let mut created = outputs
+            .contract_created_outputs
+            .unwrap_or_default()
+            .into_iter();
+
+        let mut decoded = Vec::new();
+        for output_type in outputs.output_types.unwrap_or_default() {
+            let output = match output_type.as_str() {
+                "coin" => outputs::Output::Coin(
+                    required(coins.next(), "Outputs.coinOutputs")?.try_into()?,
+                ),
+                "contract" => outputs::Output::Contract(
+                    required(contracts.next(), "Outputs.contractOutputs")?.try_into()?,
+                ),
+                "change" => outputs::Output::Change(
+                    required(changes.next(), "Outputs.changeOutputs")?.try_into()?,
+                ),
+                "variable" => outputs::Output::Variable(
+                    required(variables.next(), "Outputs.variableOutputs")?.try_into()?,
+                ),
+                "contract_created" => outputs::Output::ContractCreated(
+                    required(created.next(), "Outputs.contractCreatedOutputs")?
+                        .try_into()?,
+                ),
+                other => {
+                    return Err(RowDecodeError::invalid(
+                        "Outputs.outputTypes",
+                        format!("unknown output type {other}"),
+                    ));
+                }
+            };
+            decoded.push(output);
+        }
+
+        if coins.next().is_some()
+            || contracts.next().is_some()
+            || changes.next().is_some()
+            || variables.next().is_some()
+            || created.next().is_some()
+        {
+            return Err(RowDecodeError::invalid(
+                "Outputs.outputTypes",
+                "fewer types than outputs",
+            ));
+        }
+        Ok(decoded)
+    }
+}
+
+/// One row of the `outputs` table, flattened out of a transaction
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroOutput {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    /// Position of the output in its transaction
+    #[avro(rename = "outputIndex")]
+    pub output_index: Option<i64>,
+    #[avro(rename = "outputType")]
+    pub output_type: Option<String>,
+    /// Recipient of coin, change and variable outputs
+    pub to: Option<AvroBytes>,
+    #[avro(rename = "assetId")]
+    pub asset_id: Option<AvroBytes>,
+    pub amount: Option<i64>,
+    #[avro(rename = "amountU64")]
+    pub amount_u64: Option<String>,
+    /// Id of the UTXO the output creates, spent by a later input
+    #[avro(rename = "utxoId")]
+    pub utxo_id: Option<String>,
+    #[avro(rename = "contractId")]
+    pub contract_id: Option<AvroBytes>,
+    /// Contract input the output belongs to
+    #[avro(rename = "inputIndex")]
+    pub input_index: Option<i64>,
+    #[avro(rename = "stateRoot")]
+    pub state_root: Option<AvroBytes>,
+}
+
+impl AvroOutput {
+    /// Rows of the outputs of `tx`, the `tx_index`-th transaction of `block`
+    pub fn rows(block: &Block, tx_index: usize, tx: &Transaction) -> Vec<Self> {
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        tx.outputs
+            .iter()
+            .enumerate()
+            .map(|(index, output)| {
+                let utxo_id = UtxoId {
+                    tx_id: Bytes32::from(&tx.id),
+                    output_index: index as u16,
+                };
+                Self {
+                    block_height: Some(block.height.0 as i64),
+                    block_time: Some(block_time),
+                    transaction_id: Some(tx.id.clone().into()),
+                    tx_index: Some(tx_index as i64),
+                    output_index: Some(index as i64),
+                    utxo_id: Some(utxo_id.to_string()),
+                    ..Self::new(output)
+                }
+            })
+            .collect()
+    }
+
+    /// Row of a single output, without its block and transaction coordinates
+    pub fn new(output: &outputs::Output) -> Self {
+        let transfer =
+            |output_type: &str, amount: &Amount, asset_id: &AssetId, to: &Address| Self {
+                output_type: Some(output_type.to_string()),
+                to: Some(to.clone().into()),
+                asset_id: Some(asset_id.clone().into()),
+                amount: Some(amount.0 as i64),
+                amount_u64: Some(amount.0.to_string()),
+                ..Default::default()
+            };
+
+        match output {
+            outputs::Output::Coin(output) => {
+                transfer("coin", &output.amount, &output.asset_id, &output.to)
+            }
+            outputs::Output::Change(output) => {
+                transfer("change", &output.amount, &output.asset_id, &output.to)
+            }
+            outputs::Output::Variable(output) => {
+                transfer("variable", &output.amount, &output.asset_id, &output.to)
+            }
+            outputs::Output::Contract(output) => Self {
+                output_type: Some("contract".to_string()),
+                input_index: Some(output.input_index as i64),
+                state_root: Some(output.state_root.clone().into()),
+                ..Default::default()
+            },
+            outputs::Output::ContractCreated(output) => Self {
+                output_type: Some("contract_created".to_string()),
+                contract_id: Some(output.contract_id.clone().into()),
+                state_root: Some(output.state_root.clone().into()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use apache_avro::AvroSchema;
-    use fuel_streams_domains::outputs::types::MockOutput;
+    use fuel_streams_domains::{
+        mocks::{
+            MockBlock,
+            MockTransaction,
+        },
+        outputs::types::MockOutput,
+    };
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -246,6 +502,42 @@ mod tests {
         test_output_serialization(parser, avro_output);
     }
 
+    #[test]
+    fn test_avro_output_rows() {
+        let block = MockBlock::random();
+        let outputs = MockOutput::all();
+        let tx = MockTransaction::script(vec![], outputs.clone(), vec![]);
+        let rows = AvroOutput::rows(&block, 0, &tx);
+        assert_eq!(rows.len(), outputs.len());
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroOutput>().unwrap();
+        for (index, row) in rows.iter().enumerate() {
+            assert_eq!(row.tx_index, Some(0));
+            assert_eq!(row.output_index, Some(index as i64));
+            let utxo_id = UtxoId {
+                tx_id: Bytes32::from(&tx.id),
+                output_index: index as u16,
+            };
+            assert_eq!(row.utxo_id, Some(utxo_id.to_string()));
+            writer.append(row).unwrap();
+        }
+
+        let coin = rows
+            .iter()
+            .find(|row| row.output_type.as_deref() == Some("coin"))
+            .unwrap();
+        assert_eq!(coin.amount_u64.as_deref(), Some("1000"));
+
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroOutput>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+    }
+
     #[tokio::test]
     async fn write_output_schemas() {
         // Write schemas for all output types
@@ -259,6 +551,7 @@ mod tests {
                 OutputContractCreated::get_schema(),
             ),
             ("outputs.json", Outputs::get_schema()),
+            ("output.json", AvroOutput::get_schema()),
         ];
 
         write_schema_files(&schemas).await;
@@ -0,0 +1,181 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    blocks::Block,
+    predicates::Predicate,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::helpers::AvroBytes;
+
+/// One row of the `predicates` table: an input spent by a predicate
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroPredicate {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    /// Position of the input in its transaction
+    #[avro(rename = "inputIndex")]
+    pub input_index: Option<i64>,
+    /// Root of the bytecode, the owner of the spent coin or message
+    #[avro(rename = "predicateAddress")]
+    pub predicate_address: Option<AvroBytes>,
+    /// Null for message inputs, which carry the base asset
+    #[avro(rename = "assetId")]
+    pub asset_id: Option<AvroBytes>,
+    #[avro(rename = "predicateGasUsed")]
+    pub predicate_gas_used: Option<i64>,
+    #[avro(rename = "predicateGasUsedU64")]
+    pub predicate_gas_used_u64: Option<String>,
+    /// SHA-256 of the bytecode, the key of `predicate_bytecodes`
+    #[avro(rename = "bytecodeHash")]
+    pub bytecode_hash: Option<AvroBytes>,
+    #[avro(rename = "bytecodeLength")]
+    pub bytecode_length: Option<i64>,
+    /// Blob loaded by a loader predicate
+    #[avro(rename = "blobId")]
+    pub blob_id: Option<AvroBytes>,
+}
+
+impl AvroPredicate {
+    /// Rows of the predicates of the transactions of `block`
+    pub fn rows(block: &Block, predicates: &[Predicate]) -> Vec<Self> {
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        predicates
+            .iter()
+            .map(|predicate| Self {
+                block_height: Some(block.height.0 as i64),
+                block_time: Some(block_time),
+                ..Self::new(predicate)
+            })
+            .collect()
+    }
+
+    /// Row of a single predicate, without its block coordinates
+    pub fn new(predicate: &Predicate) -> Self {
+        Self {
+            block_height: None,
+            block_time: None,
+            transaction_id: Some(predicate.tx_id.clone().into()),
+            tx_index: Some(predicate.tx_index as i64),
+            input_index: Some(predicate.input_index as i64),
+            predicate_address: Some(predicate.predicate_address.clone().into()),
+            asset_id: predicate.asset_id.clone().map(Into::into),
+            predicate_gas_used: Some(predicate.predicate_gas_used.0 as i64),
+            predicate_gas_used_u64: Some(predicate.predicate_gas_used.0.to_string()),
+            bytecode_hash: Some(predicate.bytecode_hash().into()),
+            bytecode_length: Some(predicate.predicate_bytecode.0.0.len() as i64),
+            blob_id: predicate.blob_id.clone().map(Into::into),
+        }
+    }
+}
+
+/// One row of the `predicate_bytecodes` dimension: a distinct predicate
+/// bytecode, written once per batch by the first block spending it
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroPredicateBytecode {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "bytecodeHash")]
+    pub bytecode_hash: Option<AvroBytes>,
+    pub bytecode: Option<AvroBytes>,
+    #[avro(rename = "bytecodeLength")]
+    pub bytecode_length: Option<i64>,
+    #[avro(rename = "predicateAddress")]
+    pub predicate_address: Option<AvroBytes>,
+    #[avro(rename = "blobId")]
+    pub blob_id: Option<AvroBytes>,
+}
+
+impl AvroPredicateBytecode {
+    /// Row of the bytecode of `predicate`, first seen in `block`
+    pub fn new(block: &Block, predicate: &Predicate) -> Self {
+        Self {
+            block_height: Some(block.height.0 as i64),
+            block_time: Some(block.header.get_timestamp_utc().timestamp()),
+            bytecode_hash: Some(predicate.bytecode_hash().into()),
+            bytecode: Some(predicate.predicate_bytecode.clone().into()),
+            bytecode_length: Some(predicate.predicate_bytecode.0.0.len() as i64),
+            predicate_address: Some(predicate.predicate_address.clone().into()),
+            blob_id: predicate.blob_id.clone().map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockInput,
+        MockTransaction,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::helpers::{
+        AvroParser,
+        write_schema_files,
+    };
+
+    #[test]
+    fn test_avro_predicate_rows() {
+        let block = MockBlock::random();
+        let inputs = vec![
+            MockInput::contract(),
+            MockInput::coin_predicate(),
+            MockInput::message_coin_predicate(),
+        ];
+        let tx = MockTransaction::script(inputs, vec![], vec![]);
+        let predicates = Predicate::from_transaction(1, &tx);
+        let rows = AvroPredicate::rows(&block, &predicates);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].input_index, Some(1));
+        assert_eq!(rows[0].predicate_gas_used_u64.as_deref(), Some("1000"));
+        assert_eq!(rows[1].asset_id, None);
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroPredicate>().unwrap();
+        for row in &rows {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            assert_eq!(row.tx_index, Some(1));
+            writer.append(row).unwrap();
+        }
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroPredicate>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+
+        let bytecode = AvroPredicateBytecode::new(&block, &predicates[0]);
+        assert_eq!(bytecode.bytecode_hash, rows[0].bytecode_hash);
+        assert_eq!(bytecode.bytecode_length, rows[0].bytecode_length);
+    }
+
+    #[tokio::test]
+    async fn write_predicate_schemas() {
+        let schemas = [
+            ("predicate.json", AvroPredicate::get_schema()),
+            (
+                "predicate_bytecode.json",
+                AvroPredicateBytecode::get_schema(),
+            ),
+        ];
+
+        write_schema_files(&schemas).await;
+    }
+}
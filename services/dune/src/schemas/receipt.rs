@@ -1,12 +1,14 @@
 use apache_avro::AvroSchema;
 use fuel_streams_domains::{
     blocks::Block,
-    receipts::Receipt,
+    receipts::*,
     transactions::Transaction,
 };
 use fuel_streams_types::{
     FuelCoreScriptExecutionResult,
     FuelCoreWord,
+    HexData,
+    PanicInstruction,
     ScriptExecutionResult,
 };
 use serde::{
@@ -14,6 +16,16 @@ use serde::{
     Serialize,
 };
 
+use super::decode::{
+    RowDecodeError,
+    bytes,
+    int,
+    parse,
+    required,
+    unsigned,
+    word,
+    word_opt,
+};
 use crate::helpers::AvroBytes;
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
@@ -45,6 +57,9 @@ pub struct AvroReceipt {
     pub nonce: Option<AvroBytes>,
     pub param1: Option<i64>,
     pub param2: Option<i64>,
+    /// Added in schema v2, absent from older files
+    #[avro(default = "null")]
+    pub pc: Option<i64>,
     pub ptr: Option<i64>,
     pub ra: Option<i64>,
     pub rb: Option<i64>,
@@ -65,6 +80,38 @@ pub struct AvroReceipt {
     #[avro(rename = "toAddress")]
     pub to_address: Option<String>,
     pub val: Option<i64>,
+    // Lossless decimal text of the unsigned 64-bit columns above, added in
+    // schema v3 and absent from older files
+    #[avro(rename = "amountU64", default = "null")]
+    pub amount_u64: Option<String>,
+    #[avro(rename = "gasU64", default = "null")]
+    pub gas_u64: Option<String>,
+    #[avro(rename = "gasUsedU64", default = "null")]
+    pub gas_used_u64: Option<String>,
+    #[avro(rename = "isU64", default = "null")]
+    pub is_u64: Option<String>,
+    #[avro(rename = "lenU64", default = "null")]
+    pub len_u64: Option<String>,
+    #[avro(rename = "param1U64", default = "null")]
+    pub param1_u64: Option<String>,
+    #[avro(rename = "param2U64", default = "null")]
+    pub param2_u64: Option<String>,
+    #[avro(rename = "pcU64", default = "null")]
+    pub pc_u64: Option<String>,
+    #[avro(rename = "ptrU64", default = "null")]
+    pub ptr_u64: Option<String>,
+    #[avro(rename = "raU64", default = "null")]
+    pub ra_u64: Option<String>,
+    #[avro(rename = "rbU64", default = "null")]
+    pub rb_u64: Option<String>,
+    #[avro(rename = "rcU64", default = "null")]
+    pub rc_u64: Option<String>,
+    #[avro(rename = "rdU64", default = "null")]
+    pub rd_u64: Option<String>,
+    #[avro(rename = "resultU64", default = "null")]
+    pub result_u64: Option<String>,
+    #[avro(rename = "valU64", default = "null")]
+    pub val_u64: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -203,6 +250,21 @@ impl AvroReceipt {
             _ => None,
         };
 
+        let pc = match receipt {
+            Receipt::Call(r) => Some(r.pc.0 as i64),
+            Receipt::Return(r) => Some(r.pc.0 as i64),
+            Receipt::ReturnData(r) => Some(r.pc.0 as i64),
+            Receipt::Panic(r) => Some(r.pc.0 as i64),
+            Receipt::Revert(r) => Some(r.pc.0 as i64),
+            Receipt::Log(r) => Some(r.pc.0 as i64),
+            Receipt::LogData(r) => Some(r.pc.0 as i64),
+            Receipt::Transfer(r) => Some(r.pc.0 as i64),
+            Receipt::TransferOut(r) => Some(r.pc.0 as i64),
+            Receipt::Mint(r) => Some(r.pc.0 as i64),
+            Receipt::Burn(r) => Some(r.pc.0 as i64),
+            _ => None,
+        };
+
         let ptr = match receipt {
             Receipt::ReturnData(r) => Some(r.ptr.0 as i64),
             Receipt::LogData(r) => Some(r.ptr.0 as i64),
@@ -332,6 +394,7 @@ impl AvroReceipt {
             nonce,
             param1,
             param2,
+            pc,
             ptr,
             ra,
             rb,
@@ -347,6 +410,21 @@ impl AvroReceipt {
             to,
             to_address,
             val,
+            amount_u64: unsigned(amount),
+            gas_u64: unsigned(gas),
+            gas_used_u64: unsigned(gas_used),
+            is_u64: unsigned(is),
+            len_u64: unsigned(len),
+            param1_u64: unsigned(param1),
+            param2_u64: unsigned(param2),
+            pc_u64: unsigned(pc),
+            ptr_u64: unsigned(ptr),
+            ra_u64: unsigned(ra),
+            rb_u64: unsigned(rb),
+            rc_u64: unsigned(rc),
+            rd_u64: unsigned(rd),
+            result_u64: unsigned(result),
+            val_u64: unsigned(val),
         }
     }
 }
@@ -365,6 +443,153 @@ impl From<(&Block, &Transaction, &Receipt)> for AvroReceipt {
     }
 }
 
+/// Decodes a receipt row. Program counters decode as 0 from files written
+/// before schema v2, unsigned values are read from their `...U64` columns when
+/// present.
+impl TryFrom<AvroReceipt> for Receipt {
+    type Error = RowDecodeError;
+
+    fn try_from(row: AvroReceipt) -> Result<Self, Self::Error> {
+        let pc = word_opt(row.pc_u64, row.pc, "AvroReceipt.pc")?.unwrap_or_default();
+        let data = |data: Option<String>| {
+            data.map(|data| parse::<HexData>(&data, "AvroReceipt.data"))
+                .transpose()
+        };
+        let receipt_type = required(row.receipt_type, "AvroReceipt.receiptType")?;
+
+        let receipt = match receipt_type.as_str() {
+            "call" => Receipt::Call(CallReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                to: bytes(row.to, "AvroReceipt.to")?,
+                amount: word(row.amount_u64, row.amount, "AvroReceipt.amount")?,
+                asset_id: bytes(row.asset_id, "AvroReceipt.assetId")?,
+                gas: word(row.gas_u64, row.gas, "AvroReceipt.gas")?,
+                param1: word(row.param1_u64, row.param1, "AvroReceipt.param1")?,
+                param2: word(row.param2_u64, row.param2, "AvroReceipt.param2")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+            }),
+            "return" => Receipt::Return(ReturnReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                val: word(row.val_u64, row.val, "AvroReceipt.val")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+            }),
+            "return_data" => Receipt::ReturnData(ReturnDataReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                ptr: word(row.ptr_u64, row.ptr, "AvroReceipt.ptr")?,
+                len: word(row.len_u64, row.len, "AvroReceipt.len")?,
+                digest: bytes(row.digest, "AvroReceipt.digest")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+                data: data(row.data)?,
+            }),
+            "panic" => Receipt::Panic(PanicReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                reason: PanicInstruction {
+                    reason: required(row.reason_reason, "AvroReceipt.reasonReason")?,
+                    instruction: int(
+                        row.reason_instruction,
+                        "AvroReceipt.reasonInstruction",
+                    )?,
+                },
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+                contract_id: None,
+            }),
+            "revert" => Receipt::Revert(RevertReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                ra: word(row.ra_u64, row.ra, "AvroReceipt.ra")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+            }),
+            "log" => Receipt::Log(LogReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                ra: word(row.ra_u64, row.ra, "AvroReceipt.ra")?,
+                rb: word(row.rb_u64, row.rb, "AvroReceipt.rb")?,
+                rc: word(row.rc_u64, row.rc, "AvroReceipt.rc")?,
+                rd: word(row.rd_u64, row.rd, "AvroReceipt.rd")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+            }),
+            "log_data" => Receipt::LogData(LogDataReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                ra: word(row.ra_u64, row.ra, "AvroReceipt.ra")?,
+                rb: word(row.rb_u64, row.rb, "AvroReceipt.rb")?,
+                ptr: word(row.ptr_u64, row.ptr, "AvroReceipt.ptr")?,
+                len: word(row.len_u64, row.len, "AvroReceipt.len")?,
+                digest: bytes(row.digest, "AvroReceipt.digest")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+                data: data(row.data)?,
+            }),
+            "transfer" => Receipt::Transfer(TransferReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                to: bytes(row.to, "AvroReceipt.to")?,
+                amount: word(row.amount_u64, row.amount, "AvroReceipt.amount")?,
+                asset_id: bytes(row.asset_id, "AvroReceipt.assetId")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+            }),
+            "transfer_out" => Receipt::TransferOut(TransferOutReceipt {
+                id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                to: parse(
+                    &required(row.to_address, "AvroReceipt.toAddress")?,
+                    "AvroReceipt.toAddress",
+                )?,
+                amount: word(row.amount_u64, row.amount, "AvroReceipt.amount")?,
+                asset_id: bytes(row.asset_id, "AvroReceipt.assetId")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+            }),
+            "script_result" => Receipt::ScriptResult(ScriptResultReceipt {
+                // Same codes as `FuelCoreWord::from(FuelCoreScriptExecutionResult)`
+                result: match word::<u64>(
+                    row.result_u64,
+                    row.result,
+                    "AvroReceipt.result",
+                )? {
+                    0 => ScriptExecutionResult::Success,
+                    1 => ScriptExecutionResult::Revert,
+                    2 => ScriptExecutionResult::Panic,
+                    code => ScriptExecutionResult::GenericFailure(code),
+                },
+                gas_used: word(row.gas_used_u64, row.gas_used, "AvroReceipt.gasUsed")?,
+            }),
+            "message_out" => Receipt::MessageOut(MessageOutReceipt {
+                sender: bytes(row.sender, "AvroReceipt.sender")?,
+                recipient: bytes(row.recipient, "AvroReceipt.recipient")?,
+                amount: word(row.amount_u64, row.amount, "AvroReceipt.amount")?,
+                nonce: bytes(row.nonce, "AvroReceipt.nonce")?,
+                len: word(row.len_u64, row.len, "AvroReceipt.len")?,
+                digest: bytes(row.digest, "AvroReceipt.digest")?,
+                data: data(row.data)?,
+            }),
+            "mint" => Receipt::Mint(MintReceipt {
+                sub_id: bytes(row.sub_id, "AvroReceipt.subId")?,
+                contract_id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                val: word(row.val_u64, row.val, "AvroReceipt.val")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+            }),
+            "burn" => Receipt::Burn(BurnReceipt {
+                sub_id: bytes(row.sub_id, "AvroReceipt.subId")?,
+                contract_id: bytes(row.contract_id, "AvroReceipt.contractId")?,
+                val: word(row.val_u64, row.val, "AvroReceipt.val")?,
+                pc,
+                is: word(row.is_u64, row.is, "AvroReceipt.is")?,
+            }),
+            other => {
+                return Err(RowDecodeError::invalid(
+                    "AvroReceipt.receiptType",
+                    format!("unknown receipt type {other}"),
+                ));
+            }
+        };
+        Ok(receipt)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use apache_avro::AvroSchema;
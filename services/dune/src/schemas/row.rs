@@ -0,0 +1,291 @@
+use chrono::{
+    DateTime,
+    NaiveDate,
+};
+
+use super::{
+    AvroAssetEvent,
+    AvroAssetSupply,
+    AvroBlock,
+    AvroBlockStats,
+    AvroContract,
+    AvroDecodedCall,
+    AvroDecodedLog,
+    AvroInput,
+    AvroMessage,
+    AvroOutput,
+    AvroPredicate,
+    AvroPredicateBytecode,
+    AvroReceipt,
+    AvroTransaction,
+    AvroTransfer,
+    AvroUtxo,
+    Policies,
+    decode::backfill,
+};
+
+/// Block coordinates shared by every exported table row.
+pub trait AvroRow {
+    /// Version of the Avro schema, bumped on every schema change and used to
+    /// key the published `.avsc` files
+    const SCHEMA_VERSION: u32 = 1;
+
+    /// Serialized name of the block height column
+    const HEIGHT_COLUMN: &'static str = "blockHeight";
+
+    /// Serialized name of the block time column
+    const TIME_COLUMN: &'static str = "blockTime";
+
+    /// Height of the block the row belongs to
+    fn block_height(&self) -> Option<i64>;
+
+    /// Unix timestamp (seconds) of the block the row belongs to
+    fn block_time(&self) -> Option<i64>;
+
+    /// UTC day of the block the row belongs to
+    fn block_date(&self) -> Option<NaiveDate> {
+        self.block_time()
+            .and_then(|time| DateTime::from_timestamp(time, 0))
+            .map(|time| time.date_naive())
+    }
+
+    /// Fills the columns that later schema versions derive from older ones,
+    /// for rows read from files written before them
+    fn upgrade(&mut self) {}
+}
+
+impl AvroRow for AvroBlock {
+    /// v2 adds the `daHeightU64` decimal column
+    const SCHEMA_VERSION: u32 = 2;
+    const HEIGHT_COLUMN: &'static str = "height";
+    const TIME_COLUMN: &'static str = "time";
+
+    fn block_height(&self) -> Option<i64> {
+        self.height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.time
+    }
+
+    fn upgrade(&mut self) {
+        backfill(&mut self.da_height_u64, self.da_height);
+    }
+}
+
+impl AvroRow for AvroTransaction {
+    /// v2 adds the `...U64` decimal columns, v3 the gas and fee columns
+    const SCHEMA_VERSION: u32 = 3;
+
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+
+    fn upgrade(&mut self) {
+        backfill(&mut self.mint_amount_u64, self.mint_amount);
+        backfill(&mut self.mint_gas_price_u64, self.mint_gas_price);
+        backfill(&mut self.script_gas_limit_u64, self.script_gas_limit);
+        if let Some(Policies {
+            max_fee,
+            tip,
+            witness_limit,
+            max_fee_u64,
+            tip_u64,
+            witness_limit_u64,
+            ..
+        }) = &mut self.policies
+        {
+            backfill(max_fee_u64, *max_fee);
+            backfill(tip_u64, *tip);
+            backfill(witness_limit_u64, *witness_limit);
+        }
+        for input in self.inputs.coin_inputs.iter_mut().flatten() {
+            backfill(&mut input.amount_u64, input.amount);
+            backfill(&mut input.predicate_gas_used_u64, input.predicate_gas_used);
+        }
+        for input in self.inputs.message_inputs.iter_mut().flatten() {
+            backfill(&mut input.amount_u64, input.amount);
+            backfill(&mut input.predicate_gas_used_u64, input.predicate_gas_used);
+        }
+        let outputs = &mut self.outputs;
+        for output in outputs.coin_outputs.iter_mut().flatten() {
+            backfill(&mut output.amount_u64, output.amount);
+        }
+        for output in outputs.change_outputs.iter_mut().flatten() {
+            backfill(&mut output.amount_u64, output.amount);
+        }
+        for output in outputs.variable_outputs.iter_mut().flatten() {
+            backfill(&mut output.amount_u64, output.amount);
+        }
+    }
+}
+
+impl AvroRow for AvroReceipt {
+    /// v2 adds the `pc` column, v3 the `...U64` decimal columns
+    const SCHEMA_VERSION: u32 = 3;
+
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+
+    fn upgrade(&mut self) {
+        let columns = [
+            (&mut self.amount_u64, self.amount),
+            (&mut self.gas_u64, self.gas),
+            (&mut self.gas_used_u64, self.gas_used),
+            (&mut self.is_u64, self.is),
+            (&mut self.len_u64, self.len),
+            (&mut self.param1_u64, self.param1),
+            (&mut self.param2_u64, self.param2),
+            (&mut self.pc_u64, self.pc),
+            (&mut self.ptr_u64, self.ptr),
+            (&mut self.ra_u64, self.ra),
+            (&mut self.rb_u64, self.rb),
+            (&mut self.rc_u64, self.rc),
+            (&mut self.rd_u64, self.rd),
+            (&mut self.result_u64, self.result),
+            (&mut self.val_u64, self.val),
+        ];
+        for (text, value) in columns {
+            backfill(text, value);
+        }
+    }
+}
+
+impl AvroRow for AvroInput {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroOutput {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroUtxo {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroMessage {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroPredicate {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroPredicateBytecode {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroContract {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroAssetEvent {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroAssetSupply {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroTransfer {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroDecodedLog {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroDecodedCall {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
+
+impl AvroRow for AvroBlockStats {
+    fn block_height(&self) -> Option<i64> {
+        self.block_height
+    }
+
+    fn block_time(&self) -> Option<i64> {
+        self.block_time
+    }
+}
@@ -3,6 +3,18 @@ use super::{
     Inputs,
     OutputContract,
     Outputs,
+    decode::{
+        RowDecodeError,
+        bytes,
+        bytes_opt,
+        bytes_vec,
+        int,
+        int_opt,
+        parse,
+        required,
+        unsigned,
+        word_opt,
+    },
 };
 use crate::helpers::AvroBytes;
 use apache_avro::AvroSchema;
@@ -12,11 +24,15 @@ use fuel_streams_domains::{
     transactions::Transaction,
 };
 use fuel_streams_types::{
+    ContractId,
     FuelCoreUpgradePurpose,
+    HexData,
     Policies as DomainPolicies,
     StorageSlot,
+    TransactionType,
     TxPointer as CoreTxPointer,
     UpgradePurpose as DomainUpgradePurpose,
+    WrappedU32,
 };
 use serde::{
     Deserialize,
@@ -41,6 +57,21 @@ impl From<&CoreTxPointer> for TxPointer {
     }
 }
 
+impl TryFrom<TxPointer> for CoreTxPointer {
+    type Error = RowDecodeError;
+
+    fn try_from(tx_pointer: TxPointer) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_height: int::<_, u32>(
+                tx_pointer.block_height,
+                "TxPointer.blockHeight",
+            )?
+            .into(),
+            tx_index: int(tx_pointer.tx_index, "TxPointer.txIndex")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Policies {
@@ -50,19 +81,51 @@ pub struct Policies {
     pub tip: Option<i64>,
     #[avro(rename = "witnessLimit")]
     pub witness_limit: Option<i64>,
+    // Lossless decimal text of `maxFee`, `tip` and `witnessLimit`, added in
+    // transactions schema v2 and absent from older files
+    #[avro(rename = "maxFeeU64", default = "null")]
+    pub max_fee_u64: Option<String>,
+    #[avro(rename = "tipU64", default = "null")]
+    pub tip_u64: Option<String>,
+    #[avro(rename = "witnessLimitU64", default = "null")]
+    pub witness_limit_u64: Option<String>,
 }
 
 impl Policies {
     pub fn new(policies: &DomainPolicies) -> Self {
+        let max_fee: Option<i64> = policies.max_fee.map(|f| f.into());
+        let tip: Option<i64> = policies.tip.map(|t| t.into());
+        let witness_limit: Option<i64> = policies.witness_limit.map(|w| w.into());
         Self {
             maturity: policies.maturity.map(|m| m.into()),
-            max_fee: policies.max_fee.map(|f| f.into()),
-            tip: policies.tip.map(|t| t.into()),
-            witness_limit: policies.witness_limit.map(|w| w.into()),
+            max_fee,
+            tip,
+            witness_limit,
+            max_fee_u64: unsigned(max_fee),
+            tip_u64: unsigned(tip),
+            witness_limit_u64: unsigned(witness_limit),
         }
     }
 }
 
+impl TryFrom<Policies> for DomainPolicies {
+    type Error = RowDecodeError;
+
+    fn try_from(policies: Policies) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tip: word_opt(policies.tip_u64, policies.tip, "Policies.tip")?,
+            maturity: int_opt::<_, u32>(policies.maturity, "Policies.maturity")?
+                .map(WrappedU32::from),
+            witness_limit: word_opt(
+                policies.witness_limit_u64,
+                policies.witness_limit,
+                "Policies.witnessLimit",
+            )?,
+            max_fee: word_opt(policies.max_fee_u64, policies.max_fee, "Policies.maxFee")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpgradePurpose {
@@ -96,6 +159,30 @@ impl UpgradePurpose {
     }
 }
 
+impl TryFrom<UpgradePurpose> for DomainUpgradePurpose {
+    type Error = RowDecodeError;
+
+    fn try_from(purpose: UpgradePurpose) -> Result<Self, Self::Error> {
+        let purpose_type = required(purpose.purpose_type, "UpgradePurpose.purposeType")?;
+        let purpose = match purpose_type.as_str() {
+            "ConsensusParameters" => FuelCoreUpgradePurpose::ConsensusParameters {
+                witness_index: int(purpose.witness_index, "UpgradePurpose.witnessIndex")?,
+                checksum: bytes(purpose.checksum, "UpgradePurpose.checksum")?,
+            },
+            "StateTransition" => FuelCoreUpgradePurpose::StateTransition {
+                root: bytes(purpose.root, "UpgradePurpose.root")?,
+            },
+            other => {
+                return Err(RowDecodeError::invalid(
+                    "UpgradePurpose.purposeType",
+                    format!("unknown purpose {other}"),
+                ));
+            }
+        };
+        Ok(purpose.into())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AvroStorageSlot {
@@ -112,6 +199,17 @@ impl From<&StorageSlot> for AvroStorageSlot {
     }
 }
 
+impl TryFrom<AvroStorageSlot> for StorageSlot {
+    type Error = RowDecodeError;
+
+    fn try_from(slot: AvroStorageSlot) -> Result<Self, Self::Error> {
+        Ok(Self {
+            key: bytes(Some(slot.key), "AvroStorageSlot.key")?,
+            value: bytes(Some(slot.value), "AvroStorageSlot.value")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AvroTransaction {
@@ -204,6 +302,34 @@ pub struct AvroTransaction {
     pub inputs: Inputs,
     #[avro(rename = "outputs")]
     pub outputs: Outputs,
+    // Lossless decimal text of `mintAmount`, `mintGasPrice` and
+    // `scriptGasLimit`, added in schema v2 and absent from older files
+    #[avro(rename = "mintAmountU64", default = "null")]
+    pub mint_amount_u64: Option<String>,
+    #[avro(rename = "mintGasPriceU64", default = "null")]
+    pub mint_gas_price_u64: Option<String>,
+    #[avro(rename = "scriptGasLimitU64", default = "null")]
+    pub script_gas_limit_u64: Option<String>,
+    // Gas and fee paid by the transaction, added in schema v3 and absent from
+    // older files
+    #[avro(rename = "totalGas", default = "null")]
+    pub total_gas: Option<i64>,
+    #[avro(rename = "totalGasU64", default = "null")]
+    pub total_gas_u64: Option<String>,
+    #[avro(rename = "totalFee", default = "null")]
+    pub total_fee: Option<i64>,
+    #[avro(rename = "totalFeeU64", default = "null")]
+    pub total_fee_u64: Option<String>,
+    /// Gas used by the script, from its `ScriptResult` receipt
+    #[avro(rename = "gasUsed", default = "null")]
+    pub gas_used: Option<i64>,
+    #[avro(rename = "gasUsedU64", default = "null")]
+    pub gas_used_u64: Option<String>,
+    /// Tip paid to the block producer, null for mints
+    #[avro(default = "null")]
+    pub tip: Option<i64>,
+    #[avro(rename = "tipU64", default = "null")]
+    pub tip_u64: Option<String>,
 }
 
 impl AvroTransaction {
@@ -239,6 +365,23 @@ impl AvroTransaction {
             None
         };
 
+        let mint_amount = transaction
+            .mint_amount
+            .as_ref()
+            .map(|amount| amount.as_ref().to_owned() as i64);
+        let mint_gas_price = transaction
+            .mint_gas_price
+            .as_ref()
+            .map(|price| price.as_ref().to_owned() as i64);
+        let script_gas_limit = transaction
+            .script_gas_limit
+            .as_ref()
+            .map(|limit| limit.as_ref().to_owned() as i64);
+        let total_gas = transaction.total_gas.map(|gas| gas.0 as i64);
+        let total_fee = transaction.total_fee.map(|fee| fee.0 as i64);
+        let gas_used = transaction.gas_used.map(|gas| gas.0 as i64);
+        let tip = transaction.tip.map(|tip| tip.0 as i64);
+
         Self {
             block_height,
             block_time,
@@ -259,18 +402,12 @@ impl AvroTransaction {
                 .as_ref()
                 .map(|bid| bid.as_ref().to_vec().into()),
             maturity: transaction.maturity.map(Into::into),
-            mint_amount: transaction
-                .mint_amount
-                .as_ref()
-                .map(|amount| amount.as_ref().to_owned() as i64),
+            mint_amount,
             mint_asset_id: transaction
                 .mint_asset_id
                 .as_ref()
                 .map(|id| id.as_ref().to_vec().into()),
-            mint_gas_price: transaction
-                .mint_gas_price
-                .as_ref()
-                .map(|price| price.as_ref().to_owned() as i64),
+            mint_gas_price,
             receipts_root: transaction
                 .receipts_root
                 .as_ref()
@@ -279,10 +416,7 @@ impl AvroTransaction {
                 .salt
                 .as_ref()
                 .map(|s| s.as_ref().to_vec().into()),
-            script_gas_limit: transaction
-                .script_gas_limit
-                .as_ref()
-                .map(|limit| limit.as_ref().to_owned() as i64),
+            script_gas_limit,
             subsection_index: transaction.subsection_index.map(Into::into),
             subsections_number: transaction.subsections_number.map(Into::into),
             input_asset_ids: transaction
@@ -333,6 +467,17 @@ impl AvroTransaction {
             input_contracts,
             inputs,
             outputs,
+            mint_amount_u64: unsigned(mint_amount),
+            mint_gas_price_u64: unsigned(mint_gas_price),
+            script_gas_limit_u64: unsigned(script_gas_limit),
+            total_gas,
+            total_gas_u64: unsigned(total_gas),
+            total_fee,
+            total_fee_u64: unsigned(total_fee),
+            gas_used,
+            gas_used_u64: unsigned(gas_used),
+            tip,
+            tip_u64: unsigned(tip),
         }
     }
 }
@@ -350,11 +495,136 @@ impl From<(&Block, &Transaction)> for AvroTransaction {
     }
 }
 
+/// Decodes a transaction row. Receipts are exported to their own table, so
+/// the transaction decodes without them.
+impl TryFrom<AvroTransaction> for Transaction {
+    type Error = RowDecodeError;
+
+    fn try_from(tx: AvroTransaction) -> Result<Self, Self::Error> {
+        let r#type: TransactionType = parse(
+            &required(tx.r#type, "AvroTransaction.type")?,
+            "AvroTransaction.type",
+        )?;
+        let hex = |data: AvroBytes| HexData::from(data.0);
+
+        // Restored the way the domain derives them from the transaction inputs
+        let input_contracts = match r#type {
+            TransactionType::Mint => None,
+            _ => {
+                let mut ids: Vec<ContractId> =
+                    bytes_vec(tx.input_contracts, "AvroTransaction.inputContracts")?
+                        .unwrap_or_default();
+                ids.sort_by(|a, b| a.0.cmp(&b.0));
+                ids.dedup();
+                Some(ids)
+            }
+        };
+
+        Ok(Self {
+            id: bytes(tx.id, "AvroTransaction.id")?,
+            r#type,
+            bytecode_root: bytes_opt(tx.bytecode_root, "AvroTransaction.bytecodeRoot")?,
+            bytecode_witness_index: int_opt(
+                tx.bytecode_witness_index,
+                "AvroTransaction.bytecodeWitnessIndex",
+            )?,
+            blob_id: bytes_opt(tx.blob_id, "AvroTransaction.blobId")?,
+            input_asset_ids: bytes_vec(
+                tx.input_asset_ids,
+                "AvroTransaction.inputAssetIds",
+            )?,
+            input_contract: tx.input_contract.map(TryInto::try_into).transpose()?,
+            input_contracts,
+            inputs: tx.inputs.try_into()?,
+            output_contract: tx.output_contract.map(TryInto::try_into).transpose()?,
+            outputs: tx.outputs.try_into()?,
+            is_create: required(tx.is_create, "AvroTransaction.isCreate")?,
+            is_mint: required(tx.is_mint, "AvroTransaction.isMint")?,
+            is_script: required(tx.is_script, "AvroTransaction.isScript")?,
+            is_upgrade: required(tx.is_upgrade, "AvroTransaction.isUpgrade")?,
+            is_upload: required(tx.is_upload, "AvroTransaction.isUpload")?,
+            is_blob: required(tx.is_blob, "AvroTransaction.isBlob")?,
+            mint_amount: word_opt(
+                tx.mint_amount_u64,
+                tx.mint_amount,
+                "AvroTransaction.mintAmount",
+            )?,
+            mint_asset_id: bytes_opt(tx.mint_asset_id, "AvroTransaction.mintAssetId")?,
+            mint_gas_price: word_opt(
+                tx.mint_gas_price_u64,
+                tx.mint_gas_price,
+                "AvroTransaction.mintGasPrice",
+            )?,
+            proof_set: bytes_vec(tx.proof_set, "AvroTransaction.proofSet")?,
+            raw_payload: hex(required(tx.raw_payload, "AvroTransaction.rawPayload")?),
+            receipts_root: bytes_opt(tx.receipts_root, "AvroTransaction.receiptsRoot")?,
+            salt: bytes_opt(tx.salt, "AvroTransaction.salt")?,
+            script: tx.script.map(hex),
+            script_data: tx.script_data.map(hex),
+            script_gas_limit: word_opt(
+                tx.script_gas_limit_u64,
+                tx.script_gas_limit,
+                "AvroTransaction.scriptGasLimit",
+            )?,
+            status: parse(
+                &required(tx.status, "AvroTransaction.status")?,
+                "AvroTransaction.status",
+            )?,
+            storage_slots: tx
+                .storage_slots
+                .map(|slots| slots.into_iter().map(TryInto::try_into).collect())
+                .transpose()?,
+            subsection_index: int_opt(
+                tx.subsection_index,
+                "AvroTransaction.subsectionIndex",
+            )?,
+            subsections_number: int_opt(
+                tx.subsections_number,
+                "AvroTransaction.subsectionsNumber",
+            )?,
+            tx_pointer: tx.tx_pointer.map(TryInto::try_into).transpose()?,
+            upgrade_purpose: tx.upgrade_purpose.map(TryInto::try_into).transpose()?,
+            witnesses: tx
+                .witnesses
+                .map(|witnesses| witnesses.into_iter().map(hex).collect()),
+            receipts: Vec::new(),
+            policies: tx.policies.map(TryInto::try_into).transpose()?,
+            maturity: int_opt(tx.maturity, "AvroTransaction.maturity")?,
+            script_length: int_opt(tx.script_length, "AvroTransaction.scriptLength")?,
+            script_data_length: int_opt(
+                tx.script_data_length,
+                "AvroTransaction.scriptDataLength",
+            )?,
+            storage_slots_count: int(
+                tx.storage_slots_count,
+                "AvroTransaction.storageSlotsCount",
+            )?,
+            proof_set_count: int(tx.proof_set_count, "AvroTransaction.proofSetCount")?,
+            witnesses_count: int(tx.witnesses_count, "AvroTransaction.witnessesCount")?,
+            inputs_count: int(tx.inputs_count, "AvroTransaction.inputsCount")?,
+            outputs_count: int(tx.outputs_count, "AvroTransaction.outputsCount")?,
+            total_gas: word_opt(
+                tx.total_gas_u64,
+                tx.total_gas,
+                "AvroTransaction.totalGas",
+            )?,
+            total_fee: word_opt(
+                tx.total_fee_u64,
+                tx.total_fee,
+                "AvroTransaction.totalFee",
+            )?,
+            gas_used: word_opt(tx.gas_used_u64, tx.gas_used, "AvroTransaction.gasUsed")?,
+            tip: word_opt(tx.tip_u64, tx.tip, "AvroTransaction.tip")?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use fuel_streams_domains::{
         inputs::types::MockInput,
         outputs::types::MockOutput,
+        receipts::MockReceipt,
         transactions::MockTransaction,
     };
     use pretty_assertions::assert_eq;
@@ -534,6 +804,39 @@ mod tests {
         test_transaction_serialization(parser, avro_tx);
     }
 
+    #[test]
+    fn test_avro_transaction_fee_columns() {
+        let receipts = vec![MockReceipt::call(), MockReceipt::script_result()];
+        let tx = MockTransaction::script(vec![], vec![], receipts);
+        let avro_tx = AvroTransaction::new(&tx, Some(1), Some(0), None, None, None);
+        assert_eq!(avro_tx.total_gas_u64.as_deref(), Some("1500"));
+        assert_eq!(avro_tx.total_fee_u64.as_deref(), Some("300"));
+        assert_eq!(avro_tx.gas_used, Some(1000));
+        assert_eq!(avro_tx.tip_u64.as_deref(), Some("100"));
+
+        let decoded = Transaction::try_from(avro_tx.clone()).unwrap();
+        assert_eq!(decoded.total_gas, tx.total_gas);
+        assert_eq!(decoded.total_fee, tx.total_fee);
+        assert_eq!(decoded.gas_used, tx.gas_used);
+        assert_eq!(decoded.tip, tx.tip);
+
+        // Files written before schema v3 have none of the columns
+        let v2_tx = AvroTransaction {
+            total_gas: None,
+            total_gas_u64: None,
+            total_fee: None,
+            total_fee_u64: None,
+            gas_used: None,
+            gas_used_u64: None,
+            tip: None,
+            tip_u64: None,
+            ..avro_tx
+        };
+        let decoded = Transaction::try_from(v2_tx).unwrap();
+        assert_eq!(decoded.total_fee, None);
+        assert_eq!(decoded.gas_used, None);
+    }
+
     #[test]
     fn test_avro_transactions_all() {
         let parser = AvroParser::default();
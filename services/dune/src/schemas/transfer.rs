@@ -0,0 +1,136 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    blocks::Block,
+    transactions::Transaction,
+    transfers::Transfer,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::helpers::AvroBytes;
+
+/// One row of the `transfers` table: value moved by a transaction between
+/// contracts, addresses or to the L1
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroTransfer {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    /// Position of the receipt, or of the output for `address_to_address`
+    /// rows, in its transaction
+    pub index: Option<i64>,
+    /// `contract_to_contract`, `contract_to_address`, `address_to_address`
+    /// or `withdrawal`
+    pub kind: Option<String>,
+    /// Null for an address transfer whose inputs have several owners
+    pub from: Option<AvroBytes>,
+    pub to: Option<AvroBytes>,
+    /// Null for withdrawals, which carry the base asset
+    #[avro(rename = "assetId")]
+    pub asset_id: Option<AvroBytes>,
+    pub amount: Option<i64>,
+    #[avro(rename = "amountU64")]
+    pub amount_u64: Option<String>,
+}
+
+impl AvroTransfer {
+    /// Rows of the transfers of `tx`, the `tx_index`-th transaction of
+    /// `block`
+    pub fn rows(block: &Block, tx_index: usize, tx: &Transaction) -> Vec<Self> {
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        Transfer::from_transaction(tx_index as i32, tx)
+            .iter()
+            .map(|transfer| Self {
+                block_height: Some(block.height.0 as i64),
+                block_time: Some(block_time),
+                ..Self::new(transfer)
+            })
+            .collect()
+    }
+
+    /// Row of a single transfer, without its block coordinates
+    pub fn new(transfer: &Transfer) -> Self {
+        Self {
+            block_height: None,
+            block_time: None,
+            transaction_id: Some(transfer.tx_id.clone().into()),
+            tx_index: Some(transfer.tx_index as i64),
+            index: Some(transfer.index as i64),
+            kind: Some(transfer.kind.to_string()),
+            from: transfer.from.clone().map(Into::into),
+            to: Some(transfer.to.clone().into()),
+            asset_id: transfer.asset_id.clone().map(Into::into),
+            amount: Some(transfer.amount.0 as i64),
+            amount_u64: Some(transfer.amount.0.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockInput,
+        MockOutput,
+        MockReceipt,
+        MockTransaction,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::helpers::{
+        AvroParser,
+        write_schema_files,
+    };
+
+    #[test]
+    fn test_avro_transfer_rows() {
+        let block = MockBlock::random();
+        let inputs = vec![MockInput::coin_signed(None)];
+        let outputs = vec![MockOutput::coin(u64::MAX), MockOutput::variable(100)];
+        let receipts = vec![MockReceipt::transfer_out(), MockReceipt::message_out()];
+        let tx = MockTransaction::script(inputs, outputs, receipts);
+        let rows = AvroTransfer::rows(&block, 1, &tx);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].kind.as_deref(), Some("contract_to_address"));
+        assert_eq!(rows[1].kind.as_deref(), Some("withdrawal"));
+        assert_eq!(rows[1].asset_id, None);
+        assert_eq!(rows[2].kind.as_deref(), Some("address_to_address"));
+        assert_eq!(rows[2].amount, Some(-1));
+        assert_eq!(rows[2].amount_u64, Some(u64::MAX.to_string()));
+        assert!(rows[2].from.is_some());
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroTransfer>().unwrap();
+        for row in &rows {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            assert_eq!(row.transaction_id, Some(tx.id.clone().into()));
+            assert_eq!(row.tx_index, Some(1));
+            writer.append(row).unwrap();
+        }
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroTransfer>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+    }
+
+    #[tokio::test]
+    async fn write_transfer_schemas() {
+        let schemas = [("transfer.json", AvroTransfer::get_schema())];
+
+        write_schema_files(&schemas).await;
+    }
+}
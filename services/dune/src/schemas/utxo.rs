@@ -0,0 +1,150 @@
+use apache_avro::AvroSchema;
+use fuel_streams_domains::{
+    blocks::Block,
+    transactions::Transaction,
+    utxos::Utxo,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::helpers::AvroBytes;
+
+/// One row of the `utxos` table: a UTXO created by an output or spent by an
+/// input of a transaction
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, AvroSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvroUtxo {
+    #[avro(rename = "blockHeight")]
+    pub block_height: Option<i64>,
+    #[avro(rename = "blockTime")]
+    pub block_time: Option<i64>,
+    /// Creating transaction for unspent rows, consuming one for spent rows
+    #[avro(rename = "transactionId")]
+    pub transaction_id: Option<AvroBytes>,
+    /// Position of the transaction in its block
+    #[avro(rename = "txIndex")]
+    pub tx_index: Option<i64>,
+    /// `unspent` when created by an output, `spent` when consumed by an input
+    pub status: Option<String>,
+    #[avro(rename = "utxoType")]
+    pub utxo_type: Option<String>,
+    /// Transaction id and output index of the creating output
+    #[avro(rename = "utxoId")]
+    pub utxo_id: Option<String>,
+    pub to: Option<AvroBytes>,
+    pub amount: Option<i64>,
+    #[avro(rename = "amountU64")]
+    pub amount_u64: Option<String>,
+    #[avro(rename = "assetId")]
+    pub asset_id: Option<AvroBytes>,
+    #[avro(rename = "contractId")]
+    pub contract_id: Option<AvroBytes>,
+}
+
+impl AvroUtxo {
+    /// Rows of the UTXOs spent and created by `tx`, the `tx_index`-th
+    /// transaction of `block`
+    pub fn rows(block: &Block, tx_index: usize, tx: &Transaction) -> Vec<Self> {
+        let block_time = block.header.get_timestamp_utc().timestamp();
+        Utxo::from_transaction(tx)
+            .iter()
+            .map(|utxo| Self {
+                block_height: Some(block.height.0 as i64),
+                block_time: Some(block_time),
+                tx_index: Some(tx_index as i64),
+                ..Self::new(utxo)
+            })
+            .collect()
+    }
+
+    /// Row of a single UTXO, without its block coordinates
+    pub fn new(utxo: &Utxo) -> Self {
+        Self {
+            transaction_id: Some(utxo.tx_id.clone().into()),
+            status: Some(utxo.status.to_string()),
+            utxo_type: Some(utxo.r#type.to_string()),
+            utxo_id: Some(utxo.utxo_id.to_string()),
+            to: utxo.to.clone().map(Into::into),
+            amount: utxo.amount.map(|amount| amount.0 as i64),
+            amount_u64: utxo.amount.map(|amount| amount.0.to_string()),
+            asset_id: utxo.asset_id.clone().map(Into::into),
+            contract_id: utxo.contract_id.clone().map(Into::into),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::AvroSchema;
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockInput,
+        MockOutput,
+        MockTransaction,
+    };
+    use fuel_streams_types::{
+        Bytes32,
+        UtxoId,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::helpers::{
+        AvroParser,
+        write_schema_files,
+    };
+
+    #[test]
+    fn test_avro_utxo_rows() {
+        let block = MockBlock::random();
+        let tx = MockTransaction::script(MockInput::all(), MockOutput::all(), vec![]);
+        let rows = AvroUtxo::rows(&block, 2, &tx);
+        assert_eq!(rows.len(), Utxo::from_transaction(&tx).len());
+
+        let parser = AvroParser::default();
+        let mut writer = parser.writer_with_schema::<AvroUtxo>().unwrap();
+        for row in &rows {
+            assert_eq!(row.block_height, Some(block.height.0 as i64));
+            assert_eq!(row.transaction_id, Some(tx.id.clone().into()));
+            assert_eq!(row.tx_index, Some(2));
+            writer.append(row).unwrap();
+        }
+
+        let coin = rows
+            .iter()
+            .find(|row| row.utxo_type.as_deref() == Some("output_coin"))
+            .unwrap();
+        let index = tx
+            .outputs
+            .iter()
+            .position(|output| {
+                matches!(output, fuel_streams_domains::outputs::Output::Coin(_))
+            })
+            .unwrap();
+        let utxo_id = UtxoId {
+            tx_id: Bytes32::from(&tx.id),
+            output_index: index as u16,
+        };
+        assert_eq!(coin.status.as_deref(), Some("unspent"));
+        assert_eq!(coin.utxo_id, Some(utxo_id.to_string()));
+        assert_eq!(coin.amount_u64.as_deref(), Some("1000"));
+
+        let serialized = writer.into_inner().unwrap();
+        let deserialized = parser
+            .reader_with_schema::<AvroUtxo>()
+            .unwrap()
+            .deserialize(&serialized)
+            .unwrap();
+        assert_eq!(deserialized, rows);
+    }
+
+    #[tokio::test]
+    async fn write_utxo_schemas() {
+        let schemas = [("utxo.json", AvroUtxo::get_schema())];
+
+        write_schema_files(&schemas).await;
+    }
+}
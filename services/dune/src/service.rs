@@ -1,15 +1,34 @@
 use crate::{
     DuneError,
+    abi::AbiRegistry,
     alloc_counter,
+    assets::AssetSupplies,
     block_buffer::{
         DiskBuffer,
         FinalizedBatchFiles,
+        TableFile,
+    },
+    helpers::{
+        TableCodecs,
+        TableFormats,
+    },
+    postgres::{
+        OutputMode,
+        PostgresConfig,
+        PostgresSink,
     },
     processor::{
         Processor,
         StorageTypeConfig,
     },
-    s3::S3TableName,
+    roots,
+    s3::{
+        FuelNetwork,
+        KeyLayout,
+        S3TableName,
+        TableRow,
+        with_table_row,
+    },
     tracked::{
         TrackedFetcher,
         TrackedStream,
@@ -27,9 +46,17 @@ use fuel_core_services::{
 use futures::stream;
 use fuel_core_types::{
     fuel_tx::AssetId,
-    fuel_types::BlockHeight,
+    fuel_types::{
+        BlockHeight,
+        ChainId,
+    },
+    services::executor::Event,
 };
 use fuel_indexer_types::events::BlockEvent;
+use fuel_message_broker::{
+    BufferedPublisher,
+    JetStreamPublisher,
+};
 use fuel_receipts_manager::{
     adapters::graphql_event_adapter::{
         GraphqlEventAdapterConfig,
@@ -39,17 +66,24 @@ use fuel_receipts_manager::{
     port::FinalizedBlock,
 };
 use fuel_streams_domains::{
+    MsgPayload,
     blocks::Block,
+    messages::Message,
     transactions::Transaction,
 };
 use futures::StreamExt;
 use std::{
     cmp::Ordering,
     num::NonZeroUsize,
+    path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 use tokio::sync::watch;
 
+/// Time to wait before fetching a batch again after it failed to be written
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
 pub struct Config {
     pub url: url::Url,
     pub starting_height: BlockHeight,
@@ -58,11 +92,35 @@ pub struct Config {
     pub blocks_request_batch_size: usize,
     pub blocks_request_concurrency: usize,
     pub pending_blocks: usize,
+    pub codecs: TableCodecs,
+    pub formats: TableFormats,
+    pub key_layout: KeyLayout,
+    /// Commit an Iceberg snapshot for every uploaded table file
+    pub iceberg: bool,
+    pub output: OutputMode,
+    /// Required when `output` includes Postgres
+    pub postgres: Option<PostgresConfig>,
+    /// Publish a payload for every ingested block to this NATS server
+    pub nats_url: Option<url::Url>,
+    /// Recompute the Merkle roots of every ingested block from its rows
+    pub validate_roots: bool,
+    /// Directory of `<contract id>.json` ABIs whose logs are decoded
+    pub abi_dir: Option<PathBuf>,
+}
+
+pub type FetcherFactory = Arc<dyn Fn() -> GraphqlFetcher + Send + Sync>;
+
+/// A block of the stream together with the executor events of its execution,
+/// which `BlockEvent` doesn't carry
+pub struct IngestedBlock {
+    pub block: BlockEvent,
+    /// Executor events of the block, e.g. imported and consumed messages
+    pub events: Vec<Event>,
 }
 
 pub struct UninitializedTask {
     config: Config,
-    fetcher_factory: Arc<dyn Fn() -> GraphqlFetcher + Send + Sync>,
+    fetcher_factory: FetcherFactory,
     shared: SharedState,
 }
 
@@ -71,12 +129,19 @@ pub struct Task {
     /// Factory function to create new GraphqlFetcher instances on reconnection.
     /// We recreate the fetcher on each reconnection to avoid memory leaks from
     /// accumulated background tasks and channels in the external library.
-    fetcher_factory: Arc<dyn Fn() -> GraphqlFetcher + Send + Sync>,
+    fetcher_factory: FetcherFactory,
     blocks_stream: TrackedStream,
-    /// Disk-based block buffer that writes directly to Avro files
+    /// Disk-based block buffer that writes directly to table files
     buffer: DiskBuffer,
     processor: Processor,
+    output: OutputMode,
+    /// Postgres output, buffering its own smaller batches
+    postgres: Option<PostgresSink>,
+    /// JetStream publisher of the ingested blocks
+    publisher: Option<BufferedPublisher>,
+    validate_roots: bool,
     base_asset_id: AssetId,
+    chain_id: ChainId,
     batch_size: usize,
     /// Counter for periodic alloc_counter logging
     run_iterations: u64,
@@ -132,23 +197,76 @@ impl RunnableService for UninitializedTask {
         } = self;
 
         let client = FuelClient::new(&config.url)?;
-        let base_asset_id = *client
-            .chain_info()
+        let chain_info = client.chain_info().await?;
+        let base_asset_id = *chain_info.consensus_parameters.base_asset_id();
+        let chain_id = chain_info.consensus_parameters.chain_id();
+
+        let processor = Processor::new(config.storage_type)
             .await?
-            .consensus_parameters
-            .base_asset_id();
+            .with_layout(config.key_layout)
+            .with_formats(config.formats.clone())
+            .with_iceberg(config.iceberg);
+
+        let postgres = match (config.output.postgres(), &config.postgres) {
+            (true, Some(postgres)) => {
+                let network = FuelNetwork::load_from_env();
+                let sink = PostgresSink::connect(postgres, network.to_string()).await?;
+                sink.migrate().await?;
+                Some(sink)
+            }
+            (true, None) => {
+                anyhow::bail!("The {} output requires a Postgres config", config.output)
+            }
+            (false, _) => None,
+        };
 
-        let processor = Processor::new(config.storage_type).await?;
+        let publisher = match &config.nats_url {
+            Some(url) => {
+                let network = FuelNetwork::load_from_env();
+                let publisher =
+                    JetStreamPublisher::connect(url.as_str(), &network.to_string())
+                        .await?;
+                Some(BufferedPublisher::new(publisher))
+            }
+            None => None,
+        };
 
-        let current_height = processor
-            .load_latest_height()
-            .await?
+        let mut checkpoints = Vec::new();
+        let mut asset_supplies = AssetSupplies::default();
+        if config.output.files() {
+            // Refuse to export with a schema that breaks the published ones
+            processor.publish_schemas().await?;
+            checkpoints.push(processor.load_latest_height().await?);
+            asset_supplies = processor.load_asset_supplies().await?;
+        }
+        if let Some(sink) = &postgres {
+            checkpoints.push(sink.load_checkpoint().await?);
+        }
+
+        // Resume from the output that is furthest behind. Rewriting blocks the
+        // other output already has is harmless: range files are overwritten and
+        // Postgres rows are upserted.
+        let current_height = checkpoints
+            .into_iter()
+            .min()
+            .flatten()
             .unwrap_or(config.starting_height);
         shared.block_height.send_replace(current_height);
 
         // Create disk buffer for block accumulation
-        let buffer = DiskBuffer::new()?;
-        tracing::info!("Using disk buffer for block accumulation");
+        let mut buffer =
+            DiskBuffer::with_formats(config.codecs.clone(), config.formats.clone())?;
+        buffer.set_asset_supplies(asset_supplies);
+        if let Some(dir) = &config.abi_dir {
+            let abis = AbiRegistry::load_dir(dir)?;
+            tracing::info!("Decoding the logs and calls of {} contracts", abis.len());
+            buffer.set_abis(Arc::new(abis));
+        }
+        tracing::info!(
+            "Using disk buffer for block accumulation with formats {} and codecs {}",
+            config.formats,
+            config.codecs
+        );
 
         let mut task = Task {
             blocks_stream: TrackedStream::new(futures::stream::pending().into_boxed()),
@@ -156,7 +274,12 @@ impl RunnableService for UninitializedTask {
             fetcher_factory,
             buffer,
             processor,
+            output: config.output,
+            postgres,
+            publisher,
+            validate_roots: config.validate_roots,
             base_asset_id,
+            chain_id,
             batch_size: config.batch_size,
             run_iterations: 0,
         };
@@ -171,15 +294,13 @@ impl RunnableTask for Task {
     async fn run(&mut self, watcher: &mut StateWatcher) -> TaskNextAction {
         // Log allocation counters periodically (every 100 iterations)
         self.run_iterations += 1;
-        if self.run_iterations % 100 == 0 {
+        if self.run_iterations.is_multiple_of(100) {
             alloc_counter::log_all();
         }
 
         match self.buffer.len().cmp(&self.batch_size) {
             Ordering::Less => {}
-            Ordering::Equal => {
-                return TaskNextAction::always_continue(self.post_blocks().await)
-            }
+            Ordering::Equal => return self.post_blocks_or_retry().await,
             Ordering::Greater => {
                 tracing::error!(
                     "Batch size exceeded: {} > {}",
@@ -199,7 +320,7 @@ impl RunnableTask for Task {
 
             block = self.blocks_stream.next() => {
                 match block {
-                    Some(Ok(event)) => {
+                    Some(Ok(ingested)) => {
                         // Get the current height, converting from fuel_streams_types::BlockHeight
                         // to fuel_core_types::fuel_types::BlockHeight if needed
                         let current_height: BlockHeight = if let Some(last_height) = self.buffer.last_height() {
@@ -213,11 +334,11 @@ impl RunnableTask for Task {
                             return TaskNextAction::Stop;
                         };
 
-                        if next_height != *event.header.height() {
+                        if next_height != *ingested.block.header.height() {
                             tracing::warn!(
                                 "Received out-of-order block event: expected height {}, got height {}. Reconnecting stream.",
                                 next_height,
-                                event.header.height()
+                                ingested.block.header.height()
                             );
                             match self.reconnect().await {
                                 Ok(_) => return TaskNextAction::Continue,
@@ -229,8 +350,12 @@ impl RunnableTask for Task {
                         }
 
                         // Convert event to block and transactions, then buffer
-                        match self.append_event_to_buffer(&event) {
-                            Ok(_) => TaskNextAction::Continue,
+                        match self.append_event_to_buffer(&ingested).await {
+                            Ok(Appended::Buffered) => TaskNextAction::Continue,
+                            // Flushed like a full batch, so a failed upload doesn't
+                            // stop the service
+                            Ok(Appended::DayRolledOver) => self.post_blocks_or_retry().await,
+                            Ok(Appended::PostgresFailed) => self.retry_from_saved_height().await,
                             Err(e) => {
                                 tracing::error!("Failed to buffer block: {e}");
                                 TaskNextAction::Stop
@@ -267,6 +392,17 @@ impl RunnableTask for Task {
     }
 }
 
+/// Outcome of handing a block to the buffer
+enum Appended {
+    Buffered,
+    /// The block starts a new UTC day and was left out, so the current batch
+    /// must be flushed before the block is fetched again
+    DayRolledOver,
+    /// Writing to Postgres failed, so the blocks after the last saved height
+    /// must be fetched and written again
+    PostgresFailed,
+}
+
 impl Task {
     /// Disconnects the block stream to stop the external library's background fetching.
     ///
@@ -305,11 +441,14 @@ impl Task {
             .blocks_stream_starting_from(next_height)
             .await?
             .map(|result| {
-                result.map(|block: FinalizedBlock| BlockEvent {
-                    header: block.header,
-                    consensus: block.consensus,
-                    transactions: block.transactions,
-                    statuses: block.statuses,
+                result.map(|block: FinalizedBlock| IngestedBlock {
+                    block: BlockEvent {
+                        header: block.header,
+                        consensus: block.consensus,
+                        transactions: block.transactions,
+                        statuses: block.statuses,
+                    },
+                    events: block.events,
                 })
             })
             .into_boxed();
@@ -326,36 +465,106 @@ impl Task {
     /// Use this when recovering from errors or after upload failures.
     async fn reconnect(&mut self) -> anyhow::Result<()> {
         self.buffer.reset()?;
+        if let Some(sink) = &mut self.postgres {
+            sink.reset();
+        }
         self.connect_block_stream().await
     }
 
     /// Converts a block event to domain types and adds to the buffer
-    fn append_event_to_buffer(&mut self, event: &BlockEvent) -> anyhow::Result<()> {
-        let block = Block::new(
-            &event.header,
-            event.consensus.clone(),
-            event.transactions.len(),
-        )?;
+    async fn append_event_to_buffer(
+        &mut self,
+        ingested: &IngestedBlock,
+    ) -> anyhow::Result<Appended> {
+        let (block, transactions) = block_event_to_domain(&ingested.block, &self.base_asset_id)?;
+
+        // With the date-partitioned layout no file may span two UTC days, so the
+        // first block of a new day flushes the current batch instead of joining it.
+        // post_blocks() reconnects from the last saved height, re-fetching this block.
+        if self.processor.layout() == KeyLayout::DatePartitioned
+            && let Some(date) = self.buffer.partition_date()
+            && date != block.header.get_timestamp_utc().date_naive()
+        {
+            return Ok(Appended::DayRolledOver);
+        }
 
-        let transactions: Vec<_> = event
-            .transactions
-            .iter()
-            .zip(event.statuses.iter())
-            .map(|(tx, status)| {
-                Transaction::new(
-                    &status.id.into(),
-                    tx.as_ref(),
-                    &status.into(),
-                    &self.base_asset_id,
-                    status.result.receipts(),
-                )
-            })
-            .collect();
+        // Mismatches are flagged without stopping the export, so the blocks
+        // can be inspected with `validate` once uploaded
+        if self.validate_roots {
+            match roots::validate_export(&block, &transactions) {
+                Ok(report) => {
+                    for mismatch in &report.mismatches {
+                        tracing::error!("Root mismatch in exported rows: {mismatch}");
+                    }
+                }
+                Err(e) => tracing::error!(
+                    "Failed to validate the roots of block {}: {e}",
+                    block.height
+                ),
+            }
+        }
 
-        // Add to disk buffer (writes directly to Avro files)
+        // Add to disk buffer (writes directly to table files). With the Postgres
+        // output alone the buffer still decides where batches end.
         self.buffer.append(&block, &transactions)?;
+        let messages = Message::from_events(block.height, &ingested.events);
+        self.buffer.append_messages(&block, &messages)?;
+
+        // Blocks replayed after a reconnect are dropped by JetStream as
+        // duplicates within the stream's duplicate window. Publishing is a side
+        // output, so a failure only delays the payloads instead of the export.
+        if let Some(publisher) = &mut self.publisher {
+            let payload = MsgPayload::new(
+                block.clone(),
+                transactions.clone(),
+                self.chain_id,
+                self.base_asset_id,
+            );
+            if let Err(e) = publisher.publish(payload).await {
+                tracing::error!(
+                    "Failed to publish to JetStream, {} blocks waiting: {e}",
+                    publisher.unpublished()
+                );
+            }
+        }
 
-        Ok(())
+        if let Some(sink) = &mut self.postgres
+            && let Err(e) = sink.append(block, transactions).await
+        {
+            tracing::error!("Failed to write blocks to Postgres: {e}");
+            return Ok(Appended::PostgresFailed);
+        }
+
+        Ok(Appended::Buffered)
+    }
+
+    /// Posts the buffered batch, fetching it again if any output fails
+    async fn post_blocks_or_retry(&mut self) -> TaskNextAction {
+        match self.post_blocks().await {
+            Ok(()) => TaskNextAction::Continue,
+            Err(e) => {
+                tracing::error!("{e}");
+                self.retry_from_saved_height().await
+            }
+        }
+    }
+
+    /// Waits a moment, then drops the buffered blocks and reconnects the stream
+    /// after the last height saved by every output. Range files are overwritten
+    /// and Postgres rows upserted, so the blocks are simply written again.
+    async fn retry_from_saved_height(&mut self) -> TaskNextAction {
+        tracing::info!(
+            "Retrying from height {} in {RETRY_DELAY:?}",
+            *self.height.borrow()
+        );
+        tokio::time::sleep(RETRY_DELAY).await;
+        match self.reconnect().await {
+            Ok(_) => TaskNextAction::Continue,
+            Err(e) => {
+                tracing::error!("Failed to reconnect block stream: {e}");
+                TaskNextAction::Stop
+            }
+        }
     }
 
     async fn post_blocks(&mut self) -> anyhow::Result<()> {
@@ -379,6 +588,8 @@ impl Task {
         // Convert from fuel_streams_types::BlockHeight to fuel_core_types::fuel_types::BlockHeight
         let last_height_u32: u32 = *finalized.last_height;
         let last_height: BlockHeight = last_height_u32.into();
+        let asset_supplies = finalized.asset_supplies.clone();
+        let predicate_hashes = finalized.predicate_hashes.clone();
 
         // IMPORTANT: Reset buffer immediately after finalize() succeeds.
         // finalize() consumes the internal writers, leaving the buffer in an inconsistent
@@ -387,20 +598,34 @@ impl Task {
         // try to call post_blocks() again, which would fail on finalize() with
         // "blocks_writer already taken" - creating an infinite error loop.
         // By resetting here, the buffer is always in a consistent state. If upload fails,
-        // the service reconnects and re-buffers blocks from the last saved height.
+        // post_blocks_or_retry() reconnects and re-buffers blocks from the last saved height.
         self.buffer.reset()?;
 
-        // Upload the Avro files to storage (no background fetching during this)
-        process_finalized_batch(&self.processor, finalized)
-            .await
-            .map_err(|err| {
-                anyhow::anyhow!(
-                    "Failed to process batch of blocks and transactions: {err}"
-                )
+        if let Some(sink) = &mut self.postgres {
+            sink.flush().await.map_err(|err| {
+                anyhow::anyhow!("Failed to write batch to Postgres: {err}")
             })?;
+        }
 
-        // Only after successful upload do we update the persisted height
-        self.processor.save_latest_height(last_height).await?;
+        if self.output.files() {
+            // Upload the table files to storage (no background fetching during this)
+            process_finalized_batch(&self.processor, finalized)
+                .await
+                .map_err(|err| {
+                    anyhow::anyhow!(
+                        "Failed to process batch of blocks and transactions: {err}"
+                    )
+                })?;
+
+            // Only after successful upload do we update the persisted height.
+            // The supplies go first: blocks at or below their height are never
+            // applied again, so replaying a batch whose height wasn't saved
+            // can't count its mints twice.
+            self.processor.save_asset_supplies(&asset_supplies).await?;
+            self.processor.save_latest_height(last_height).await?;
+        }
+        self.buffer.set_asset_supplies(asset_supplies);
+        self.buffer.set_predicate_hashes(predicate_hashes);
         self.height.send_replace(last_height);
 
         // Reconnect to resume fetching from the new height
@@ -410,33 +635,106 @@ impl Task {
     }
 }
 
-pub fn new_service(config: Config) -> anyhow::Result<ServiceRunner<UninitializedTask>> {
+/// Converts a block event into the domain block and its transactions
+pub fn block_event_to_domain(
+    event: &BlockEvent,
+    base_asset_id: &AssetId,
+) -> anyhow::Result<(Block, Vec<Transaction>)> {
+    let block = Block::new(
+        &event.header,
+        event.consensus.clone(),
+        event.transactions.len(),
+    )?;
+
+    let transactions: Vec<_> = event
+        .transactions
+        .iter()
+        .zip(event.statuses.iter())
+        .map(|(tx, status)| {
+            Transaction::new(&status.id.into(), tx.as_ref(), status, base_asset_id)
+        })
+        .collect();
+
+    Ok((block, transactions))
+}
+
+/// Creates a factory that produces fresh GraphqlFetcher instances.
+/// Each fetcher is created with reduced channel capacities to limit memory usage.
+/// The external library spawns background tasks on each stream creation,
+/// so we need fresh fetchers on reconnection to avoid task/memory accumulation.
+pub fn fetcher_factory(
+    url: &url::Url,
+    blocks_request_batch_size: usize,
+    blocks_request_concurrency: usize,
+    pending_blocks_limit: usize,
+) -> anyhow::Result<FetcherFactory> {
     // Create a shared client that will be reused across all fetcher instances
-    let client = Arc::new(FuelClient::new(&config.url)?);
-
-    // Capture config values for the factory closure
-    let blocks_request_batch_size = config.blocks_request_batch_size;
-    let blocks_request_concurrency = config.blocks_request_concurrency;
-    let pending_blocks_limit = config.pending_blocks;
-
-    // Create a factory that produces fresh GraphqlFetcher instances.
-    // Each fetcher is created with reduced channel capacities to limit memory usage.
-    // The external library spawns background tasks on each stream creation,
-    // so we need fresh fetchers on reconnection to avoid task/memory accumulation.
-    let fetcher_factory: Arc<dyn Fn() -> GraphqlFetcher + Send + Sync> =
-        Arc::new(move || {
-            let graphql_config = GraphqlEventAdapterConfig {
-                client: client.clone(),
-                // The external library creates broadcast channels with this capacity
-                // that persist until background tasks terminate.
-                heartbeat_capacity: NonZeroUsize::new(10_000).expect("Is not zero; qed"),
-                event_capacity: NonZeroUsize::new(10_000).expect("Is not zero; qed"),
-                blocks_request_batch_size,
-                blocks_request_concurrency,
-                pending_blocks_limit,
-            };
-            create_graphql_event_adapter(graphql_config)
-        });
+    let client = Arc::new(FuelClient::new(url)?);
+
+    Ok(Arc::new(move || {
+        let graphql_config = GraphqlEventAdapterConfig {
+            client: client.clone(),
+            // The external library creates broadcast channels with this capacity
+            // that persist until background tasks terminate.
+            heartbeat_capacity: NonZeroUsize::new(10_000).expect("Is not zero; qed"),
+            event_capacity: NonZeroUsize::new(10_000).expect("Is not zero; qed"),
+            blocks_request_batch_size,
+            blocks_request_concurrency,
+            pending_blocks_limit,
+        };
+        create_graphql_event_adapter(graphql_config)
+    }))
+}
+
+/// Fetches the inclusive block range `from..=to` from the node and converts
+/// every block to domain types. Intended for one-off tools, not the live path.
+pub async fn fetch_blocks_range(
+    url: &url::Url,
+    from: BlockHeight,
+    to: BlockHeight,
+) -> anyhow::Result<Vec<(Block, Vec<Transaction>)>> {
+    if from > to {
+        return Err(DuneError::InvalidBlockRange {
+            start: *from,
+            end: *to,
+        }
+        .into());
+    }
+
+    let client = FuelClient::new(url)?;
+    let base_asset_id = *client
+        .chain_info()
+        .await?
+        .consensus_parameters
+        .base_asset_id();
+
+    let factory = fetcher_factory(url, 10, 100, 10_000)?;
+    let fetcher = factory();
+    let count = (*to - *from) as usize + 1;
+    let mut stream = fetcher.blocks_stream_starting_from(from).await?.take(count);
+
+    let mut blocks = Vec::with_capacity(count);
+    while let Some(block) = stream.next().await {
+        let block: FinalizedBlock = block?;
+        let event = BlockEvent {
+            header: block.header,
+            consensus: block.consensus,
+            transactions: block.transactions,
+            statuses: block.statuses,
+        };
+        blocks.push(block_event_to_domain(&event, &base_asset_id)?);
+    }
+
+    Ok(blocks)
+}
+
+pub fn new_service(config: Config) -> anyhow::Result<ServiceRunner<UninitializedTask>> {
+    let fetcher_factory = fetcher_factory(
+        &config.url,
+        config.blocks_request_batch_size,
+        config.blocks_request_concurrency,
+        config.pending_blocks,
+    )?;
 
     let (height, _) = watch::channel(config.starting_height);
     let task = UninitializedTask {
@@ -452,59 +750,58 @@ pub fn new_service(config: Config) -> anyhow::Result<ServiceRunner<Uninitialized
 
 /// Process finalized batch files by uploading to storage.
 /// Uploads sequentially to minimize memory usage - each file is streamed
-/// directly to S3 without loading into memory.
+/// directly to S3 without loading into memory. With Iceberg enabled, each
+/// table file is committed as a snapshot right after its upload.
 pub async fn process_finalized_batch(
     processor: &Processor,
     files: FinalizedBatchFiles,
 ) -> anyhow::Result<()> {
-    let first_height = files.first_height;
-    let last_height = files.last_height;
-
     // Upload sequentially to minimize memory usage
     // Each upload streams from disk to S3 without loading into memory
-    tracing::info!(
-        "Uploading blocks from file: {}",
-        files.blocks_path.display()
-    );
-    processor
-        .process_data_from_file(
-            first_height,
-            last_height,
-            &files.blocks_path,
-            S3TableName::Blocks,
-        )
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to upload blocks: {}", e))?;
+    for (table, file) in files.tables() {
+        with_table_row!(table, |Row| {
+            upload_table_file::<Row>(processor, &files, file).await?
+        });
+    }
 
-    tracing::info!(
-        "Uploading transactions from file: {}",
-        files.transactions_path.display()
-    );
-    processor
-        .process_data_from_file(
-            first_height,
-            last_height,
-            &files.transactions_path,
-            S3TableName::Transactions,
-        )
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to upload transactions: {}", e))?;
+    // FinalizedBatchFiles::drop() will clean up the temp directory
+    Ok(())
+}
 
-    tracing::info!(
-        "Uploading receipts from file: {}",
-        files.receipts_path.display()
-    );
-    processor
+/// Uploads one table file of a finalized batch and commits it to Iceberg
+async fn upload_table_file<T: TableRow>(
+    processor: &Processor,
+    files: &FinalizedBatchFiles,
+    table_file: &TableFile,
+) -> anyhow::Result<()> {
+    let table = T::TABLE;
+    let path = table_file.path.as_path();
+    tracing::info!("Uploading {table} from file: {}", path.display());
+    let stored = processor
         .process_data_from_file(
-            first_height,
-            last_height,
-            &files.receipts_path,
-            S3TableName::Receipts,
+            files.first_height,
+            files.last_height,
+            files.partition_date,
+            path,
+            table,
         )
         .await
-        .map_err(|e| anyhow::anyhow!("Failed to upload receipts: {}", e))?;
+        .map_err(|e| anyhow::anyhow!("Failed to upload {table}: {}", e))?;
+
+    if processor.iceberg() {
+        let file = processor.data_file(
+            &stored,
+            path,
+            table,
+            table_file.rows,
+            files.partition_date,
+        )?;
+        processor
+            .commit_iceberg::<T>(table, file, files.last_height)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to commit {table} snapshot: {}", e))?;
+    }
 
-    // FinalizedBatchFiles::drop() will clean up the temp directory
     Ok(())
 }
 
@@ -545,7 +842,15 @@ pub async fn process_batch(
 
 #[cfg(test)]
 mod tests {
-    use crate::processor::StorageTypeConfig;
+    use crate::{
+        helpers::{
+            TableCodecs,
+            TableFormats,
+        },
+        postgres::OutputMode,
+        processor::StorageTypeConfig,
+        s3::KeyLayout,
+    };
     use fuel_core::service::{
         Config,
         FuelService,
@@ -567,6 +872,15 @@ mod tests {
             blocks_request_batch_size: 10,
             blocks_request_concurrency: 100,
             pending_blocks: 10_000,
+            codecs: TableCodecs::default(),
+            formats: TableFormats::default(),
+            key_layout: KeyLayout::Flat,
+            iceberg: false,
+            output: OutputMode::Files,
+            postgres: None,
+            nats_url: None,
+            validate_roots: false,
+            abi_dir: None,
         };
 
         // Given
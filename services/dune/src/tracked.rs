@@ -7,11 +7,13 @@ use std::{
 };
 
 use fuel_core_services::stream::BoxStream;
-use fuel_indexer_types::events::BlockEvent;
 use fuel_receipts_manager::adapters::graphql_event_adapter::GraphqlFetcher;
 use futures::Stream;
 
-use crate::alloc_counter;
+use crate::{
+    alloc_counter,
+    service::IngestedBlock,
+};
 
 // ---------------------------------------------------------------------------
 // TrackedStream
@@ -20,18 +22,18 @@ use crate::alloc_counter;
 /// A `BoxStream` wrapper whose `Drop` proves the stream was actually
 /// deallocated, not just replaced.
 pub struct TrackedStream {
-    inner: BoxStream<anyhow::Result<BlockEvent>>,
+    inner: BoxStream<anyhow::Result<IngestedBlock>>,
 }
 
 impl TrackedStream {
-    pub fn new(inner: BoxStream<anyhow::Result<BlockEvent>>) -> Self {
+    pub fn new(inner: BoxStream<anyhow::Result<IngestedBlock>>) -> Self {
         alloc_counter::inc(&alloc_counter::BLOCK_STREAM);
         Self { inner }
     }
 }
 
 impl Stream for TrackedStream {
-    type Item = anyhow::Result<BlockEvent>;
+    type Item = anyhow::Result<IngestedBlock>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         Pin::new(&mut self.inner).poll_next(cx)
@@ -0,0 +1,1041 @@
+//! Audits exported range files against the node they were exported from.
+//!
+//! [`Verifier`] re-fetches a block range from a fuel-core node, rebuilds the
+//! rows the exporter would have written for it and compares them field by
+//! field with the rows read back through the [`DataLakeReader`]. Rows are
+//! matched by block height, transaction id and, for receipts, their position
+//! within the transaction. Only Avro range files are read back.
+
+use std::{
+    any::Any,
+    collections::{
+        BTreeMap,
+        btree_map::Entry,
+    },
+    fmt::{
+        self,
+        Display,
+    },
+    sync::Arc,
+    time::Duration,
+};
+
+use apache_avro::types::Value;
+use fuel_streams_domains::{
+    assets::AssetEvent,
+    blocks::Block,
+    predicates::Predicate,
+    transactions::Transaction,
+};
+use fuel_web_utils::shutdown::ShutdownController;
+use futures::TryStreamExt;
+use rand::Rng;
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+    DuneError,
+    DuneResult,
+    VerifyArgs,
+    compact::RangeObject,
+    data_lake::DataLakeReader,
+    helpers::AvroParserError,
+    processor::{
+        Processor,
+        StorageTypeConfig,
+    },
+    s3::{
+        FuelNetwork,
+        S3Storage,
+        S3StorageOpts,
+        S3TableName,
+        Storage,
+        StorageConfig,
+        TableRow,
+        with_table_row,
+    },
+    schemas::{
+        AvroAssetEvent,
+        AvroAssetSupply,
+        AvroBlock,
+        AvroBlockStats,
+        AvroContract,
+        AvroDecodedCall,
+        AvroDecodedLog,
+        AvroInput,
+        AvroMessage,
+        AvroOutput,
+        AvroPredicate,
+        AvroPredicateBytecode,
+        AvroReceipt,
+        AvroRow,
+        AvroTransaction,
+        AvroTransfer,
+        AvroUtxo,
+    },
+    service::fetch_blocks_range,
+};
+
+/// Default number of blocks fetched from the node and compared at once
+pub const DEFAULT_CHUNK_SIZE: u32 = 100;
+
+/// Rows the exporter writes for a set of blocks, for every table that can be
+/// rebuilt from the node alone.
+///
+/// Messages come from executor events, which the node is not queried for
+/// here. Predicate bytecodes are deduplicated per upload batch, so which block
+/// carries a bytecode depends on where the batch started. Asset supplies are
+/// carried over from batches before the range, and which logs and calls are
+/// decoded depends on the ABIs the exporter loaded.
+#[derive(Debug, Default)]
+pub struct TableRows {
+    tables: BTreeMap<S3TableName, Box<dyn Any + Send + Sync>>,
+}
+
+impl TableRows {
+    /// Builds the rows the exporter writes for the given blocks
+    pub fn new(blocks_and_txs: &[(Block, Vec<Transaction>)]) -> Self {
+        let blocks = blocks_and_txs
+            .iter()
+            .map(|(block, _)| AvroBlock::new(block))
+            .collect();
+        let block_stats = blocks_and_txs
+            .iter()
+            .map(|(block, txs)| AvroBlockStats::new(block, txs))
+            .collect();
+        let transactions = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter().map(move |tx| AvroTransaction::from((block, tx)))
+            })
+            .collect();
+        let receipts = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter().flat_map(move |tx| {
+                    tx.receipts
+                        .iter()
+                        .map(move |receipt| AvroReceipt::from((block, tx, receipt)))
+                })
+            })
+            .collect();
+        let inputs = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter()
+                    .enumerate()
+                    .flat_map(move |(index, tx)| AvroInput::rows(block, index, tx))
+            })
+            .collect();
+        let outputs = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter()
+                    .enumerate()
+                    .flat_map(move |(index, tx)| AvroOutput::rows(block, index, tx))
+            })
+            .collect();
+        let utxos = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter()
+                    .enumerate()
+                    .flat_map(move |(index, tx)| AvroUtxo::rows(block, index, tx))
+            })
+            .collect();
+        let predicates = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter().enumerate().flat_map(move |(index, tx)| {
+                    let predicates = Predicate::from_transaction(index as i32, tx);
+                    AvroPredicate::rows(block, &predicates)
+                })
+            })
+            .collect();
+        let contracts = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter()
+                    .enumerate()
+                    .flat_map(move |(index, tx)| AvroContract::rows(block, index, tx))
+            })
+            .collect();
+        let assets = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter().enumerate().flat_map(move |(index, tx)| {
+                    let events = AssetEvent::from_transaction(index as i32, tx);
+                    AvroAssetEvent::rows(block, &events)
+                })
+            })
+            .collect();
+        let transfers = blocks_and_txs
+            .iter()
+            .flat_map(|(block, txs)| {
+                txs.iter()
+                    .enumerate()
+                    .flat_map(move |(index, tx)| AvroTransfer::rows(block, index, tx))
+            })
+            .collect();
+
+        let mut rows = Self::default();
+        rows.insert::<AvroBlock>(blocks);
+        rows.insert::<AvroBlockStats>(block_stats);
+        rows.insert::<AvroTransaction>(transactions);
+        rows.insert::<AvroReceipt>(receipts);
+        rows.insert::<AvroInput>(inputs);
+        rows.insert::<AvroOutput>(outputs);
+        rows.insert::<AvroUtxo>(utxos);
+        rows.insert::<AvroPredicate>(predicates);
+        rows.insert::<AvroContract>(contracts);
+        rows.insert::<AvroAssetEvent>(assets);
+        rows.insert::<AvroTransfer>(transfers);
+        rows
+    }
+
+    fn insert<T: TableRow>(&mut self, rows: Vec<T>) {
+        self.tables.insert(T::TABLE, Box::new(rows));
+    }
+
+    /// Rows of the table of `T`, if it can be rebuilt
+    pub fn get<T: TableRow>(&self) -> Option<&[T]> {
+        self.tables
+            .get(&T::TABLE)?
+            .downcast_ref::<Vec<T>>()
+            .map(Vec::as_slice)
+    }
+
+    /// Tables that can be rebuilt, in [`S3TableName`] order
+    pub fn tables(&self) -> impl Iterator<Item = S3TableName> + '_ {
+        self.tables.keys().copied()
+    }
+
+    pub fn contains(&self, table: S3TableName) -> bool {
+        self.tables.contains_key(&table)
+    }
+}
+
+/// Identifies a row across the expected and the stored copy of a table.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RowKey {
+    pub height: i64,
+    pub tx_id: Option<String>,
+    /// Position among the rows sharing the height and transaction id
+    pub index: Option<usize>,
+}
+
+impl Display for RowKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "height {}", self.height)?;
+        if let Some(tx_id) = &self.tx_id {
+            write!(f, " tx {tx_id}")?;
+        }
+        if let Some(index) = self.index {
+            write!(f, " #{index}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How the rows of a table are matched when compared against the node.
+pub trait VerifyRow: TableRow {
+    /// Whether several rows share a key and are told apart by their position
+    const POSITIONAL: bool = false;
+
+    /// Key of the row, without its position
+    fn key(&self) -> RowKey;
+}
+
+impl VerifyRow for AvroBlock {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: None,
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroBlockStats {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: None,
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroTransaction {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.id.as_ref().map(|id| hex(&id.0)),
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroReceipt {
+    const POSITIONAL: bool = true;
+
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroInput {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: self.input_index.map(|index| index as usize),
+        }
+    }
+}
+
+impl VerifyRow for AvroOutput {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: self.output_index.map(|index| index as usize),
+        }
+    }
+}
+
+impl VerifyRow for AvroPredicate {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: self.input_index.map(|index| index as usize),
+        }
+    }
+}
+
+impl VerifyRow for AvroContract {
+    const POSITIONAL: bool = true;
+
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroAssetEvent {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: self.receipt_index.map(|index| index as usize),
+        }
+    }
+}
+
+impl VerifyRow for AvroTransfer {
+    const POSITIONAL: bool = true;
+
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroUtxo {
+    const POSITIONAL: bool = true;
+
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroMessage {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: None,
+            index: self.message_index.map(|index| index as usize),
+        }
+    }
+}
+
+impl VerifyRow for AvroPredicateBytecode {
+    const POSITIONAL: bool = true;
+
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: None,
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroAssetSupply {
+    const POSITIONAL: bool = true;
+
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: None,
+            index: None,
+        }
+    }
+}
+
+impl VerifyRow for AvroDecodedLog {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: self.receipt_index.map(|index| index as usize),
+        }
+    }
+}
+
+impl VerifyRow for AvroDecodedCall {
+    fn key(&self) -> RowKey {
+        RowKey {
+            height: self.block_height().unwrap_or_default(),
+            tx_id: self.transaction_id.as_ref().map(|id| hex(&id.0)),
+            index: self.receipt_index.map(|index| index as usize),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscrepancyKind {
+    /// The node has the row but storage does not
+    Missing,
+    /// Storage has a row the node does not, or a duplicate of one
+    Extra,
+    /// Both have the row but a field differs
+    Mismatch {
+        field: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// A difference between the stored rows and the rows rebuilt from the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub table: S3TableName,
+    pub key: RowKey,
+    pub kind: DiscrepancyKind,
+}
+
+impl Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { table, key, kind } = self;
+        match kind {
+            DiscrepancyKind::Missing => write!(f, "{table} {key}: missing"),
+            DiscrepancyKind::Extra => write!(f, "{table} {key}: extra row"),
+            DiscrepancyKind::Mismatch {
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{table} {key}: {field} expected {expected}, found {actual}"
+            ),
+        }
+    }
+}
+
+/// Outcome of verifying one or more block ranges.
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    /// Blocks fetched from the node
+    pub blocks: usize,
+    /// Expected rows compared across all tables
+    pub rows: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl VerifyReport {
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    pub fn merge(&mut self, other: VerifyReport) {
+        self.blocks += other.blocks;
+        self.rows += other.rows;
+        self.discrepancies.extend(other.discrepancies);
+    }
+}
+
+impl Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Verified {} blocks and {} rows: {} discrepancies",
+            self.blocks,
+            self.rows,
+            self.discrepancies.len()
+        )
+    }
+}
+
+/// Compares the rows of one table. Stored rows are matched to the expected
+/// ones by key, so only the order of the receipts within a transaction
+/// matters.
+pub fn diff_rows<T: VerifyRow>(
+    table: S3TableName,
+    expected: &[T],
+    actual: &[T],
+) -> DuneResult<Vec<Discrepancy>> {
+    let discrepancy = |key: RowKey, kind| Discrepancy { table, key, kind };
+
+    let (expected, _) = keyed(expected);
+    let (mut actual, duplicates) = keyed(actual);
+
+    let mut discrepancies = Vec::new();
+    for (key, row) in expected {
+        let Some(stored) = actual.remove(&key) else {
+            discrepancies.push(discrepancy(key, DiscrepancyKind::Missing));
+            continue;
+        };
+        let expected = fields(row)?;
+        let mut stored = fields(stored)?;
+        for (field, expected) in expected {
+            let actual = stored
+                .remove(&field)
+                .unwrap_or_else(|| "absent".to_string());
+            if actual != expected {
+                discrepancies.push(discrepancy(
+                    key.clone(),
+                    DiscrepancyKind::Mismatch {
+                        field,
+                        expected,
+                        actual,
+                    },
+                ));
+            }
+        }
+        for (field, actual) in stored {
+            discrepancies.push(discrepancy(
+                key.clone(),
+                DiscrepancyKind::Mismatch {
+                    field,
+                    expected: "absent".to_string(),
+                    actual,
+                },
+            ));
+        }
+    }
+    discrepancies.extend(
+        actual
+            .into_keys()
+            .chain(duplicates)
+            .map(|key| discrepancy(key, DiscrepancyKind::Extra)),
+    );
+
+    Ok(discrepancies)
+}
+
+/// Indexes rows by key, returning the keys of the rows that repeat one
+fn keyed<T: VerifyRow>(rows: &[T]) -> (BTreeMap<RowKey, &T>, Vec<RowKey>) {
+    let mut positions = BTreeMap::<RowKey, usize>::new();
+    let mut keyed = BTreeMap::new();
+    let mut duplicates = Vec::new();
+    for row in rows {
+        let mut key = row.key();
+        if T::POSITIONAL {
+            let position = positions.entry(key.clone()).or_default();
+            key.index = Some(*position);
+            *position += 1;
+        }
+        match keyed.entry(key) {
+            Entry::Occupied(entry) => duplicates.push(entry.key().clone()),
+            Entry::Vacant(entry) => {
+                entry.insert(row);
+            }
+        }
+    }
+    (keyed, duplicates)
+}
+
+/// Flattens a row into its leaf fields, keyed by their dotted path
+fn fields<T: Serialize>(row: &T) -> DuneResult<BTreeMap<String, String>> {
+    let value = apache_avro::to_value(row).map_err(AvroParserError::from)?;
+    let mut fields = BTreeMap::new();
+    flatten(&value, &mut String::new(), &mut fields);
+    Ok(fields)
+}
+
+fn flatten(value: &Value, path: &mut String, fields: &mut BTreeMap<String, String>) {
+    let len = path.len();
+    match value {
+        Value::Record(entries) => {
+            for (name, value) in entries {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(name);
+                flatten(value, path, fields);
+                path.truncate(len);
+            }
+        }
+        Value::Union(_, value) => flatten(value, path, fields),
+        Value::Array(items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                path.push_str(&format!("[{i}]"));
+                flatten(item, path, fields);
+                path.truncate(len);
+            }
+        }
+        value => {
+            fields.insert(path.clone(), render(value));
+        }
+    }
+}
+
+fn render(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Boolean(value) => value.to_string(),
+        Value::Int(value) => value.to_string(),
+        Value::Long(value) => value.to_string(),
+        Value::String(value) | Value::Enum(_, value) => format!("{value:?}"),
+        Value::Bytes(bytes) | Value::Fixed(_, bytes) => hex(bytes),
+        Value::Array(_) => "[]".to_string(),
+        value => format!("{value:?}"),
+    }
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{hex}")
+}
+
+/// Picks `samples` random windows of `size` blocks within `from..=to`,
+/// merging the ones that overlap. The whole range is returned when it is no
+/// larger than a single window.
+pub fn sample_windows(
+    from: u32,
+    to: u32,
+    samples: usize,
+    size: u32,
+    rng: &mut impl Rng,
+) -> Vec<(u32, u32)> {
+    if from > to {
+        return Vec::new();
+    }
+    let size = size.max(1);
+    if to - from < size {
+        return vec![(from, to)];
+    }
+
+    let mut windows: Vec<_> = (0..samples)
+        .map(|_| {
+            let start = rng.random_range(from..=to - size + 1);
+            (start, start + size - 1)
+        })
+        .collect();
+    windows.sort();
+
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(windows.len());
+    for (start, end) in windows {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Compares stored range files with the blocks of a fuel-core node.
+#[derive(Debug)]
+pub struct Verifier<S> {
+    reader: DataLakeReader<S>,
+    url: Url,
+    tables: Vec<S3TableName>,
+    chunk_size: u32,
+}
+
+impl<S: Storage> Verifier<S> {
+    pub fn new(reader: DataLakeReader<S>, url: Url) -> Self {
+        Self {
+            reader,
+            url,
+            tables: TableRows::new(&[]).tables().collect(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Tables to compare
+    pub fn with_tables(mut self, tables: Vec<S3TableName>) -> Self {
+        self.tables = tables;
+        self
+    }
+
+    /// Number of blocks fetched from the node and held in memory at once
+    pub fn with_chunk_size(mut self, chunk_size: u32) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Verifies every block of `from..=to`. The range must already be
+    /// produced by the node.
+    pub async fn verify_range(&self, from: u32, to: u32) -> DuneResult<VerifyReport> {
+        if from > to {
+            return Err(DuneError::InvalidBlockRange {
+                start: from,
+                end: to,
+            });
+        }
+
+        // List each table once and pick the files of every chunk from it
+        let verifiable = TableRows::new(&[]);
+        let mut tables = Vec::with_capacity(self.tables.len());
+        for &table in &self.tables {
+            if table == S3TableName::Metadata {
+                return Err(
+                    anyhow::anyhow!("The metadata table has no range files").into()
+                );
+            }
+            // See `TableRows` for why a table can't be rebuilt from the node
+            if !verifiable.contains(table) {
+                return Err(anyhow::anyhow!("The {table} table can't be verified").into());
+            }
+            let objects = self.reader.discover(table, from.into(), to.into()).await?;
+            tables.push((table, objects));
+        }
+
+        let mut report = VerifyReport::default();
+        let mut start = from;
+        loop {
+            let end = start.saturating_add(self.chunk_size - 1).min(to);
+            let blocks_and_txs =
+                fetch_blocks_range(&self.url, start.into(), end.into()).await?;
+            let rows = TableRows::new(&blocks_and_txs);
+            report.blocks += blocks_and_txs.len();
+
+            for (table, objects) in &tables {
+                let objects: Vec<_> = objects
+                    .iter()
+                    .filter(|object| *object.start <= end && *object.end >= start)
+                    .cloned()
+                    .collect();
+                let (rows, discrepancies) = with_table_row!(*table, |Row| {
+                    self.diff_table::<Row>(&rows, objects, start, end).await?
+                })
+                .ok_or_else(|| {
+                    anyhow::anyhow!("The metadata table has no range files")
+                })?;
+                report.rows += rows;
+                report.discrepancies.extend(discrepancies);
+            }
+
+            if end == to {
+                break;
+            }
+            start = end + 1;
+        }
+
+        Ok(report)
+    }
+
+    async fn diff_table<T: VerifyRow>(
+        &self,
+        rows: &TableRows,
+        objects: Vec<RangeObject>,
+        from: u32,
+        to: u32,
+    ) -> DuneResult<(usize, Vec<Discrepancy>)> {
+        let table = T::TABLE;
+        let expected = rows.get::<T>().ok_or_else(|| {
+            DuneError::Other(anyhow::anyhow!("The {table} table can't be verified"))
+        })?;
+        let actual: Vec<T> = self
+            .reader
+            .read_rows(objects, from.into(), to.into())
+            .try_collect()
+            .await?;
+        Ok((expected.len(), diff_rows(table, expected, &actual)?))
+    }
+}
+
+/// Height of the last block committed by the exporter
+async fn committed_height() -> DuneResult<Option<u32>> {
+    let processor = Processor::new(StorageTypeConfig::S3).await?;
+    Ok(processor.load_latest_height().await?.map(|height| *height))
+}
+
+/// Entry point of the `verify` command
+pub async fn run(args: VerifyArgs) -> anyhow::Result<()> {
+    let storage = Arc::new(S3Storage::new(S3StorageOpts::admin_opts()).await?);
+    let network = FuelNetwork::load_from_env();
+    let mut verifier =
+        Verifier::new(DataLakeReader::new(storage, network), args.url.clone())
+            .with_chunk_size(args.chunk_size);
+    if !args.tables.is_empty() {
+        verifier = verifier.with_tables(args.tables.clone());
+    }
+
+    if args.continuous {
+        return audit(&verifier, &args).await;
+    }
+
+    let to = match args.to {
+        Some(to) => to,
+        None => committed_height()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No committed height found, pass --to"))?,
+    };
+    let windows = match args.samples {
+        Some(samples) => {
+            sample_windows(args.from, to, samples, args.sample_size, &mut rand::rng())
+        }
+        None => vec![(args.from, to)],
+    };
+
+    let mut report = VerifyReport::default();
+    for (from, to) in windows {
+        tracing::info!("Verifying blocks {from} to {to}");
+        report.merge(verifier.verify_range(from, to).await?);
+    }
+
+    for discrepancy in &report.discrepancies {
+        println!("{discrepancy}");
+    }
+    println!("{report}");
+
+    if !report.is_clean() {
+        anyhow::bail!("Found {} discrepancies", report.discrepancies.len());
+    }
+    Ok(())
+}
+
+/// Verifies random windows of the committed range every interval until a
+/// shutdown signal is received. Failed rounds are logged and retried on the
+/// next interval.
+async fn audit<S: Storage>(
+    verifier: &Verifier<S>,
+    args: &VerifyArgs,
+) -> anyhow::Result<()> {
+    let shutdown = Arc::new(ShutdownController::new()).spawn_signal_handler();
+    let interval = Duration::from_secs(args.interval_secs);
+    let samples = args.samples.unwrap_or(1);
+
+    while !shutdown.is_shutdown_initiated() {
+        let to = match args.to {
+            Some(to) => Ok(Some(to)),
+            None => committed_height().await,
+        };
+        match to {
+            Ok(Some(to)) if to >= args.from => {
+                let windows = sample_windows(
+                    args.from,
+                    to,
+                    samples,
+                    args.sample_size,
+                    &mut rand::rng(),
+                );
+                for (from, to) in windows {
+                    match verifier.verify_range(from, to).await {
+                        Ok(report) if report.is_clean() => {
+                            tracing::info!("Audit of blocks {from} to {to}: {report}");
+                        }
+                        Ok(report) => {
+                            for discrepancy in &report.discrepancies {
+                                tracing::error!("{discrepancy}");
+                            }
+                            tracing::error!("Audit of blocks {from} to {to}: {report}");
+                        }
+                        Err(e) => {
+                            tracing::error!("Audit of blocks {from} to {to} failed: {e}");
+                        }
+                    }
+                }
+            }
+            Ok(_) => tracing::info!("No committed blocks to audit yet"),
+            Err(e) => tracing::error!("Failed to load the committed height: {e}"),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = shutdown.wait_for_shutdown() => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_streams_domains::mocks::{
+        MockBlock,
+        MockReceipt,
+        MockTransaction,
+    };
+    use pretty_assertions::assert_eq;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng,
+    };
+
+    use super::*;
+
+    fn test_rows() -> TableRows {
+        let blocks_and_txs: Vec<_> = (1..=3u32)
+            .map(|height| {
+                let block = MockBlock::build(height.into());
+                let txs = vec![
+                    MockTransaction::script(vec![], vec![], MockReceipt::all()),
+                    MockTransaction::script(vec![], vec![], MockReceipt::all()),
+                ];
+                (block, txs)
+            })
+            .collect();
+        TableRows::new(&blocks_and_txs)
+    }
+
+    #[test]
+    fn test_identical_rows_are_clean() -> DuneResult<()> {
+        let rows = test_rows();
+        let blocks = rows.get::<AvroBlock>().unwrap();
+        let transactions = rows.get::<AvroTransaction>().unwrap();
+        let receipts = rows.get::<AvroReceipt>().unwrap();
+        let mut shuffled = receipts.to_vec();
+        shuffled.reverse();
+
+        assert!(diff_rows(S3TableName::Blocks, blocks, blocks)?.is_empty());
+        assert!(
+            diff_rows(S3TableName::Transactions, transactions, transactions)?.is_empty()
+        );
+        assert!(diff_rows(S3TableName::Receipts, receipts, receipts)?.is_empty());
+        // Receipts of different transactions may be stored in any order, but
+        // not the receipts of a single transaction
+        assert!(!diff_rows(S3TableName::Receipts, receipts, &shuffled)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_and_extra_rows() -> DuneResult<()> {
+        let rows = test_rows();
+        let blocks = rows.get::<AvroBlock>().unwrap();
+        let mut stored = blocks.to_vec();
+        let missing = stored.remove(1);
+        stored.push(stored[0].clone());
+        let mut extra = stored[0].clone();
+        extra.height = Some(4);
+        stored.push(extra);
+
+        let discrepancies = diff_rows(S3TableName::Blocks, blocks, &stored)?;
+        let key = |height| RowKey {
+            height,
+            tx_id: None,
+            index: None,
+        };
+        assert_eq!(
+            discrepancies,
+            vec![
+                Discrepancy {
+                    table: S3TableName::Blocks,
+                    key: key(missing.height.unwrap()),
+                    kind: DiscrepancyKind::Missing,
+                },
+                Discrepancy {
+                    table: S3TableName::Blocks,
+                    key: key(4),
+                    kind: DiscrepancyKind::Extra,
+                },
+                Discrepancy {
+                    table: S3TableName::Blocks,
+                    key: key(1),
+                    kind: DiscrepancyKind::Extra,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_mismatched_fields() -> DuneResult<()> {
+        let rows = test_rows();
+        let receipts = rows.get::<AvroReceipt>().unwrap();
+        let index = receipts.len() - 1;
+        let mut stored = receipts.to_vec();
+        stored[index].amount = Some(-1);
+
+        let expected = receipts[index]
+            .amount
+            .map(|a| a.to_string())
+            .unwrap_or("null".to_string());
+        assert_eq!(
+            diff_rows(S3TableName::Receipts, receipts, &stored)?,
+            vec![Discrepancy {
+                table: S3TableName::Receipts,
+                key: RowKey {
+                    height: 3,
+                    tx_id: receipts[index].transaction_id.as_ref().map(|id| hex(&id.0)),
+                    index: Some(MockReceipt::all().len() - 1),
+                },
+                kind: DiscrepancyKind::Mismatch {
+                    field: "amount".to_string(),
+                    expected,
+                    actual: "-1".to_string(),
+                },
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_rows_skip_tables_not_rebuilt() {
+        let rows = test_rows();
+        assert_eq!(rows.tables().count(), 11);
+        assert_eq!(rows.get::<AvroBlock>().map(<[_]>::len), Some(3));
+        assert!(rows.contains(S3TableName::BlockStats));
+        assert!(rows.get::<AvroMessage>().is_none());
+        assert!(!rows.contains(S3TableName::AssetSupplies));
+        assert!(!rows.contains(S3TableName::Metadata));
+    }
+
+    #[test]
+    fn test_nested_fields_are_flattened() -> DuneResult<()> {
+        let rows = test_rows();
+        let fields = fields(&rows.get::<AvroTransaction>().unwrap()[0])?;
+        assert_eq!(fields.get("blockHeight").map(String::as_str), Some("1"));
+        assert!(fields.keys().any(|field| field.starts_with("txPointer.")));
+        assert!(fields.values().any(|value| value.starts_with("0x")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample_windows() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        assert_eq!(sample_windows(5, 10, 3, 100, &mut rng), vec![(5, 10)]);
+        assert!(sample_windows(10, 5, 3, 100, &mut rng).is_empty());
+
+        for _ in 0..100 {
+            let windows = sample_windows(1, 10_000, 8, 25, &mut rng);
+            assert!(!windows.is_empty());
+            for (i, &(start, end)) in windows.iter().enumerate() {
+                assert!(start >= 1 && end <= 10_000 && start <= end);
+                assert!(end - start + 1 >= 25);
+                if i > 0 {
+                    assert!(start > windows[i - 1].1 + 1);
+                }
+            }
+        }
+    }
+}